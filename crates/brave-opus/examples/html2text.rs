@@ -7,9 +7,11 @@ use anthropic::{
 };
 use brave::{
     apis::{web_search::Api, WebSearchParams},
+    cache::Cache,
+    documents::{Document, ToDocuments},
     Brave,
 };
-use color_eyre::eyre::OptionExt;
+use brave_opus::rerank::{Reranker, DEFAULT_TOP_K};
 use futures::stream::TryStreamExt;
 use std::fmt::Debug;
 use std::io::Write;
@@ -30,14 +32,15 @@ async fn main() -> color_eyre::eyre::Result<()> {
     let semaphore = Arc::new(Semaphore::new(3));
     let brave_semaphore = Arc::new(Semaphore::new(1));
 
-    let key = std::env::var("BRAVE_SUBSCRIPTION_TOKEN")?;
+    let auth = brave::Auth::from_env().map_err(color_eyre::eyre::Error::msg)?;
 
-    let brave_client = Brave::new(brave::Auth::new(&key), "https://api.search.brave.com/res/v1");
+    // Repeated queries are served from `BRAVE_CACHE_DIR`, if set.
+    let brave_client =
+        Brave::new(auth, "https://api.search.brave.com/res/v1").cache(Cache::from_env());
 
-    let key = std::env::var("ANTHROPIC_API_KEY")?;
+    let auth = anthropic::Auth::from_env().map_err(color_eyre::eyre::Error::msg)?;
 
-    let anthropic_client =
-        Anthropic::new(anthropic::Auth::new(&key), "https://api.anthropic.com/v1/");
+    let anthropic_client = Anthropic::new(auth, "https://api.anthropic.com/v1/");
 
     let messages = vec![
                 Message {
@@ -74,16 +77,16 @@ RETURN JUST THREE UPDATED PROMPTS SEPARATED BY A SINGLE NEW LINE WITHOUT QUOTES
             let count = *count;
             tokio::spawn(async move {
                 let _permit = sem.acquire().await.unwrap();
-                let mut params = WebSearchParams::new(&search_prompt);
-                params.count = Some(count);
-                params.country = Some("ALL".to_string());
+                let params = WebSearchParams::builder(&search_prompt)
+                    .count(count)
+                    .country(brave::CountryCode::all())
+                    .build()
+                    .unwrap();
 
-                let mut response: brave::WebSearchApiResponse =
+                let response: brave::WebSearchApiResponse =
                     brave_client.search(&params, None).unwrap();
 
-                let web = response.web.take().ok_or_eyre("can't find `web` in response").unwrap();
-
-                web.results.ok_or_eyre("can't find `results` in `web`").unwrap()
+                (response.to_documents(), response.web_results().to_vec())
             })
         })
         .collect();
@@ -92,21 +95,35 @@ RETURN JUST THREE UPDATED PROMPTS SEPARATED BY A SINGLE NEW LINE WITHOUT QUOTES
     let nested_results = futures::future::join_all(tasks).await;
 
     // Flatten Vec<Vec<Something>> into Vec<Something>
-    let all_results: Vec<brave::Result> =
-        nested_results.into_iter().flat_map(|r| r.unwrap()).collect();
+    let (documents, all_results): (Vec<_>, Vec<_>) =
+        nested_results.into_iter().map(|r| r.unwrap()).unzip();
+    let documents: Vec<Document> = documents.into_iter().flatten().collect();
+    let all_results: Vec<brave::Result> = all_results.into_iter().flatten().collect();
+
+    // Only the pages of the results most relevant to the prompt are fetched.
+    let rerank = config::Config::load(None)?.profile(None)?.rerank;
+    let top_k = rerank.top_k.unwrap_or(DEFAULT_TOP_K);
+    let all_results = Reranker::from_config(&rerank).top_k(prompt, all_results, top_k);
+
+    let snippets = documents
+        .iter()
+        .map(|d| format!("## {}\n\n{}", d.title, d.text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
 
     println!("\n# Context\n");
 
     let tasks: Vec<_> = all_results.iter().map(|result| {
         let sem = semaphore.clone();
         let agent = ureq::AgentBuilder::new().build();
+        let cassette = brave_client.cassette.clone();
         let url = result.url.clone().unwrap_or_default();
         let anthropic_client = anthropic_client.clone();
         tokio::spawn(async move {
             let _permit = sem.acquire().await.unwrap();
 
-            let response = agent.get(&url).call().unwrap();
-            let tree = html2text::parse(response.into_reader()).unwrap();
+            let html = fetch(&agent, cassette.as_ref(), &url).unwrap();
+            let tree = html2text::parse(html.as_bytes()).unwrap();
             let text = tree.render(200, Decorator::new()).unwrap().into_string().unwrap();
 
             let messages = vec![
@@ -151,7 +168,12 @@ RETURN ONLY THE RELEVANT TEXT WITHOUT ANY FURTHER COMMENTS!!!\n\n```\n{}\n```",
 
     let messages = vec![Message {
         role: Role::User,
-        content: format!("Context:\n\n ```{}```\n\nPrompt: {}", results.join("\n\n"), prompt),
+        content: format!(
+            "Snippets:\n\n```{}```\n\nContext:\n\n ```{}```\n\nPrompt: {}",
+            snippets,
+            results.join("\n\n"),
+            prompt
+        ),
     }];
 
     let body = MessageBody::with_stream("claude-3-opus-20240229", messages, 4096);
@@ -169,6 +191,25 @@ RETURN ONLY THE RELEVANT TEXT WITHOUT ANY FURTHER COMMENTS!!!\n\n```\n{}\n```",
     Ok(())
 }
 
+/// Downloads the page, going through the cassette like the API clients do.
+fn fetch(
+    agent: &ureq::Agent,
+    cassette: Option<&cassette::Cassette>,
+    url: &str,
+) -> color_eyre::eyre::Result<String> {
+    let request = cassette::Request::new("GET", url, None);
+    if let Some(cassette) = cassette.filter(|cassette| cassette.is_replay()) {
+        return Ok(cassette.load(&request)?.body);
+    }
+
+    let html = agent.get(url).call()?.into_string()?;
+    if let Some(cassette) = cassette {
+        cassette.save(&request, &cassette::Response::new(200, &html))?;
+    }
+
+    Ok(html)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 struct SimpleAnnotation;
 
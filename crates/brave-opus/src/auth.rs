@@ -0,0 +1,247 @@
+//! Stores the API keys of the profiles in the system keyring, or an encrypted file, and finds
+//! the key of each API.
+
+use std::cell::OnceCell;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::Result;
+
+use anthropic::messages::{MessageApi as _, MessageBody};
+use anthropic::{Anthropic, Message, Role};
+use brave::{plan::web_search_plan, subscription::Subscription, Brave};
+use config::credentials::{read_token, Credential, Store};
+
+/// Model used to check the Anthropic key when the profile doesn't set one.
+const CHECK_MODEL: &str = "claude-3-haiku-20240307";
+
+#[derive(Debug, Parser)]
+#[command(name = "auth")]
+#[command(about = "Manage the stored API keys of the profile")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Store the API keys of the profile.
+    ///
+    /// Keys are asked for on the terminal, or read from stdin one per line. Leave a key empty to
+    /// keep the stored one.
+    Login {
+        /// Only store this key.
+        #[clap(long, value_enum)]
+        key: Option<Key>,
+    },
+    /// Remove the API keys of the profile.
+    Logout {
+        /// Only remove this key.
+        #[clap(long, value_enum)]
+        key: Option<Key>,
+    },
+    /// Check the API keys of the profile, and show what the Brave plan unlocks.
+    Status,
+}
+
+/// The keys `brave-opus` uses.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Key {
+    /// Anthropic API.
+    Anthropic,
+    /// Brave Web Search Data for AI.
+    DataForAi,
+}
+
+impl Key {
+    const ALL: [Self; 2] = [Self::Anthropic, Self::DataForAi];
+
+    fn selected(key: Option<Self>) -> Vec<Self> {
+        key.map_or_else(|| Self::ALL.to_vec(), |key| vec![key])
+    }
+}
+
+impl From<Key> for Credential {
+    fn from(key: Key) -> Self {
+        match key {
+            Key::Anthropic => Self::Anthropic,
+            Key::DataForAi => Self::DataForAi,
+        }
+    }
+}
+
+/// Where a key was found.
+#[derive(Debug, Clone, Copy)]
+pub enum Source {
+    Given,
+    Store,
+    Config,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Given => "option or environment",
+            Self::Store => "credential store",
+            Self::Config => "config file",
+        })
+    }
+}
+
+/// The API keys given to `brave-opus`, in order of precedence: the options or their environment
+/// variables, the credential store and the profile of the config file.
+pub struct Keys {
+    pub anthropic_api_key: Option<String>,
+    pub brave_api_key: Option<String>,
+    /// Name of the profile the keys are stored under.
+    pub profile: String,
+    /// Tokens of the profile in the config file.
+    pub tokens: config::Tokens,
+    /// Opened on the first lookup, so it isn't reached when the keys are given.
+    store: OnceCell<Option<Store>>,
+}
+
+impl Keys {
+    pub fn new(profile: String, tokens: config::Tokens) -> Self {
+        Self {
+            anthropic_api_key: None,
+            brave_api_key: None,
+            profile,
+            tokens,
+            store: OnceCell::new(),
+        }
+    }
+
+    /// The key of the API, and where it was found.
+    pub fn key(&self, credential: Credential) -> Result<Option<(String, Source)>> {
+        let given = match credential {
+            Credential::Anthropic => &self.anthropic_api_key,
+            Credential::DataForAi => &self.brave_api_key,
+            Credential::DataForSearch
+            | Credential::Suggest
+            | Credential::Spellcheck
+            | Credential::Autosuggest => &None,
+        };
+        if let Some(key) = given {
+            return Ok(Some((key.clone(), Source::Given)));
+        }
+
+        if let Some(store) = self.store() {
+            if let Some(key) = store.get(&self.profile, credential)? {
+                return Ok(Some((key, Source::Store)));
+            }
+        }
+
+        Ok(self.tokens.get(credential).map(|key| (key.to_string(), Source::Config)))
+    }
+
+    fn store(&self) -> Option<&Store> {
+        self.store
+            .get_or_init(|| {
+                Store::open()
+                    .map_err(|err| log::warn!("Can't open the credential store: {err}"))
+                    .ok()
+            })
+            .as_ref()
+    }
+}
+
+pub fn run(cli: Cli, keys: &Keys, profile: &config::Profile) -> Result<()> {
+    let name = &keys.profile;
+
+    match cli.command {
+        Command::Login { key } => {
+            let store = Store::open()?;
+            for key in Key::selected(key) {
+                let credential = Credential::from(key);
+                let value = read_token(&format!("{credential} key for `{name}`: "))?;
+                if value.is_empty() {
+                    continue;
+                }
+                store.set(name, credential, &value)?;
+                println!("Stored the {credential} key of `{name}` in {store}");
+            }
+        }
+        Command::Logout { key } => {
+            let store = Store::open()?;
+            for key in Key::selected(key) {
+                let credential = Credential::from(key);
+                if store.delete(name, credential)? {
+                    println!("Removed the {credential} key of `{name}` from {store}");
+                } else {
+                    println!("No {credential} key of `{name}` in {store}");
+                }
+            }
+        }
+        Command::Status => {
+            println!("Profile `{name}`");
+            if let Some(store) = keys.store() {
+                println!("Credential store: {store}");
+            }
+            println!();
+            anthropic_status(keys, profile)?;
+            brave_status(keys)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the Anthropic key with a message of a single token.
+fn anthropic_status(keys: &Keys, profile: &config::Profile) -> Result<()> {
+    let credential = Credential::Anthropic;
+    let Some((key, source)) = keys.key(credential)? else {
+        println!("{credential:<12} not set");
+        return Ok(());
+    };
+
+    let mut auth = anthropic::Auth::new(&key);
+    auth.version.clone_from(&profile.anthropic_version);
+    let client = Anthropic::new(auth, "https://api.anthropic.com/v1/");
+    let model = profile.model.as_deref().unwrap_or(CHECK_MODEL);
+    let message = Message { role: Role::User, content: "Hi".to_string() };
+    let response = client.message_create(&MessageBody::new(model, vec![message], 1));
+
+    let (state, details) = match response {
+        Ok(_) => ("valid", format!("model: {model}")),
+        Err(err @ anthropic::error::Error::ApiError(_)) => ("invalid", err.to_string()),
+        Err(err) => ("unchecked", err.to_string()),
+    };
+    println!("{credential:<12} {state:<10} ({source})  {details}");
+    Ok(())
+}
+
+/// Checks the Brave key with a single web search, and shows what its plan unlocks.
+fn brave_status(keys: &Keys) -> Result<()> {
+    let credential = Credential::DataForAi;
+    let Some((key, source)) = keys.key(credential)? else {
+        println!("{credential:<12} not set");
+        return Ok(());
+    };
+
+    let auth = brave::Auth::default().token(Subscription::DataForAi, &key);
+    let client = Brave::new(auth, "https://api.search.brave.com/res/v1");
+    let (state, details) = match web_search_plan(&client) {
+        Ok(plan) => (
+            "valid",
+            format!(
+                "summarizer: {}, extra snippets: {}",
+                yes(plan.summarizer),
+                yes(plan.extra_snippets)
+            ),
+        ),
+        Err(err @ brave::error::Error::ApiError(Some(401 | 403), _)) => {
+            ("invalid", err.to_string())
+        }
+        Err(err) => ("unchecked", err.to_string()),
+    };
+    println!("{credential:<12} {state:<10} ({source})  {details}");
+    Ok(())
+}
+
+fn yes(enabled: bool) -> &'static str {
+    if enabled {
+        "yes"
+    } else {
+        "no"
+    }
+}
@@ -0,0 +1,3 @@
+//! The parts of `brave-opus` shared by its binary and examples.
+
+pub mod rerank;
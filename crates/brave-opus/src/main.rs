@@ -1,13 +1,24 @@
 #![allow(clippy::empty_line_after_outer_attr)]
+mod auth;
 mod run;
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
+use crate::auth::Keys;
+
+/// Environment variable of the Brave key before it was renamed after the Data for AI plan.
+const DEPRECATED_BRAVE_API_KEY_ENV: &str = "BRAVE_API_KEY";
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Run Anthropic Claude 3 using Brave's API as RAG
     #[clap(name = "run")]
     Run(crate::run::Cli),
+    /// Store the API keys in the system keyring, or an encrypted file, and check them
+    #[clap(name = "auth")]
+    Auth(crate::auth::Cli),
 }
 
 #[derive(Debug, Parser)]
@@ -22,9 +33,17 @@ pub struct Cli {
     /// Anthropic API Key
     #[clap(short, long, env = "ANTHROPIC_API_KEY", global = true)]
     pub anthropic_api_key: Option<String>,
-    /// Brave API Key
-    #[clap(short, long, env = "BRAVE_API_KEY", global = true)]
+    /// Brave Web Search Data for AI API Key. `BRAVE_API_KEY` is still read, but deprecated.
+    #[clap(short, long, env = "BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY", global = true)]
     pub brave_api_key: Option<String>,
+    /// The config file with the profiles. Defaults to `~/.config/brave/config.toml`.
+    #[clap(long, env = config::PATH_ENV, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// The profile of the config file to use, for its keys and defaults. Options and environment
+    /// variables take precedence over the keys stored with `brave-opus auth login`, which take
+    /// precedence over the profile.
+    #[clap(long, env = config::PROFILE_ENV, global = true)]
+    pub profile: Option<String>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -39,18 +58,39 @@ async fn main() -> color_eyre::eyre::Result<()> {
         std::process::exit(1);
     })?;
 
-    run();
-    Ok(())
+    run()
 }
 
-fn run() {
+fn run() -> color_eyre::eyre::Result<()> {
     log::debug!("Parsing CLI arguments");
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.brave_api_key.is_none() {
+        cli.brave_api_key = deprecated_brave_api_key();
+    }
+
+    log::debug!("Reading the config");
+    let config = config::Config::load(cli.config.as_deref())?;
+    let profile = config.profile(cli.profile.as_deref())?;
+    let mut keys =
+        Keys::new(config.profile_name(cli.profile.as_deref()).to_string(), profile.tokens.clone());
+    keys.anthropic_api_key = cli.anthropic_api_key;
+    keys.brave_api_key = cli.brave_api_key;
 
     log::debug!("Running command");
     match cli.command {
-        Commands::Run(cli) => {
-            crate::run::execute(&cli);
-        }
+        Commands::Run(run) => crate::run::execute(run.defaults(&profile), &keys, &profile)?,
+        Commands::Auth(cli) => crate::auth::run(cli, &keys, &profile)?,
     }
+
+    Ok(())
+}
+
+/// The Brave key of `BRAVE_API_KEY`, the variable used before the key of each Brave plan got its
+/// own.
+fn deprecated_brave_api_key() -> Option<String> {
+    let key = std::env::var(DEPRECATED_BRAVE_API_KEY_ENV).ok().filter(|key| !key.is_empty())?;
+    log::warn!(
+        "{DEPRECATED_BRAVE_API_KEY_ENV} is deprecated, set BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY instead"
+    );
+    Some(key)
 }
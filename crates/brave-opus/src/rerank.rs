@@ -0,0 +1,178 @@
+//! Re-ranks web search results against the prompt with BM25, so only the pages most relevant to
+//! it are fetched and sent to Claude.
+//!
+//! Each result is scored on its title, description and `extra_snippets`, as a document of the
+//! corpus made of all the results being ranked. The score is then multiplied by the weight of
+//! the domain of the result, if any.
+
+use std::collections::{HashMap, HashSet};
+
+use brave::{documents::plain_text, filter::host};
+
+/// Term frequency saturation: how quickly repeating a term stops raising the score.
+pub const K1: f64 = 1.2;
+/// Length normalization: how much longer results are penalized, from 0 to 1.
+pub const B: f64 = 0.75;
+/// Number of results whose pages are fetched, when the profile doesn't set `top_k`.
+pub const DEFAULT_TOP_K: usize = 5;
+
+/// Words of prompts too common to tell results apart.
+const STOP_WORDS: [&str; 40] = [
+    "a", "about", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from",
+    "how", "i", "in", "is", "it", "me", "my", "of", "on", "or", "should", "that", "the", "this",
+    "to", "was", "what", "when", "where", "which", "who", "why", "will", "with", "you", "your",
+];
+
+/// A result and its score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scored {
+    pub result: brave::Result,
+    pub score: f64,
+}
+
+/// Scores results with BM25, and weighs them by domain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reranker {
+    pub k1: f64,
+    pub b: f64,
+    /// The weight of each domain, and its subdomains.
+    pub domains: Vec<(String, f64)>,
+}
+
+impl Default for Reranker {
+    fn default() -> Self {
+        Self { k1: K1, b: B, domains: Vec::new() }
+    }
+}
+
+impl Reranker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The reranker with the domain weights of a profile.
+    #[must_use]
+    pub fn from_config(rerank: &config::Rerank) -> Self {
+        rerank
+            .domains
+            .iter()
+            .fold(Self::new(), |reranker, (domain, weight)| reranker.domain(domain, *weight))
+    }
+
+    /// Multiplies the score of the results of the domain, or its subdomains: a weight above `1`
+    /// boosts them, below `1` penalizes them, and `0` drops them. Can be called more than once.
+    #[must_use]
+    pub fn domain(mut self, domain: &str, weight: f64) -> Self {
+        let domain = host(domain.trim()).trim_start_matches("www.").to_string();
+        self.domains.push((domain, weight));
+        self
+    }
+
+    /// Scores each result against the query, in the order of the results.
+    #[must_use]
+    pub fn score(&self, query: &str, results: &[brave::Result]) -> Vec<f64> {
+        let documents = results.iter().map(|result| tokenize(&text(result))).collect::<Vec<_>>();
+        let terms = tokenize(query).into_iter().collect::<HashSet<_>>();
+
+        let count = documents.len() as f64;
+        let average = documents.iter().map(Vec::len).sum::<usize>() as f64 / count.max(1.0);
+
+        let idf = terms
+            .iter()
+            .map(|term| {
+                let matching =
+                    documents.iter().filter(|document| document.contains(term)).count() as f64;
+                (term, ((count - matching + 0.5) / (matching + 0.5) + 1.0).ln())
+            })
+            .collect::<HashMap<_, _>>();
+
+        documents
+            .iter()
+            .zip(results)
+            .map(|(document, result)| {
+                let mut frequencies = HashMap::new();
+                for token in document {
+                    *frequencies.entry(token.as_str()).or_insert(0_u32) += 1;
+                }
+                let length = document.len() as f64 / average.max(1.0);
+
+                let score = idf
+                    .iter()
+                    .map(|(term, idf)| {
+                        let frequency =
+                            f64::from(frequencies.get(term.as_str()).copied().unwrap_or(0));
+                        idf * frequency * (self.k1 + 1.0)
+                            / (frequency + self.k1 * (1.0 - self.b + self.b * length))
+                    })
+                    .sum::<f64>();
+                score * self.weight(result)
+            })
+            .collect()
+    }
+
+    /// Sorts the results by score, from the most relevant. Results with the url of an earlier
+    /// one are dropped, and so are the results of domains weighted `0`. Ties keep their order, so
+    /// Brave's ranking breaks them.
+    #[must_use]
+    pub fn rerank(&self, query: &str, results: Vec<brave::Result>) -> Vec<Scored> {
+        let mut seen = HashSet::new();
+        let results = results
+            .into_iter()
+            .filter(|result| self.weight(result) > 0.0)
+            .filter(|result| result.url.as_ref().map_or(true, |url| seen.insert(url.clone())))
+            .collect::<Vec<_>>();
+
+        let scores = self.score(query, &results);
+        let mut scored = results
+            .into_iter()
+            .zip(scores)
+            .map(|(result, score)| Scored { result, score })
+            .collect::<Vec<_>>();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored
+    }
+
+    /// The `k` most relevant results, see `rerank`.
+    #[must_use]
+    pub fn top_k(&self, query: &str, results: Vec<brave::Result>, k: usize) -> Vec<brave::Result> {
+        self.rerank(query, results).into_iter().take(k).map(|scored| scored.result).collect()
+    }
+
+    /// The weight of the most specific domain of the result, or `1`.
+    fn weight(&self, result: &brave::Result) -> f64 {
+        let host = result
+            .meta_url
+            .as_ref()
+            .and_then(|meta_url| meta_url.hostname.as_deref())
+            .map_or_else(|| host(result.url.as_deref().unwrap_or_default()), str::to_lowercase);
+
+        self.domains
+            .iter()
+            .filter(|(domain, _)| {
+                host == *domain
+                    || host.strip_suffix(domain.as_str()).is_some_and(|rest| rest.ends_with('.'))
+            })
+            .max_by_key(|(domain, _)| domain.len())
+            .map_or(1.0, |(_, weight)| *weight)
+    }
+}
+
+/// The text a result is scored on.
+fn text(result: &brave::Result) -> String {
+    let mut parts = vec![
+        result.title.as_deref().unwrap_or_default(),
+        result.description.as_deref().unwrap_or_default(),
+    ];
+    parts.extend(result.extra_snippets.iter().flatten().map(String::as_str));
+    plain_text(&parts.join("\n"))
+}
+
+/// Splits the text into lowercase words, without the stop words.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
@@ -1,5 +1,24 @@
 use clap::Parser;
+use color_eyre::eyre::{eyre, Result};
 
+use anthropic::messages::{MessageApi as _, MessageBody};
+use anthropic::{Anthropic, Message, Role};
+use brave::{subscription::Subscription, web_search::Api as _, Brave, WebSearchParams};
+use brave_opus::rerank::{Reranker, DEFAULT_TOP_K};
+use config::credentials::Credential;
+
+use crate::auth::Keys;
+
+/// Model used when neither `--model` nor the profile set one.
+const DEFAULT_MODEL: &str = "claude-3-opus-20240229";
+/// Number of results asked to Brave, before re-ranking them.
+const SEARCH_COUNT: u16 = 20;
+/// Width the pages are rendered at, in characters.
+const PAGE_WIDTH: usize = 100;
+/// Number of characters kept of each page, so a long page doesn't crowd out the others.
+const MAX_PAGE_CHARS: usize = 20_000;
+/// The maximum number of tokens of the answer.
+const MAX_TOKENS: i32 = 4096;
 
 #[derive(Debug, Parser)]
 #[command(name = "run")]
@@ -7,8 +26,92 @@ use clap::Parser;
 pub struct Cli {
     /// Prompt to execute
     prompt: String,
+    /// The Anthropic model to run.
+    #[clap(long)]
+    model: Option<String>,
+    /// The Anthropic API version.
+    #[clap(long)]
+    anthropic_version: Option<String>,
+    /// Number of search results whose pages are fetched, once re-ranked against the prompt.
+    ///
+    /// Defaults to the `top_k` of the `rerank` settings of the profile, or 5.
+    #[clap(long)]
+    top_k: Option<usize>,
+}
+
+impl Cli {
+    /// Fills in the options that weren't given with the defaults of the profile.
+    pub fn defaults(mut self, profile: &config::Profile) -> Self {
+        self.model = self.model.or_else(|| profile.model.clone());
+        self.anthropic_version =
+            self.anthropic_version.or_else(|| profile.anthropic_version.clone());
+        self.top_k = self.top_k.or(profile.rerank.top_k);
+        self
+    }
+}
+
+/// Searches the prompt with Brave, fetches the pages of the results most relevant to it, and
+/// asks Claude to answer the prompt with them.
+pub fn execute(cli: Cli, keys: &Keys, profile: &config::Profile) -> Result<()> {
+    let (brave_key, _) = keys.key(Credential::DataForAi)?.ok_or_else(|| {
+        eyre!("No Brave key found, set one with `brave-opus auth login --key data-for-ai`")
+    })?;
+    let (anthropic_key, _) = keys.key(Credential::Anthropic)?.ok_or_else(|| {
+        eyre!("No Anthropic key found, set one with `brave-opus auth login --key anthropic`")
+    })?;
+
+    let auth = brave::Auth::default().token(Subscription::DataForAi, &brave_key);
+    let brave = Brave::new(auth, "https://api.search.brave.com/res/v1");
+    let params =
+        WebSearchParams::builder(&cli.prompt).count(SEARCH_COUNT).extra_snippets(true).build()?;
+    let response = brave.search(&params, None)?;
+
+    // Only the pages of the results most relevant to the prompt are fetched.
+    let top_k = cli.top_k.unwrap_or(DEFAULT_TOP_K);
+    let results = Reranker::from_config(&profile.rerank).top_k(
+        &cli.prompt,
+        response.web_results().to_vec(),
+        top_k,
+    );
+    log::info!("Fetching the pages of {} results", results.len());
+
+    let pages = results
+        .iter()
+        .filter_map(|result| {
+            let url = result.url.as_deref()?;
+            let title = result.title.as_deref().unwrap_or(url);
+            match fetch(&brave, url) {
+                Ok(text) => Some(format!("## {title}\n\nSource: {url}\n\n{text}")),
+                Err(err) => {
+                    log::warn!("Can't fetch {url}: {err}");
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut auth = anthropic::Auth::new(&anthropic_key);
+    auth.version = cli.anthropic_version;
+    let anthropic = Anthropic::new(auth, "https://api.anthropic.com/v1/");
+    let content = format!(
+        "Answer the prompt using the pages between triple backticks, and cite their sources.\n\n\
+         ```\n{}\n```\n\nPrompt: {}",
+        pages.join("\n\n"),
+        cli.prompt
+    );
+    let message = Message { role: Role::User, content };
+    let model = cli.model.as_deref().unwrap_or(DEFAULT_MODEL);
+    let response = anthropic.message_create(&MessageBody::new(model, vec![message], MAX_TOKENS))?;
+
+    for text in response.content.iter().filter_map(|content| content.text.as_deref()) {
+        println!("{text}");
+    }
+    Ok(())
 }
 
-pub fn execute(cli: &Cli) {
-    println!("{cli:?}");
+/// Fetches a page with the agent of the client, and renders it as plain text.
+fn fetch(client: &Brave, url: &str) -> Result<String> {
+    let html = client.agent().get(url).call()?.into_string()?;
+    let text = html2text::config::plain().string_from_read(html.as_bytes(), PAGE_WIDTH)?;
+    Ok(text.chars().take(MAX_PAGE_CHARS).collect())
 }
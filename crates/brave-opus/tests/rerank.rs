@@ -0,0 +1,83 @@
+//! Checks the BM25 re-ranking of search results against the prompt.
+
+use brave_opus::rerank::Reranker;
+use serde_json::json;
+
+fn result(url: &str, title: &str, description: &str, snippets: &[&str]) -> brave::Result {
+    serde_json::from_value(json!({
+        "url": url,
+        "title": title,
+        "description": description,
+        "extra_snippets": snippets,
+    }))
+    .unwrap()
+}
+
+fn results() -> Vec<brave::Result> {
+    vec![
+        result("https://www.pinterest.com/rust", "Rust pins", "Rusty cars and tools.", &[]),
+        result(
+            "https://tokio.rs/",
+            "Tokio",
+            "An asynchronous runtime for the Rust programming language.",
+            &["Tokio is an <strong>async</strong> runtime.", "Async tasks and timers."],
+        ),
+        result("https://www.rust-lang.org/", "Rust", "A language empowering everyone.", &[]),
+        result("https://blog.example.com/cooking", "Cooking", "Recipes for the weekend.", &[]),
+    ]
+}
+
+fn urls(results: &[brave::Result]) -> Vec<&str> {
+    results.iter().map(|result| result.url.as_deref().unwrap()).collect()
+}
+
+#[test]
+fn results_matching_the_prompt_rank_first() {
+    let reranker = Reranker::new();
+    let scores = reranker.score("Which async runtime for Rust?", &results());
+
+    assert!(scores[1] > scores[0] && scores[1] > scores[2], "{scores:?}");
+    assert!(scores[0] > 0.0, "{scores:?}");
+    assert_eq!(scores[3], 0.0);
+
+    let ranked = reranker.top_k("Which async runtime for Rust?", results(), 2);
+    assert_eq!(urls(&ranked)[0], "https://tokio.rs/");
+    assert_eq!(ranked.len(), 2);
+}
+
+#[test]
+fn domain_weights_boost_penalize_and_drop_results() {
+    let rerank = config::Rerank {
+        top_k: None,
+        domains: [("rust-lang.org".into(), 10.0), ("pinterest.com".into(), 0.0)].into(),
+    };
+    let ranked = Reranker::from_config(&rerank).rerank("rust async", results());
+
+    let ranked = ranked.into_iter().map(|scored| scored.result).collect::<Vec<_>>();
+    assert_eq!(
+        urls(&ranked),
+        ["https://www.rust-lang.org/", "https://tokio.rs/", "https://blog.example.com/cooking"]
+    );
+
+    // The most specific domain wins over its parent.
+    let reranker = Reranker::new().domain("example.com", 0.0).domain("blog.example.com", 1.0);
+    let ranked = reranker.top_k("cooking", results(), 10);
+    assert_eq!(urls(&ranked)[0], "https://blog.example.com/cooking");
+}
+
+#[test]
+fn repeated_urls_are_dropped_and_ties_keep_their_order() {
+    let mut results = results();
+    results.push(results[1].clone());
+
+    let ranked = Reranker::new().top_k("weather", results, 10);
+    assert_eq!(
+        urls(&ranked),
+        [
+            "https://www.pinterest.com/rust",
+            "https://tokio.rs/",
+            "https://www.rust-lang.org/",
+            "https://blog.example.com/cooking",
+        ]
+    );
+}
@@ -0,0 +1,336 @@
+//! Stores the subscription tokens of the profiles in the system keyring, or an encrypted file,
+//! and finds the token of each subscription.
+
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::Result;
+
+use brave::plan::{spellcheck_plan, suggest_plan, web_search_plan};
+use brave::{
+    subscription::{Feature, Subscription},
+    Auth,
+};
+use config::credentials::{read_token, Credential, Store};
+
+use crate::{build_client, Settings};
+
+#[derive(Debug, Parser)]
+#[command(name = "auth")]
+#[command(about = "Manage the stored subscription tokens of the profile")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Store the subscription tokens of the profile.
+    ///
+    /// Tokens are asked for on the terminal, or read from stdin one per line. Leave a token
+    /// empty to keep the stored one.
+    Login {
+        /// Only store this token.
+        #[clap(long, value_enum)]
+        token: Option<Token>,
+    },
+    /// Remove the subscription tokens of the profile.
+    Logout {
+        /// Only remove this token.
+        #[clap(long, value_enum)]
+        token: Option<Token>,
+    },
+    /// Check the tokens of the profile, and show what their plans unlock.
+    Status,
+}
+
+/// The tokens `bravecli` uses.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Token {
+    /// Web Search Data for Search.
+    DataForSearch,
+    /// Web Search Data for AI.
+    DataForAi,
+    /// Suggest.
+    Suggest,
+    /// Spellcheck.
+    Spellcheck,
+    /// Autosuggest.
+    Autosuggest,
+}
+
+impl Token {
+    const ALL: [Self; 5] =
+        [Self::DataForSearch, Self::DataForAi, Self::Suggest, Self::Spellcheck, Self::Autosuggest];
+
+    fn selected(token: Option<Self>) -> Vec<Self> {
+        token.map_or_else(|| Self::ALL.to_vec(), |token| vec![token])
+    }
+}
+
+impl From<Token> for Credential {
+    fn from(token: Token) -> Self {
+        match token {
+            Token::DataForSearch => Self::DataForSearch,
+            Token::DataForAi => Self::DataForAi,
+            Token::Suggest => Self::Suggest,
+            Token::Spellcheck => Self::Spellcheck,
+            Token::Autosuggest => Self::Autosuggest,
+        }
+    }
+}
+
+impl From<Token> for Subscription {
+    fn from(token: Token) -> Self {
+        match token {
+            Token::DataForSearch => Self::DataForSearch,
+            Token::DataForAi => Self::DataForAi,
+            Token::Suggest => Self::Suggest,
+            Token::Spellcheck => Self::Spellcheck,
+            Token::Autosuggest => Self::Autosuggest,
+        }
+    }
+}
+
+impl From<Subscription> for Token {
+    fn from(subscription: Subscription) -> Self {
+        match subscription {
+            Subscription::DataForSearch => Self::DataForSearch,
+            Subscription::DataForAi => Self::DataForAi,
+            Subscription::Suggest => Self::Suggest,
+            Subscription::Spellcheck => Self::Spellcheck,
+            Subscription::Autosuggest => Self::Autosuggest,
+        }
+    }
+}
+
+/// Where a token was found.
+#[derive(Debug, Clone, Copy)]
+pub enum Source {
+    Option,
+    Env,
+    Store,
+    Config,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Option => "--subscription-token",
+            Self::Env => "environment",
+            Self::Store => "credential store",
+            Self::Config => "config file",
+        })
+    }
+}
+
+/// The tokens given to `bravecli`, in order of precedence: the `--subscription-token` option,
+/// the environment variables, the credential store and the profile of the config file.
+#[derive(Clone)]
+pub struct Credentials {
+    pub subscription_token: Option<String>,
+    /// Tokens of the environment variables of each subscription, see `Subscription::env`.
+    pub env: BTreeMap<Subscription, String>,
+    /// Name of the profile the tokens are stored under.
+    pub profile: String,
+    /// Tokens of the profile in the config file.
+    pub tokens: config::Tokens,
+    /// Opened on the first lookup, so it isn't reached when the token is given.
+    store: Rc<OnceCell<Option<Store>>>,
+}
+
+impl Credentials {
+    pub fn new(profile: String, tokens: config::Tokens) -> Self {
+        Self {
+            subscription_token: None,
+            env: BTreeMap::new(),
+            profile,
+            tokens,
+            store: Rc::default(),
+        }
+    }
+
+    /// The tokens of the subscriptions that cover the features, or `None` if there are none.
+    ///
+    /// `--subscription-token` replaces the others, and is sent with every request. Only the
+    /// tokens the features need are looked up, so the credential store isn't opened when the
+    /// environment covers them.
+    pub fn auth(&self, features: &[Feature]) -> Result<Option<Auth>> {
+        if let Some(token) = &self.subscription_token {
+            return Ok(Some(Auth::new(token)));
+        }
+
+        let mut auth = Auth::default();
+        for &feature in features {
+            if auth.grants(feature) {
+                continue;
+            }
+            if let Some((token, value, source)) = self.resolve(feature)? {
+                log::debug!("Using the {} token of the {source}", Credential::from(token));
+                auth = auth.token(token.into(), &value);
+            }
+        }
+        Ok((!auth.tokens.is_empty()).then_some(auth))
+    }
+
+    /// The token of the first subscription that covers the feature, in order of preference, and
+    /// where it was found. The environment is checked for all of them before the store.
+    fn resolve(&self, feature: Feature) -> Result<Option<(Token, String, Source)>> {
+        let tokens: Vec<Token> = feature.subscriptions().iter().copied().map(Token::from).collect();
+
+        for &token in &tokens {
+            if let Some(value) = self.env(token) {
+                return Ok(Some((token, value.clone(), Source::Env)));
+            }
+        }
+        for &token in &tokens {
+            if let Some((value, source)) = self.credential(token)? {
+                return Ok(Some((token, value, source)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The value of the token, and where it was found.
+    fn token(&self, token: Token) -> Result<Option<(String, Source)>> {
+        if let Some(token) = &self.subscription_token {
+            return Ok(Some((token.clone(), Source::Option)));
+        }
+        self.credential(token)
+    }
+
+    fn credential(&self, token: Token) -> Result<Option<(String, Source)>> {
+        if let Some(value) = self.env(token) {
+            return Ok(Some((value.clone(), Source::Env)));
+        }
+
+        let credential = Credential::from(token);
+        if let Some(store) = self.store() {
+            if let Some(token) = store.get(&self.profile, credential)? {
+                return Ok(Some((token, Source::Store)));
+            }
+        }
+
+        Ok(self.tokens.get(credential).map(|token| (token.to_string(), Source::Config)))
+    }
+
+    fn env(&self, token: Token) -> Option<&String> {
+        self.env.get(&token.into())
+    }
+
+    fn store(&self) -> Option<&Store> {
+        self.store
+            .get_or_init(|| {
+                Store::open()
+                    .map_err(|err| log::warn!("Can't open the credential store: {err}"))
+                    .ok()
+            })
+            .as_ref()
+    }
+}
+
+pub fn run(cli: Cli, credentials: &Credentials, settings: &Settings) -> Result<()> {
+    let profile = &credentials.profile;
+
+    match cli.command {
+        Command::Login { token } => {
+            let store = Store::open()?;
+            for token in Token::selected(token) {
+                let credential = Credential::from(token);
+                let value = read_token(&format!("{credential} token for `{profile}`: "))?;
+                if value.is_empty() {
+                    continue;
+                }
+                store.set(profile, credential, &value)?;
+                println!("Stored the {credential} token of `{profile}` in {store}");
+            }
+        }
+        Command::Logout { token } => {
+            let store = Store::open()?;
+            for token in Token::selected(token) {
+                let credential = Credential::from(token);
+                if store.delete(profile, credential)? {
+                    println!("Removed the {credential} token of `{profile}` from {store}");
+                } else {
+                    println!("No {credential} token of `{profile}` in {store}");
+                }
+            }
+        }
+        Command::Status => {
+            println!("Profile `{profile}`");
+            if let Some(store) = credentials.store() {
+                println!("Credential store: {store}");
+            }
+            println!();
+            for token in Token::ALL {
+                status(credentials, token, settings)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints where the token of the subscription comes from, and what its plan unlocks.
+fn status(credentials: &Credentials, token: Token, settings: &Settings) -> Result<()> {
+    let credential = Credential::from(token);
+    let Some((value, source)) = credentials.token(token)? else {
+        println!("{credential:<15} not set");
+        return Ok(());
+    };
+
+    let client = build_client(Auth::default().token(token.into(), &value), settings.clone())?;
+    let features = match token {
+        Token::DataForSearch | Token::DataForAi => web_search_plan(&client).map(|plan| {
+            format!(
+                "summarizer: {}, extra snippets: {}",
+                yes(plan.summarizer),
+                yes(plan.extra_snippets)
+            )
+        }),
+        Token::Suggest | Token::Autosuggest => {
+            suggest_plan(&client).map(|plan| format!("rich suggest: {}", yes(plan.rich)))
+        }
+        Token::Spellcheck => spellcheck_plan(&client).map(|()| String::new()),
+    };
+
+    // Only a rejection of the API says the token is wrong, other errors leave it unchecked.
+    let (state, details) = match features {
+        Ok(features) => ("valid", features),
+        Err(err @ brave::error::Error::ApiError(Some(401 | 403), _)) => {
+            ("invalid", err.to_string())
+        }
+        Err(err) => ("unchecked", err.to_string()),
+    };
+    println!("{credential:<15} {state:<10} ({source})  {details}");
+    Ok(())
+}
+
+fn yes(enabled: bool) -> &'static str {
+    if enabled {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_tokens_of_the_features_are_looked_up() {
+        let tokens =
+            config::Tokens { data_for_ai: Some("profile-ai".into()), ..config::Tokens::default() };
+        let mut credentials = Credentials::new("test".into(), tokens);
+        credentials.env.insert(Subscription::Suggest, "env-suggest".into());
+
+        let auth = credentials.auth(&[Feature::Suggest]).unwrap().unwrap();
+
+        assert_eq!(auth.tokens.len(), 1);
+        assert_eq!(auth.tokens[&Subscription::Suggest], "env-suggest");
+        assert!(credentials.store.get().is_none(), "the store was opened");
+    }
+}
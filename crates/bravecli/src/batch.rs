@@ -0,0 +1,303 @@
+//! Runs the same web search for many queries, writing one JSON line per query.
+//!
+//! Lines are written as soon as each search completes, so an interrupted batch can be resumed:
+//! queries that already have a response in the output file are skipped.
+
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Serialize;
+
+use brave::{
+    error::Error, filter::Filter, web_search::Api, Brave, WebSearchApiResponse, WebSearchParams,
+};
+
+/// Number of times a rate limited request is retried.
+const RETRIES: u32 = 5;
+/// Lowest rate limit, of one request every ~17 minutes, so the interval between requests fits
+/// in a `Duration`.
+pub const MIN_RATE_LIMIT: f64 = 0.001;
+
+/// A line of the output, with the response of the query or the error of its search.
+#[derive(Debug, Serialize)]
+struct Line<'a> {
+    query: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<WebSearchApiResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// How a batch is run.
+#[derive(Debug)]
+pub struct Options {
+    /// File the lines are appended to, or stdout when `None`.
+    pub output: Option<PathBuf>,
+    /// Number of searches sent at the same time.
+    pub concurrency: usize,
+    /// Maximum number of searches started per second.
+    pub rate_limit: f64,
+    pub version: Option<String>,
+}
+
+/// Reads the queries of a batch, one per line, from a file or stdin when the path is `-`.
+/// Blank lines and repeated queries are skipped.
+pub fn read_queries(path: &Path) -> Result<Vec<String>> {
+    let mut contents = String::new();
+    if path == Path::new("-") {
+        std::io::stdin().read_to_string(&mut contents)?;
+    } else {
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(|err| eyre!("Can't read the queries in {}: {err}", path.display()))?;
+    }
+
+    let mut seen = HashSet::new();
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|query| !query.is_empty() && seen.insert(*query))
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Searches every query with the params of the template, and writes a line with the query and
+/// its response, or its error, as each search completes.
+pub fn run(
+    client: &Brave,
+    queries: Vec<String>,
+    template: &WebSearchParams,
+    filter: &Filter,
+    options: &Options,
+) -> Result<()> {
+    let (writer, done) = match &options.output {
+        Some(path) => open_output(path)?,
+        None => (Box::new(std::io::stdout()) as Box<dyn Write + Send>, HashSet::new()),
+    };
+
+    let total = queries.len();
+    let queries = queries.into_iter().filter(|query| !done.contains(query)).collect::<Vec<_>>();
+    if queries.len() < total {
+        log::info!("Skipping {} queries already in the output", total - queries.len());
+    }
+
+    let pending = queries.len();
+    let queue = Mutex::new(queries.into_iter());
+    let writer = Mutex::new(writer);
+    let limiter = RateLimiter::new(options.rate_limit);
+    let failed = Mutex::new(0_usize);
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.concurrency.clamp(1, pending.max(1)) {
+            scope.spawn(|| loop {
+                let Some(query) = queue.lock().unwrap().next() else {
+                    break;
+                };
+
+                let params = WebSearchParams { q: query.clone(), ..template.clone() };
+                let mut line = Line { query: &query, response: None, error: None };
+                let version = options.version.as_deref();
+                match retry(&limiter, &query, || client.search(&params, version)) {
+                    Ok(mut response) => {
+                        filter.apply(&mut response);
+                        line.response = Some(response);
+                    }
+                    Err(err) => {
+                        log::error!("Search for `{query}` failed: {err}");
+                        *failed.lock().unwrap() += 1;
+                        line.error = Some(err.to_string());
+                    }
+                }
+
+                let line = serde_json::to_string(&line).expect("responses serialize to JSON");
+                let mut writer = writer.lock().unwrap();
+                if let Err(err) = writeln!(writer, "{line}").and_then(|()| writer.flush()) {
+                    log::error!("Can't write the response of `{query}`: {err}");
+                }
+            });
+        }
+    });
+
+    let failed = failed.into_inner().unwrap();
+    if failed > 0 {
+        return Err(eyre!(
+            "{failed} of {pending} searches failed, run the batch again to retry them"
+        ));
+    }
+    Ok(())
+}
+
+/// Sends a request for the query once the rate limit allows it, and retries it while the API
+/// answers `429 Too Many Requests`, whatever the body of the response.
+pub fn retry<T>(
+    limiter: &RateLimiter,
+    query: &str,
+    mut request: impl FnMut() -> brave::ApiResult<T>,
+) -> brave::ApiResult<T> {
+    let mut backoff = Duration::from_secs(1);
+    for _ in 0..RETRIES {
+        limiter.wait();
+        match request() {
+            Err(Error::ApiError(Some(429), _)) => {
+                log::warn!("Rate limited for `{query}`, retrying in {backoff:?}");
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    limiter.wait();
+    request()
+}
+
+/// Opens the output for appending, and reads the queries that already have a response.
+fn open_output(path: &Path) -> Result<(Box<dyn Write + Send>, HashSet<String>)> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+        .map_err(|err| eyre!("Can't open the output {}: {err}", path.display()))?;
+
+    let mut done = HashSet::new();
+    for line in BufReader::new(&file).lines() {
+        let line = line?;
+        // Lines cut short by an interruption don't parse, so their query runs again.
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) {
+            if let (Some(query), Some(_)) = (json["query"].as_str(), json.get("response")) {
+                done.insert(query.to_string());
+            }
+        }
+    }
+
+    // Starts on a new line if the last one was cut short.
+    if file.seek(SeekFrom::End(0))? > 0 {
+        let mut last = [0];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+        if last != *b"\n" {
+            writeln!(file)?;
+        }
+    }
+
+    Ok((Box::new(file), done))
+}
+
+/// Parses a `--rate-limit`: `0` for no limit, or at least `MIN_RATE_LIMIT` requests per second.
+pub fn parse_rate_limit(s: &str) -> std::result::Result<f64, String> {
+    let rate = s.parse::<f64>().map_err(|err| err.to_string())?;
+    if rate == 0.0 || (MIN_RATE_LIMIT..=f64::MAX).contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(format!("expected 0 for no limit, or a number of at least {MIN_RATE_LIMIT}"))
+    }
+}
+
+/// Spaces out the requests of every worker, so at most `rate` start each second.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// A `rate` of `0` or less means no limit. Lower positive rates are raised to
+    /// `MIN_RATE_LIMIT`.
+    pub fn new(rate: f64) -> Self {
+        let interval = if rate > 0.0 {
+            Duration::from_secs_f64(1.0 / rate.max(MIN_RATE_LIMIT))
+        } else {
+            Duration::ZERO
+        };
+        Self { interval, next: Mutex::new(Instant::now()) }
+    }
+
+    /// Blocks until the next request can start.
+    pub fn wait(&self) {
+        let now = Instant::now();
+        let start = {
+            let mut next = self.next.lock().unwrap();
+            let start = (*next).max(now);
+            *next = start + self.interval;
+            start
+        };
+        std::thread::sleep(start - now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_are_trimmed_and_deduplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queries.txt");
+        std::fs::write(&path, "rust\n\n  tokio \nrust\r\ntokio\nasync rust\n").unwrap();
+
+        assert_eq!(read_queries(&path).unwrap(), ["rust", "tokio", "async rust"]);
+        assert!(read_queries(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn resumed_outputs_skip_only_the_answered_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.jsonl");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"query":"rust","response":{"type":"search"}}"#,
+                "\n",
+                r#"{"query":"tokio","error":"timed out"}"#,
+                "\n",
+                r#"{"query":"async rust","resp"#,
+            ),
+        )
+        .unwrap();
+
+        let (mut writer, done) = open_output(&path).unwrap();
+        assert_eq!(done, HashSet::from(["rust".to_string()]));
+
+        writeln!(writer, r#"{{"query":"tokio","response":{{}}}}"#).unwrap();
+        drop(writer);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            contents.ends_with("\"resp\n{\"query\":\"tokio\",\"response\":{}}\n"),
+            "{contents}"
+        );
+
+        let (_, done) = open_output(&path).unwrap();
+        assert_eq!(done, HashSet::from(["rust".to_string(), "tokio".to_string()]));
+    }
+
+    #[test]
+    fn rate_limits_are_zero_or_spaced_out() {
+        assert_eq!(parse_rate_limit("0"), Ok(0.0));
+        assert_eq!(parse_rate_limit("0.5"), Ok(0.5));
+        for rate in ["1e-20", "-1", "NaN", "inf", "fast"] {
+            assert!(parse_rate_limit(rate).is_err(), "{rate} was accepted");
+        }
+
+        assert_eq!(RateLimiter::new(0.0).interval, Duration::ZERO);
+        assert_eq!(RateLimiter::new(4.0).interval, Duration::from_millis(250));
+        assert_eq!(RateLimiter::new(1e-20).interval, Duration::from_secs(1000));
+    }
+
+    #[test]
+    fn only_rate_limited_requests_are_retried() {
+        let limiter = RateLimiter::new(0.0);
+        let mut statuses = vec![500, 429];
+        let result = retry(&limiter, "rust", || {
+            let status = statuses.pop().unwrap();
+            Err::<(), _>(Error::ApiError(Some(status), "Too Many Requests".to_string()))
+        });
+
+        assert!(matches!(result, Err(Error::ApiError(Some(500), _))));
+        assert!(statuses.is_empty());
+    }
+}
@@ -0,0 +1,425 @@
+//! Expands a seed query into related ones with the suggest API, and searches the web for each of
+//! them, collecting every url found along with the expansion path that led to it.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+
+use clap::Parser;
+use color_eyre::eyre::{bail, Result};
+use serde::Serialize;
+
+use brave::{
+    apis::{CountryCode, LanguageCode, SafeSearch, SuggestSearchParams, WebSearchParams},
+    subscription::Feature,
+    suggest::Api as _,
+    web_search::Api as _,
+    Brave, WebSearchApiResponse,
+};
+
+use crate::batch::{parse_rate_limit, retry, RateLimiter};
+use crate::output::{self, Format, TABLE_CELL_WIDTH};
+use crate::profile::Defaults;
+
+/// Separator of the queries of an expansion path, when printed as text.
+const PATH_SEPARATOR: &str = " > ";
+
+#[derive(Debug, Parser)]
+#[command(name = "expand")]
+#[command(about = "Expand a query with its suggestions, and collect the urls found for each")]
+pub struct Cli {
+    /// The seed query.
+    q: String,
+
+    /// How many times suggestions are expanded again: `1` searches the seed and its
+    /// suggestions, `2` the suggestions of the suggestions too, and so on.
+    #[clap(long, default_value_t = 1)]
+    depth: usize,
+
+    /// The number of suggestions asked for each query. The maximum is 20.
+    #[clap(long)]
+    suggestions: Option<u16>,
+
+    /// The maximum number of queries searched, the seed included.
+    #[clap(long, default_value_t = 50)]
+    limit: usize,
+
+    /// Whether to enhance suggestions with rich results. This is an extra option in plans which
+    /// needs to be enabled.
+    #[clap(long)]
+    rich: bool,
+
+    /// The number of search results of each query. The maximum is 20.
+    #[clap(long)]
+    count: Option<u16>,
+
+    /// The search query country, e.g. `us`.
+    #[clap(long)]
+    country: Option<CountryCode>,
+
+    /// The language of the suggestions and search results, e.g. `en`.
+    #[clap(long)]
+    search_lang: Option<LanguageCode>,
+
+    /// Filters search results for adult content: `off`, `moderate` or `strict`.
+    #[clap(long)]
+    safesearch: Option<SafeSearch>,
+
+    /// Web Search API Version, in the `YYYY-MM-DD` format.
+    #[clap(long)]
+    version: Option<String>,
+
+    /// Suggest Search API Version, in the `YYYY-MM-DD` format.
+    #[clap(long)]
+    suggest_version: Option<String>,
+
+    /// Maximum number of requests started per second, e.g. `1` for the free plans.
+    ///
+    /// Requests rejected for exceeding the rate limit of the plan are retried after a while.
+    #[clap(long, value_name = "PER_SEC", default_value_t = 1.0, value_parser = parse_rate_limit)]
+    rate_limit: f64,
+
+    /// The output format.
+    ///
+    /// - `json`: The corpus, as a list of urls with the path that found them.
+    /// - `jsonl`: One url of the corpus per line.
+    /// - `table`: The url, title and path of each url.
+    /// - `markdown`: A table with the url, title and path of each url.
+    /// - `csv`: The url, title, description and path of each url.
+    /// - `urls`: Just the urls.
+    ///
+    /// Defaults to `json`.
+    #[clap(long, verbatim_doc_comment, value_enum)]
+    format: Option<Format>,
+}
+
+impl Cli {
+    /// Fills in the options that weren't given with the defaults of the profile.
+    pub fn defaults(mut self, defaults: &Defaults) -> Self {
+        self.version = self.version.or_else(|| defaults.web_search_version.clone());
+        self.suggest_version = self.suggest_version.or_else(|| defaults.suggest_version.clone());
+        self.country = self.country.or_else(|| defaults.country.clone());
+        self.search_lang = self.search_lang.or_else(|| defaults.search_lang.clone());
+        self.safesearch = self.safesearch.or(defaults.safesearch);
+        self.format = self.format.or(defaults.format);
+        self
+    }
+
+    fn suggest_params(&self, q: &str) -> Result<SuggestSearchParams> {
+        let mut builder = SuggestSearchParams::builder(q).rich(self.rich);
+        if let Some(country) = &self.country {
+            builder = builder.country(country.clone());
+        }
+        if let Some(lang) = &self.search_lang {
+            builder = builder.lang(lang.clone());
+        }
+        if let Some(count) = self.suggestions {
+            builder = builder.count(count);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn search_params(&self, q: &str) -> Result<WebSearchParams> {
+        let mut builder = WebSearchParams::builder(q);
+        if let Some(country) = &self.country {
+            builder = builder.country(country.clone());
+        }
+        if let Some(search_lang) = &self.search_lang {
+            builder = builder.search_lang(search_lang.clone());
+        }
+        if let Some(safesearch) = self.safesearch {
+            builder = builder.safesearch(safesearch);
+        }
+        if let Some(count) = self.count {
+            builder = builder.count(count);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// A query to search, and the queries it was expanded from, starting with the seed.
+#[derive(Debug, Clone)]
+struct Expansion {
+    path: Vec<String>,
+    /// Why the suggestions or the search of the query failed.
+    error: Option<String>,
+}
+
+impl Expansion {
+    fn new(path: Vec<String>) -> Self {
+        Self { path, error: None }
+    }
+
+    fn query(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
+}
+
+/// A url of the corpus.
+#[derive(Debug, Serialize)]
+struct Entry {
+    url: String,
+    title: String,
+    description: String,
+    /// The expansion path of the first query that found the url, starting with the seed.
+    path: Vec<String>,
+    /// The position of the url in the results of that query, from 1.
+    rank: usize,
+    /// The number of queries that found the url.
+    hits: usize,
+}
+
+pub fn run(cli: Cli, client: Brave) -> Result<()> {
+    if !client.auth.grants(Feature::Suggest) {
+        bail!("Expanding needs a Suggest subscription token");
+    }
+
+    let limiter = RateLimiter::new(cli.rate_limit);
+    let mut expansions = expand(&cli.q, cli.depth, cli.limit, |query| {
+        let params = cli.suggest_params(query)?;
+        let response =
+            retry(&limiter, query, || client.suggest(&params, cli.suggest_version.as_deref()))?;
+        Ok(response.results.into_iter().filter_map(|result| result.query).collect())
+    });
+    log::info!("Searching {} queries", expansions.len());
+
+    let search = |query: &str| -> Result<WebSearchApiResponse> {
+        let params = cli.search_params(query)?;
+        Ok(retry(&limiter, query, || client.search(&params, cli.version.as_deref()))?)
+    };
+    let mut corpus: Vec<Entry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for expansion in &mut expansions {
+        let response = match search(expansion.query()) {
+            Ok(response) => response,
+            Err(err) => {
+                log::error!("Search for `{}` failed: {err}", expansion.query());
+                expansion.error = Some(err.to_string());
+                continue;
+            }
+        };
+
+        for row in output::rows(&response).into_iter().filter(|row| !row.url.is_empty()) {
+            if let Some(&i) = index.get(&row.url) {
+                corpus[i].hits += 1;
+                continue;
+            }
+            index.insert(row.url.clone(), corpus.len());
+            corpus.push(Entry {
+                url: row.url,
+                title: row.title,
+                description: row.description,
+                path: expansion.path.clone(),
+                rank: row.rank,
+                hits: 1,
+            });
+        }
+    }
+
+    print(cli.format.unwrap_or(Format::Json), &corpus)?;
+
+    let failed = expansions.iter().filter(|expansion| expansion.error.is_some()).count();
+    if failed > 0 {
+        bail!("{failed} of {} queries failed, the corpus misses their urls", expansions.len());
+    }
+    Ok(())
+}
+
+/// Walks the suggestions breadth first from the seed, down to `depth`, until `limit` queries are
+/// found. Queries seen before, ignoring case, aren't expanded again. A query whose suggestions
+/// can't be fetched keeps the error, and the walk goes on without them.
+fn expand(
+    seed: &str,
+    depth: usize,
+    limit: usize,
+    mut suggest: impl FnMut(&str) -> Result<Vec<String>>,
+) -> Vec<Expansion> {
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let mut seen = HashSet::from([seed.to_lowercase()]);
+    let mut expansions = vec![Expansion::new(vec![seed.to_string()])];
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(parent) = queue.pop_front() {
+        if expansions[parent].path.len() > depth {
+            continue;
+        }
+
+        let suggestions = match suggest(expansions[parent].query()) {
+            Ok(suggestions) => suggestions,
+            Err(err) => {
+                log::error!("Suggestions for `{}` failed: {err}", expansions[parent].query());
+                expansions[parent].error = Some(err.to_string());
+                continue;
+            }
+        };
+
+        for suggestion in suggestions {
+            if expansions.len() >= limit {
+                return expansions;
+            }
+            if !seen.insert(suggestion.to_lowercase()) {
+                continue;
+            }
+
+            let mut path = expansions[parent].path.clone();
+            path.push(suggestion);
+            log::debug!("Expanded to `{}`", path.join(PATH_SEPARATOR));
+            queue.push_back(expansions.len());
+            expansions.push(Expansion::new(path));
+        }
+    }
+
+    expansions
+}
+
+fn print(format: Format, corpus: &[Entry]) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    let path = |entry: &Entry| entry.path.join(PATH_SEPARATOR);
+
+    match format {
+        Format::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(corpus)?)?,
+        Format::Jsonl => {
+            for entry in corpus {
+                writeln!(stdout, "{}", serde_json::to_string(entry)?)?;
+            }
+        }
+        Format::Table => {
+            let header = ["URL", "TITLE", "PATH"].map(ToString::to_string);
+            let lines = corpus.iter().map(|entry| {
+                vec![
+                    entry.url.clone(),
+                    output::truncate(&entry.title, TABLE_CELL_WIDTH),
+                    path(entry),
+                ]
+            });
+            write!(stdout, "{}", output::table(header.to_vec(), lines))?;
+        }
+        Format::Markdown => {
+            let header = ["url", "title", "path"].map(ToString::to_string);
+            let lines = corpus
+                .iter()
+                .map(|entry| vec![entry.url.clone(), entry.title.clone(), path(entry)]);
+            write!(stdout, "{}", output::markdown_table(&header, lines))?;
+        }
+        Format::Csv => {
+            let header = ["url", "title", "description", "path", "rank", "hits"];
+            let lines = corpus.iter().map(|entry| {
+                vec![
+                    entry.url.clone(),
+                    entry.title.clone(),
+                    entry.description.clone(),
+                    path(entry),
+                    entry.rank.to_string(),
+                    entry.hits.to_string(),
+                ]
+            });
+            write!(stdout, "{}", output::csv(header.map(ToString::to_string).to_vec(), lines))?;
+        }
+        Format::Urls => {
+            for entry in corpus {
+                writeln!(stdout, "{}", entry.url)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use color_eyre::eyre::eyre;
+
+    /// Suggests from a fixed graph of queries, and records the queries expanded.
+    fn suggest<'a>(
+        graph: &'a [(&str, &[&str])],
+        expanded: &'a mut Vec<String>,
+    ) -> impl FnMut(&str) -> Result<Vec<String>> + 'a {
+        move |query| {
+            expanded.push(query.to_string());
+            match graph.iter().find(|(parent, _)| *parent == query) {
+                Some((_, suggestions)) => Ok(suggestions.iter().map(ToString::to_string).collect()),
+                None => Err(eyre!("no suggestions for {query}")),
+            }
+        }
+    }
+
+    fn paths(expansions: &[Expansion]) -> Vec<String> {
+        expansions.iter().map(|expansion| expansion.path.join(PATH_SEPARATOR)).collect()
+    }
+
+    const GRAPH: &[(&str, &[&str])] = &[
+        ("rust", &["rust game", "Rust", "rust lang"]),
+        ("rust game", &["rust game servers", "rust lang"]),
+        ("rust lang", &["rust lang book"]),
+        ("rust game servers", &["rust game servers list"]),
+        ("rust lang book", &[]),
+    ];
+
+    #[test]
+    fn suggestions_are_expanded_breadth_first_down_to_the_depth() {
+        let mut expanded = Vec::new();
+        let expansions = expand("rust", 0, 50, suggest(GRAPH, &mut expanded));
+        assert_eq!(paths(&expansions), ["rust"]);
+        assert!(expanded.is_empty());
+
+        let mut expanded = Vec::new();
+        let expansions = expand("rust", 1, 50, suggest(GRAPH, &mut expanded));
+        assert_eq!(paths(&expansions), ["rust", "rust > rust game", "rust > rust lang"]);
+        assert_eq!(expanded, ["rust"]);
+
+        let mut expanded = Vec::new();
+        let expansions = expand("rust", 2, 50, suggest(GRAPH, &mut expanded));
+        assert_eq!(
+            paths(&expansions),
+            [
+                "rust",
+                "rust > rust game",
+                "rust > rust lang",
+                "rust > rust game > rust game servers",
+                "rust > rust lang > rust lang book",
+            ]
+        );
+        assert_eq!(expanded, ["rust", "rust game", "rust lang"]);
+    }
+
+    #[test]
+    fn expansions_stop_at_the_limit() {
+        let mut expanded = Vec::new();
+        let expansions = expand("rust", 3, 4, suggest(GRAPH, &mut expanded));
+        assert_eq!(
+            paths(&expansions),
+            [
+                "rust",
+                "rust > rust game",
+                "rust > rust lang",
+                "rust > rust game > rust game servers"
+            ]
+        );
+
+        let mut expanded = Vec::new();
+        assert_eq!(expand("rust", 1, 1, suggest(GRAPH, &mut expanded)).len(), 1);
+
+        let mut expanded = Vec::new();
+        assert!(expand("rust", 1, 0, suggest(GRAPH, &mut expanded)).is_empty());
+        assert!(expanded.is_empty());
+    }
+
+    #[test]
+    fn failed_suggestions_are_recorded_and_skipped() {
+        let graph: &[(&str, &[&str])] =
+            &[("rust", &["rust game", "rust lang"]), ("rust lang", &["rust lang book"])];
+        let mut expanded = Vec::new();
+        let expansions = expand("rust", 2, 50, suggest(graph, &mut expanded));
+
+        assert_eq!(
+            paths(&expansions),
+            ["rust", "rust > rust game", "rust > rust lang", "rust > rust lang > rust lang book"]
+        );
+        assert_eq!(expansions[1].error.as_deref(), Some("no suggestions for rust game"));
+        assert!(expansions.iter().enumerate().all(|(i, e)| i == 1 || e.error.is_none()));
+    }
+}
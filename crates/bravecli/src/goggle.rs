@@ -0,0 +1,91 @@
+//! Checks local goggle files, and reads them for the searches that re-rank their results with
+//! them.
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{bail, eyre, Result};
+
+use brave::goggles::{self, Action, Goggle};
+
+#[derive(Debug, Parser)]
+#[command(name = "goggle")]
+#[command(about = "Check goggle files before searching with them")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check every instruction of a goggle file, and show what the goggle does.
+    Lint {
+        /// The goggle file.
+        file: PathBuf,
+    },
+}
+
+pub fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Lint { file } => {
+            let goggle: Goggle = read(&file)?.parse()?;
+            let name = goggle.metadata("name").unwrap_or("Unnamed goggle");
+            let count = |matches: fn(&Action) -> bool| {
+                goggle
+                    .instructions
+                    .iter()
+                    .filter(|instruction| matches(&instruction.action))
+                    .count()
+            };
+            println!(
+                "{}: {name}, {} instructions ({} boost, {} downrank, {} discard)",
+                file.display(),
+                goggle.instructions.len(),
+                count(|action| matches!(action, Action::Boost(_))),
+                count(|action| matches!(action, Action::Downrank(_))),
+                count(|action| matches!(action, Action::Discard)),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a goggle file, and checks it.
+///
+/// # Errors
+///
+/// Will return `Err` if the file can't be read, or has an invalid instruction. The error lists
+/// every invalid line.
+pub fn read(path: &Path) -> Result<String> {
+    let goggle = std::fs::read_to_string(path)
+        .map_err(|err| eyre!("Can't read the goggle {}: {err}", path.display()))?;
+
+    match goggles::lint(&goggle).as_slice() {
+        [] => Ok(goggle),
+        [err] => bail!("The goggle {} is invalid: {err}", path.display()),
+        errors => {
+            let lines: String = errors.iter().map(|err| format!("\n  {err}")).collect();
+            bail!("The goggle {} has {} errors:{lines}", path.display(), errors.len())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_invalid_line_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.goggle");
+        std::fs::write(&path, "! name: Bad\n$boost=11,site=example.com\n$color=red\n/docs/\n")
+            .unwrap();
+
+        let err = read(&path).unwrap_err().to_string();
+
+        assert!(err.contains("has 2 errors"), "{err}");
+        assert!(err.contains("\n  line 2: invalid boost value '11'"), "{err}");
+        assert!(err.contains("\n  line 3: unknown option 'color'"), "{err}");
+    }
+}
@@ -1,11 +1,23 @@
 use clap::{Parser, Subcommand, ValueEnum};
 
+mod auth;
+mod batch;
+mod expand;
+mod goggle;
+mod output;
+mod profile;
 mod search;
 mod suggest;
 mod summarizer;
-mod value_parsers;
+mod tui;
 
-use brave::Brave;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use brave::{cache::Cache, subscription::Feature, Auth, Brave};
+
+use crate::auth::Credentials;
+use crate::profile::Defaults;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = "Brave Web Search API client")]
@@ -21,12 +33,18 @@ pub struct Cli {
     /// 4. Spellcheck.
     /// 5. Suggest.
     ///
-    /// You can also set individual tokens as environment variables for each subscription:
+    /// You can also set individual tokens as environment variables for each subscription, and
+    /// each request is sent with the token of a subscription that covers it:
     ///
-    /// NOTE: This option takes precedence over the following environment variables.
+    /// NOTE: This option takes precedence over the following environment variables, which take
+    /// precedence over the tokens stored with `bravecli auth login`, which take precedence over
+    /// the tokens of the profile.
     ///
     /// Envs:
     ///
+    /// BRAVE_WEB_SEARCH_DATA_FOR_SEARCH_API_KEY
+    ///     Brave Web Search Data for Search API Key (Free, Base, or Pro.)
+    ///
     /// BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY
     ///     Brave Web Search Data for AI API Key (Free AI, Base AI, or Pro AI.)
     ///
@@ -40,23 +58,29 @@ pub struct Cli {
     /// - Summarizer
     ///
     /// BRAVE_SUGGEST_API_KEY
-    ///     Brave Suggest API Key (Free Autosuggest, Autosuggest.)
+    ///     Brave Suggest API Key.
+    ///
+    /// BRAVE_SPELLCHECK_API_KEY
+    ///     Brave Spellcheck API Key (Free Spellcheck, Spellcheck.)
+    ///
+    /// BRAVE_AUTOSUGGEST_API_KEY
+    ///     Brave Autosuggest API Key (Free Autosuggest, Autosuggest.)
     #[clap(long, global = true, verbatim_doc_comment)]
     pub subscription_token: Option<String>,
 
-    /// Hidden variable to handle environment variables without exposing the secret value to stdout.
-    #[clap(
-        long,
-        env = "BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY",
-        global = true,
-        verbatim_doc_comment,
-        hide = true
-    )]
-    pub brave_web_search_data_for_ai_api_key: Option<String>,
+    /// The config file with the profiles.
+    ///
+    /// Defaults to `brave/config.toml` in the XDG config directory, e.g.
+    /// `~/.config/brave/config.toml`.
+    #[clap(long, env = config::PATH_ENV, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
 
-    /// Hidden variable to handle environment variables without exposing the secret value to stdout.
-    #[clap(long, env = "BRAVE_SUGGEST_API_KEY", global = true, verbatim_doc_comment, hide = true)]
-    pub brave_suggest_api_key: Option<String>,
+    /// The profile of the config file to use, for its tokens and defaults.
+    ///
+    /// Defaults to the `default_profile` of the config file, or the profile named `default`.
+    /// Options and environment variables take precedence over the profile.
+    #[clap(long, env = config::PROFILE_ENV, global = true)]
+    pub profile: Option<String>,
 
     /// Log level
     #[clap(long, env = "RUST_LOG", global = true, value_enum)]
@@ -66,6 +90,49 @@ pub struct Cli {
     #[clap(long, global = true)]
     pub verbose: bool,
 
+    /// Records every request to the cassette directory, or replays the recorded ones without
+    /// reaching the network.
+    ///
+    /// Possible values: record, replay
+    ///
+    /// When replaying, the subscription token isn't required.
+    #[clap(long, env = "CASSETTE_MODE", global = true, value_name = "MODE")]
+    pub cassette: Option<cassette::Mode>,
+
+    /// Directory where the cassette interactions are stored.
+    #[clap(long, env = "CASSETTE_DIR", global = true, default_value = cassette::DEFAULT_DIR)]
+    pub cassette_dir: String,
+
+    /// Caches search and suggest responses in this directory, so repeated queries don't reach
+    /// the API.
+    #[clap(long, env = "BRAVE_CACHE_DIR", global = true)]
+    pub cache_dir: Option<String>,
+
+    /// Number of seconds cached responses are served for.
+    #[clap(long, global = true, value_name = "SECS", requires = "cache_dir")]
+    pub cache_ttl: Option<u64>,
+
+    /// Ignores the cached responses, and asks the API for fresh ones. Fresh responses are still
+    /// cached.
+    #[clap(long, global = true, requires = "cache_dir")]
+    pub no_cache: bool,
+
+    /// Number of seconds a request can take, including reading the response.
+    #[clap(long, global = true, value_name = "SECS")]
+    pub http_timeout: Option<u64>,
+
+    /// Number of seconds to wait for a connection to the API.
+    #[clap(long, global = true, value_name = "SECS")]
+    pub connect_timeout: Option<u64>,
+
+    /// Sends every request through this proxy, e.g. `http://proxy:3128`.
+    #[clap(long, env = "BRAVE_PROXY", global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Trusts the root certificates of this PEM file, on top of the Mozilla ones. Can be repeated.
+    #[clap(long, global = true, value_name = "PATH")]
+    pub ca_cert: Vec<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -93,7 +160,7 @@ impl From<Verbose> for log::LevelFilter {
 pub enum Commands {
     /// Query the general web with Brave's search api
     #[clap(name = "search")]
-    Search(crate::search::Cli),
+    Search(Box<crate::search::Cli>),
     /// Query the general web with Brave's summarizer api
     ///
     /// > *NOTE*
@@ -103,6 +170,18 @@ pub enum Commands {
     /// Query to generate potential suggestions for a given query
     #[clap(name = "suggest")]
     Suggest(crate::suggest::Cli),
+    /// Expand a query with its suggestions, and collect the urls found for each of them
+    #[clap(name = "expand")]
+    Expand(crate::expand::Cli),
+    /// Check goggle files before searching with them
+    #[clap(name = "goggle")]
+    Goggle(crate::goggle::Cli),
+    /// Search the web interactively, with autocomplete, results tabs and previews
+    #[clap(name = "tui")]
+    Tui(crate::tui::Cli),
+    /// Store the subscription tokens in the system keyring, or an encrypted file, and check them
+    #[clap(name = "auth")]
+    Auth(crate::auth::Cli),
 }
 
 #[tokio::main]
@@ -130,71 +209,116 @@ fn run() -> color_eyre::eyre::Result<()> {
 
     log::info!("Parsing CLI arguments");
 
+    log::info!("Reading the config");
+    let config = config::Config::load(cli.config.as_deref())?;
+    let profile = config.profile(cli.profile.as_deref())?;
+    let defaults = Defaults::try_from(&profile)?;
+
     log::info!("Getting credentials");
-    let credentials = Credentials {
-        subscription_token: cli.subscription_token.take(),
-        brave_web_search_data_for_ai_api_key: cli.brave_web_search_data_for_ai_api_key.take(),
-        brave_suggest_api_key: cli.brave_suggest_api_key.take(),
+    let cassette = cli.cassette.map(|mode| cassette::Cassette::new(mode, &cli.cassette_dir));
+    let cache = cli.cache_dir.as_ref().map(|dir| {
+        let cache = Cache::new(dir).bypass(cli.no_cache);
+        match cli.cache_ttl {
+            Some(ttl) => cache.default_ttl(Duration::from_secs(ttl)),
+            None => cache,
+        }
+    });
+    let settings = Settings {
+        cassette,
+        cache,
+        timeout: cli.http_timeout.map(Duration::from_secs),
+        connect_timeout: cli.connect_timeout.map(Duration::from_secs),
+        proxy: cli.proxy.take(),
+        ca_certs: std::mem::take(&mut cli.ca_cert),
     };
+    let mut credentials =
+        Credentials::new(config.profile_name(cli.profile.as_deref()).to_string(), profile.tokens);
+    credentials.subscription_token = cli.subscription_token.take();
+    // Only the token of each subscription is read, `--subscription-token` has no variable.
+    credentials.env = Auth::from_env().map(|auth| auth.tokens).unwrap_or_default();
 
     log::info!("Running command");
     match cli.command {
         Commands::Search(cli) => {
-            crate::search::run(cli, get_client(credentials, Subscription::WebSearch)?)
+            let client = get_client(&credentials, &[cli.feature()], settings)?;
+            crate::search::run((*cli).defaults(&defaults), client)
         }
         Commands::Summarizer(cli) => {
-            crate::summarizer::run(cli, get_client(credentials, Subscription::WebSearch)?)
+            let client = get_client(&credentials, &[Feature::Summarizer], settings)?;
+            crate::summarizer::run(cli.defaults(&defaults), client)
         }
         Commands::Suggest(cli) => {
-            crate::suggest::run(cli, get_client(credentials, Subscription::Suggest)?)
+            let client = get_client(&credentials, &[Feature::Suggest], settings)?;
+            crate::suggest::run(cli.defaults(&defaults), client)
+        }
+        Commands::Expand(cli) => {
+            let client =
+                get_client(&credentials, &[Feature::Suggest, Feature::WebSearch], settings)?;
+            crate::expand::run(cli.defaults(&defaults), client)
         }
+        Commands::Goggle(cli) => crate::goggle::run(cli),
+        Commands::Tui(cli) => {
+            // Autocomplete is optional, as the tokens may not cover the Suggest subscription.
+            let features = [Feature::WebSearch, Feature::Suggest];
+            let search = get_client(&credentials, &features, settings)?;
+            let suggest = search.auth.grants(Feature::Suggest).then(|| search.clone());
+            crate::tui::run(cli, search, suggest, defaults)
+        }
+        Commands::Auth(cli) => crate::auth::run(cli, &credentials, &settings),
     }
 }
 
-enum Subscription {
-    Suggest,
-    WebSearch,
-}
-
-struct Credentials {
-    subscription_token: Option<String>,
-    brave_web_search_data_for_ai_api_key: Option<String>,
-    brave_suggest_api_key: Option<String>,
+#[derive(Clone)]
+struct Settings {
+    cassette: Option<cassette::Cassette>,
+    cache: Option<Cache>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    ca_certs: Vec<String>,
 }
 
+/// Creates a client with the tokens of the subscriptions that cover the features of the command,
+/// so each request is sent with the token of a plan that covers it.
 fn get_client(
-    mut credentials: Credentials,
-    subscription: Subscription,
+    credentials: &Credentials,
+    features: &[Feature],
+    settings: Settings,
 ) -> color_eyre::eyre::Result<Brave> {
     log::info!("Creating Brave Client");
 
-    let subscription_token = credentials.subscription_token.take();
-    let brave_web_search_data_for_ai_api_key =
-        credentials.brave_web_search_data_for_ai_api_key.take();
-    let brave_suggest_api_key = credentials.brave_suggest_api_key.take();
-
-    let token = match subscription {
-        Subscription::Suggest => {
-            if let Some(subscription_token) = subscription_token {
-                Ok(subscription_token)
-            } else if let Some(brave_suggest_api_key) = brave_suggest_api_key {
-                Ok(brave_suggest_api_key)
-            } else {
-                Err(color_eyre::eyre::eyre!("No subscription token found"))
-            }
-        }
-        Subscription::WebSearch => {
-            if let Some(subscription_token) = subscription_token {
-                Ok(subscription_token)
-            } else if let Some(brave_web_search_data_for_ai_api_key) =
-                brave_web_search_data_for_ai_api_key
-            {
-                Ok(brave_web_search_data_for_ai_api_key)
-            } else {
-                Err(color_eyre::eyre::eyre!("No subscription token found"))
-            }
-        }
-    }?;
+    // Recorded interactions don't keep the token, so the credential store isn't reached.
+    let replaying = settings.cassette.as_ref().is_some_and(cassette::Cassette::is_replay);
+
+    let auth = if replaying {
+        Auth::new("")
+    } else if let Some(auth) = credentials.auth(features)? {
+        auth
+    } else {
+        return Err(color_eyre::eyre::eyre!(
+            "No subscription token found, set one with `bravecli auth login`"
+        ));
+    };
+
+    build_client(auth, settings)
+}
+
+fn build_client(auth: Auth, settings: Settings) -> color_eyre::eyre::Result<Brave> {
+    let mut builder = Brave::builder(auth, "https://api.search.brave.com/res/v1");
+    if let Some(timeout) = settings.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = settings.connect_timeout {
+        builder = builder.timeout_connect(timeout);
+    }
+    if let Some(proxy) = &settings.proxy {
+        builder = builder.proxy(proxy);
+    }
+    for path in &settings.ca_certs {
+        let pem = std::fs::read(path)
+            .map_err(|err| color_eyre::eyre::eyre!("Can't read the certificate {path}: {err}"))?;
+        builder = builder.root_certificates(pem);
+    }
 
-    Ok(Brave::new(brave::Auth::new(&token), "https://api.search.brave.com/res/v1"))
+    Ok(builder.build()?.cassette(settings.cassette).cache(settings.cache))
 }
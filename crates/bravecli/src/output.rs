@@ -0,0 +1,488 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use color_eyre::eyre::{bail, Result};
+use serde::Serialize;
+
+use brave::{
+    documents::plain_text,
+    filter::host,
+    ranking::{Ranked, RankedItem},
+    DiscussionResult, Json, MetaUrl, NewsResult, VideoResult, WebSearchApiResponse, QA,
+};
+
+/// Maximum number of characters of a cell shown in a table.
+pub const TABLE_CELL_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Jsonl,
+    Table,
+    Markdown,
+    Csv,
+    Urls,
+}
+
+/// A search result of any section, flattened into the columns shared by every format.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub rank: usize,
+    /// The section of the response holding the result, e.g. `web` or `news`.
+    pub r#type: &'static str,
+    pub title: String,
+    pub url: String,
+    pub domain: String,
+    pub age: String,
+    pub description: String,
+    /// The result as returned by the API.
+    pub result: Json,
+}
+
+impl Row {
+    fn new(r#type: &'static str, result: &impl Serialize) -> Self {
+        Self {
+            rank: 0,
+            r#type,
+            title: String::new(),
+            url: String::new(),
+            domain: String::new(),
+            age: String::new(),
+            description: String::new(),
+            result: serde_json::to_value(result).unwrap_or_default(),
+        }
+    }
+
+    fn link(mut self, url: Option<&String>, meta_url: Option<&MetaUrl>) -> Self {
+        self.url = url.cloned().unwrap_or_default();
+        self.domain = meta_url
+            .and_then(|meta_url| meta_url.hostname.clone().or_else(|| meta_url.netloc.clone()))
+            .unwrap_or_else(|| host(&self.url));
+        self
+    }
+
+    fn text(mut self, title: Option<&String>, description: Option<&String>) -> Self {
+        self.title = title.map(|title| plain_text(title)).unwrap_or_default();
+        self.description = description.map(|text| plain_text(text)).unwrap_or_default();
+        self
+    }
+
+    fn age(mut self, age: Option<&String>) -> Self {
+        self.age = age.cloned().unwrap_or_default();
+        self
+    }
+}
+
+impl From<&brave::Result> for Row {
+    fn from(result: &brave::Result) -> Self {
+        Row::new("web", result)
+            .link(result.url.as_ref(), result.meta_url.as_ref())
+            .text(result.title.as_ref(), result.description.as_ref())
+            .age(result.age.as_ref().or(result.page_age.as_ref()))
+    }
+}
+
+impl From<&NewsResult> for Row {
+    fn from(result: &NewsResult) -> Self {
+        Row::new("news", result)
+            .link(result.url.as_ref(), result.meta_url.as_ref())
+            .text(result.title.as_ref(), result.description.as_ref())
+            .age(result.age.as_ref())
+    }
+}
+
+impl From<&VideoResult> for Row {
+    fn from(result: &VideoResult) -> Self {
+        Row::new("videos", result)
+            .link(result.url.as_ref(), result.meta_url.as_ref())
+            .text(result.title.as_ref(), result.description.as_ref())
+            .age(result.age.as_ref())
+    }
+}
+
+impl From<&QA> for Row {
+    fn from(result: &QA) -> Self {
+        Row::new("faq", result)
+            .link(result.url.as_ref(), result.meta_url.as_ref())
+            .text(result.question.as_ref().or(result.title.as_ref()), result.answer.as_ref())
+    }
+}
+
+impl From<&DiscussionResult> for Row {
+    fn from(result: &DiscussionResult) -> Self {
+        Row::new("discussions", result)
+            .link(result.url.as_ref(), None)
+            .text(result.title.as_ref(), result.description.as_ref())
+    }
+}
+
+/// Lists the web, news, video, FAQ and discussion results of a response, following Brave's
+/// preferred ranking when the response has one, or section by section otherwise.
+pub fn rows(response: &WebSearchApiResponse) -> Vec<Row> {
+    let ranked = response.ranked();
+    let rows: Vec<Row> = if ranked.is_empty() {
+        response
+            .web_results()
+            .iter()
+            .map(Row::from)
+            .chain(response.news_results().iter().map(Row::from))
+            .chain(response.video_results().iter().map(Row::from))
+            .chain(response.faq_results().iter().map(Row::from))
+            .chain(response.discussion_results().iter().map(Row::from))
+            .collect()
+    } else {
+        ranked
+            .into_iter()
+            .filter_map(|Ranked { item, .. }| match item {
+                RankedItem::Web(result) => Some(Row::from(result)),
+                RankedItem::News(result) => Some(Row::from(result)),
+                RankedItem::Video(result) => Some(Row::from(result)),
+                RankedItem::Faq(result) => Some(Row::from(result)),
+                RankedItem::Discussion(result) => Some(Row::from(result)),
+                RankedItem::Infobox(_) | RankedItem::Location(_) => None,
+            })
+            .collect()
+    };
+
+    rank(rows)
+}
+
+/// Numbers the rows from 1, in order.
+pub fn rank(rows: Vec<Row>) -> Vec<Row> {
+    rows.into_iter().enumerate().map(|(i, row)| Row { rank: i + 1, ..row }).collect()
+}
+
+/// Prints the rows in the given format. `Format::Json` prints `json` instead, which holds the
+/// whole response, unless fields are selected.
+///
+/// # Errors
+///
+/// Will return `Err` if fields are selected for `Format::Urls`, or stdout can't be written.
+pub fn print(format: Format, json: &impl Serialize, rows: &[Row], fields: &[String]) -> Result<()> {
+    if !fields.is_empty() {
+        return print_fields(format, rows, fields);
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        Format::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(json)?)?,
+        Format::Jsonl => {
+            for row in rows {
+                writeln!(stdout, "{}", serde_json::to_string(&row.result)?)?;
+            }
+        }
+        Format::Table => {
+            let header = ["RANK", "TYPE", "TITLE", "DOMAIN", "AGE"].map(ToString::to_string);
+            let lines = rows.iter().map(|row| {
+                vec![
+                    row.rank.to_string(),
+                    row.r#type.to_string(),
+                    truncate(&row.title, TABLE_CELL_WIDTH),
+                    row.domain.clone(),
+                    row.age.clone(),
+                ]
+            });
+            write!(stdout, "{}", table(header.to_vec(), lines))?;
+        }
+        Format::Markdown => write!(stdout, "{}", markdown(rows))?,
+        Format::Csv => {
+            let header = ["rank", "type", "title", "url", "domain", "age", "description"];
+            let lines = rows.iter().map(|row| {
+                vec![
+                    row.rank.to_string(),
+                    row.r#type.to_string(),
+                    row.title.clone(),
+                    row.url.clone(),
+                    row.domain.clone(),
+                    row.age.clone(),
+                    row.description.clone(),
+                ]
+            });
+            write!(stdout, "{}", csv(header.map(ToString::to_string).to_vec(), lines))?;
+        }
+        Format::Urls => {
+            for row in rows.iter().filter(|row| !row.url.is_empty()) {
+                writeln!(stdout, "{}", row.url)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the selected fields of each row.
+fn print_fields(format: Format, rows: &[Row], fields: &[String]) -> Result<()> {
+    let selections = rows.iter().map(|row| Selection::new(row, fields)).collect::<Vec<_>>();
+    let lines = || selections.iter().map(Selection::cells);
+
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        Format::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(&selections)?)?,
+        Format::Jsonl => {
+            for selection in &selections {
+                writeln!(stdout, "{}", serde_json::to_string(selection)?)?;
+            }
+        }
+        Format::Table => {
+            let header = fields.iter().map(|field| field.to_uppercase()).collect();
+            let lines =
+                lines().map(|cells| cells.iter().map(|c| truncate(c, TABLE_CELL_WIDTH)).collect());
+            write!(stdout, "{}", table(header, lines))?;
+        }
+        Format::Markdown => write!(stdout, "{}", markdown_table(fields, lines()))?,
+        Format::Csv => write!(stdout, "{}", csv(fields.to_vec(), lines()))?,
+        Format::Urls => bail!("--fields can't be combined with --format urls"),
+    }
+
+    Ok(())
+}
+
+/// The selected fields of a row, in order.
+struct Selection<'a>(Vec<(&'a str, Json)>);
+
+impl<'a> Selection<'a> {
+    fn new(row: &Row, fields: &'a [String]) -> Self {
+        Self(fields.iter().map(|field| (field.as_str(), row.field(field))).collect())
+    }
+
+    /// The fields as text: strings as they are, missing fields as empty strings, and any other
+    /// value as JSON.
+    fn cells(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(_, value)| match value {
+                Json::Null => String::new(),
+                Json::String(s) => plain_text(s),
+                value => value.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Serialize for Selection<'_> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(field, value)| (field, value)))
+    }
+}
+
+impl Row {
+    /// Looks up a field of the result as returned by the API, e.g. `page_age`, or a nested one
+    /// like `meta_url.hostname`. `rank` is the rank of the row.
+    fn field(&self, name: &str) -> Json {
+        if name == "rank" {
+            return Json::from(self.rank);
+        }
+        name.split('.')
+            .try_fold(&self.result, |json, key| json.get(key))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+pub fn table(header: Vec<String>, lines: impl Iterator<Item = Vec<String>>) -> String {
+    let lines = std::iter::once(header).chain(lines).collect::<Vec<_>>();
+
+    let mut widths = vec![0; lines[0].len()];
+    for line in &lines {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for line in lines {
+        let cells = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+fn markdown(rows: &[Row]) -> String {
+    let mut markdown = String::new();
+    for row in rows {
+        let title = if row.title.is_empty() { &row.url } else { &row.title };
+        let title = title.replace('[', "\\[").replace(']', "\\]");
+        if row.url.is_empty() {
+            markdown.push_str(&format!("{}. {title}", row.rank));
+        } else {
+            markdown.push_str(&format!("{}. [{title}]({})", row.rank, row.url));
+        }
+
+        let details = [row.domain.as_str(), row.age.as_str()]
+            .into_iter()
+            .filter(|detail| !detail.is_empty())
+            .collect::<Vec<_>>();
+        if !details.is_empty() {
+            markdown.push_str(&format!(" — {}", details.join(", ")));
+        }
+        markdown.push('\n');
+
+        if !row.description.is_empty() {
+            for line in row.description.lines() {
+                markdown.push_str(&format!("   {line}\n"));
+            }
+        }
+    }
+    markdown
+}
+
+pub fn markdown_table(fields: &[String], lines: impl Iterator<Item = Vec<String>>) -> String {
+    let row = |cells: Vec<String>| {
+        let cells = cells.iter().map(|cell| cell.replace('|', "\\|").replace('\n', " "));
+        format!("| {} |\n", cells.collect::<Vec<_>>().join(" | "))
+    };
+
+    let mut markdown = row(fields.to_vec());
+    markdown.push_str(&row(vec!["---".to_string(); fields.len()]));
+    for cells in lines {
+        markdown.push_str(&row(cells));
+    }
+    markdown
+}
+
+pub fn csv(header: Vec<String>, lines: impl Iterator<Item = Vec<String>>) -> String {
+    let mut csv = String::new();
+    for cells in std::iter::once(header).chain(lines) {
+        csv.push_str(&cells.iter().map(|cell| csv_cell(cell)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV cell if it holds a separator, quote or line break, following RFC 4180.
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+pub fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(width - 1).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn row(rank: usize, title: &str, url: &str) -> Row {
+        Row {
+            rank,
+            r#type: "web",
+            title: title.to_string(),
+            url: url.to_string(),
+            domain: host(url),
+            age: String::new(),
+            description: String::new(),
+            result: json!({"title": title, "url": url}),
+        }
+    }
+
+    fn cells(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn tables_align_columns_on_their_widest_cell() {
+        let table = table(
+            cells(&["RANK", "TITLE", "AGE"]),
+            [cells(&["1", "Ünïcode title", ""]), cells(&["10", "Short", "2 days ago"])].into_iter(),
+        );
+
+        assert_eq!(
+            table,
+            "RANK  TITLE          AGE\n\
+             1     Ünïcode title\n\
+             10    Short          2 days ago\n"
+        );
+    }
+
+    #[test]
+    fn csv_cells_are_quoted_following_rfc_4180() {
+        assert_eq!(csv_cell("plain text"), "plain text");
+        assert_eq!(csv_cell("a, b"), "\"a, b\"");
+        assert_eq!(csv_cell("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_cell("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_cell("carriage\rreturn"), "\"carriage\rreturn\"");
+
+        let csv = csv(
+            cells(&["title", "url"]),
+            [cells(&["Rust, \"the\" book", "https://doc.rust-lang.org/book/"])].into_iter(),
+        );
+        assert_eq!(csv, "title,url\n\"Rust, \"\"the\"\" book\",https://doc.rust-lang.org/book/\n");
+    }
+
+    #[test]
+    fn markdown_escapes_link_titles_and_table_cells() {
+        let mut first = row(1, "[Beta] Rust", "https://www.rust-lang.org/");
+        first.age = "2 days ago".to_string();
+        first.description = "A language.\nFast.".to_string();
+        let rows = [first, row(2, "", "https://example.com/"), row(3, "No link", "")];
+
+        assert_eq!(
+            markdown(&rows),
+            "1. [\\[Beta\\] Rust](https://www.rust-lang.org/) — www.rust-lang.org, 2 days ago\n   \
+             A language.\n   \
+             Fast.\n\
+             2. [https://example.com/](https://example.com/) — example.com\n\
+             3. No link\n"
+        );
+
+        let table = markdown_table(
+            &cells(&["title", "description"]),
+            [cells(&["a | b", "two\nlines"])].into_iter(),
+        );
+        assert_eq!(table, "| title | description |\n| --- | --- |\n| a \\| b | two lines |\n");
+    }
+
+    #[test]
+    fn fields_are_looked_up_by_dotted_path() {
+        let mut row = row(3, "Rust", "https://www.rust-lang.org/");
+        row.result = json!({
+            "title": "<strong>Rust</strong> &amp; Cargo",
+            "page_age": "2024-01-02T00:00:00",
+            "meta_url": {"hostname": "www.rust-lang.org", "path": null},
+            "extra_snippets": ["one", "two"],
+            "rank": 99,
+        });
+
+        assert_eq!(row.field("rank"), json!(3));
+        assert_eq!(row.field("page_age"), json!("2024-01-02T00:00:00"));
+        assert_eq!(row.field("meta_url.hostname"), json!("www.rust-lang.org"));
+        assert_eq!(row.field("meta_url"), json!({"hostname": "www.rust-lang.org", "path": null}));
+        assert_eq!(row.field("meta_url.path"), Json::Null);
+        assert_eq!(row.field("meta_url.missing"), Json::Null);
+        assert_eq!(row.field("page_age.year"), Json::Null);
+        assert_eq!(row.field(""), Json::Null);
+
+        let fields = cells(&["rank", "title", "meta_url.path", "extra_snippets"]);
+        let selection = Selection::new(&row, &fields);
+        assert_eq!(selection.cells(), ["3", "Rust & Cargo", "", r#"["one","two"]"#]);
+        assert_eq!(
+            serde_json::to_string(&selection).unwrap(),
+            r#"{"rank":3,"title":"<strong>Rust</strong> &amp; Cargo","meta_url.path":null,"extra_snippets":["one","two"]}"#
+        );
+    }
+
+    #[test]
+    fn long_cells_are_truncated_with_an_ellipsis() {
+        assert_eq!(truncate("short", 5), "short");
+        assert_eq!(truncate("longer", 5), "long…");
+        assert_eq!(truncate("ünïcödé", 4), "ünï…");
+    }
+}
@@ -0,0 +1,51 @@
+//! The defaults of the commands, taken from the selected profile of the config file.
+
+use clap::ValueEnum;
+use color_eyre::eyre::{eyre, Result};
+
+use brave::apis::{CountryCode, LanguageCode, SafeSearch};
+
+use crate::output::Format;
+
+/// The settings of a profile the commands use, validated once.
+#[derive(Debug, Clone, Default)]
+pub struct Defaults {
+    pub country: Option<CountryCode>,
+    pub search_lang: Option<LanguageCode>,
+    pub safesearch: Option<SafeSearch>,
+    pub format: Option<Format>,
+    pub web_search_version: Option<String>,
+    pub suggest_version: Option<String>,
+}
+
+impl TryFrom<&config::Profile> for Defaults {
+    type Error = color_eyre::eyre::Error;
+
+    fn try_from(profile: &config::Profile) -> Result<Self> {
+        Ok(Self {
+            country: parse("country", profile.country.as_deref())?,
+            search_lang: parse("search_lang", profile.search_lang.as_deref())?,
+            safesearch: parse("safesearch", profile.safesearch.as_deref())?,
+            format: profile
+                .format
+                .as_deref()
+                .map(|format| {
+                    Format::from_str(format, true)
+                        .map_err(|err| eyre!("Invalid `format` in the profile: {err}"))
+                })
+                .transpose()?,
+            web_search_version: profile.web_search_version.clone(),
+            suggest_version: profile.suggest_version.clone(),
+        })
+    }
+}
+
+fn parse<T>(name: &str, value: Option<&str>) -> Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .map(|value| value.parse().map_err(|err| eyre!("Invalid `{name}` in the profile: {err}")))
+        .transpose()
+}
@@ -1,7 +1,22 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use clap::Parser;
 use color_eyre::eyre::Result;
 
-use brave::{apis::WebSearchParams, web_search::Api, Brave};
+use brave::{
+    apis::{
+        CountryCode, Freshness, LanguageCode, ResultFilter, SafeSearch, Units, WebSearchParams,
+    },
+    filter::{parse_age, Filter},
+    subscription::Feature,
+    web_search::Api,
+    Brave,
+};
+
+use crate::batch;
+use crate::output::{self, Format, Row};
+use crate::profile::Defaults;
 
 #[derive(Debug, Parser)]
 #[command(name = "search")]
@@ -10,8 +25,44 @@ pub struct Cli {
     /// The user's search query term.
     ///
     /// Maximum 400 characters and 50 words in the query.
-    #[clap(value_parser = crate::value_parsers::q_value_parser)]
-    q: String,
+    #[clap(required_unless_present = "batch")]
+    q: Option<String>,
+
+    /// Run a search for every query of this file, one per line, or stdin when it's `-` or left
+    /// out. The other options are shared by every search.
+    ///
+    /// Prints one JSON line per query, with the `query` and its `response`, or its `error`.
+    #[clap(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        conflicts_with_all = ["q", "all", "fields"]
+    )]
+    batch: Option<PathBuf>,
+
+    /// Append the lines of the batch to this file instead of printing them.
+    ///
+    /// Queries that already have a response in the file are skipped, so an interrupted batch
+    /// can be run again to resume it. Queries that failed are retried.
+    #[clap(long, short, value_name = "FILE", requires = "batch")]
+    output: Option<PathBuf>,
+
+    /// Number of searches of the batch sent at the same time.
+    #[clap(long, default_value_t = 4, requires = "batch")]
+    concurrency: usize,
+
+    /// Maximum number of searches of the batch started per second, e.g. `1` for the free plans.
+    ///
+    /// Searches rejected for exceeding the rate limit of the plan are retried after a while.
+    #[clap(
+        long,
+        value_name = "PER_SEC",
+        default_value_t = 1.0,
+        value_parser = batch::parse_rate_limit,
+        requires = "batch"
+    )]
+    rate_limit: f64,
 
     /// Web Search API Version.
     ///
@@ -26,7 +77,7 @@ pub struct Cli {
     /// of supported values, see [Country
     /// Codes](https://api.search.brave.com/app/documentation/web-search/codes#country-codes)
     #[clap(long)]
-    country: Option<String>,
+    country: Option<CountryCode>,
 
     /// The search language preference.
     ///
@@ -34,7 +85,7 @@ pub struct Cli {
     /// provided. For a list of possible values, see [Language
     /// Codes.](https://api.search.brave.com/app/documentation/web-search/codes#language-codes)
     #[clap(long)]
-    search_lang: Option<String>,
+    search_lang: Option<LanguageCode>,
 
     /// User interface language preferred in response.
     ///
@@ -42,13 +93,13 @@ pub struct Cli {
     /// 9110](https://www.rfc-editor.org/rfc/rfc9110.html#name-accept-language) for a list of
     /// supported values.
     #[clap(long)]
-    ui_lang: Option<String>,
+    ui_lang: Option<LanguageCode>,
 
     /// The number of search results returned in response.
     ///
     /// The maximum is 20. The actual number delivered may be less than requested. Combine this
     /// parameter with `offset` to paginate search results.
-    #[clap(long, value_parser = clap::value_parser!(u16).range(1..21))]
+    #[clap(long)]
     count: Option<u16>,
 
     /// The zero based offset for search results per page (count) to skip before returning the
@@ -57,7 +108,7 @@ pub struct Cli {
     /// example, if your user interface displays 20 search results per page, set `count` to 20 and
     /// `offset` to `0` to show the first page of results. To get subsequent pages, increment
     /// `offset` by `1` (e.g. `0`, `1`, `2`.) The results may overlap across multiple pages.
-    #[clap(long, value_parser = clap::value_parser!(u16).range(1..10))]
+    #[clap(long)]
     offset: Option<u16>,
 
     /// Filters search results for adult content.
@@ -67,8 +118,8 @@ pub struct Cli {
     /// - `off`: Adult content is included where relevant.
     /// - `moderate`: Adult text but no adult images or videos.
     /// - `strict`: No adult content with adult text, images, and videos.
-    #[clap(long, verbatim_doc_comment, value_parser = ["off", "moderate", "strict"])]
-    safesearch: Option<String>,
+    #[clap(long, verbatim_doc_comment)]
+    safesearch: Option<SafeSearch>,
 
     /// Filters search results by when they were discovered.
     ///
@@ -77,9 +128,10 @@ pub struct Cli {
     /// - `pd`: Discovered within the last 24 hours.
     /// - `pw`: Discovered within the last 7 days.
     /// - `pm`: Discovered within the last 31 days.
-    /// - `YYYY-MM-DDtoYYYY-MM-DD`: A timeframe is also supported by specifying the data range (e.g. `2022-04-01-to2024-07-30`)
-    #[clap(long, verbatim_doc_comment, value_parser = crate::value_parsers::freshness_value_parser)]
-    freshness: Option<String>,
+    /// - `py`: Discovered within the last 365 days.
+    /// - `YYYY-MM-DDtoYYYY-MM-DD`: A timeframe is also supported by specifying the data range (e.g. `2022-04-01to2024-07-30`)
+    #[clap(long, verbatim_doc_comment)]
+    freshness: Option<Freshness>,
 
     /// Whether display strings (e.g. result snippets) should include decoration markers (e.g.
     /// highlighting characters.)
@@ -108,14 +160,19 @@ pub struct Cli {
     /// - `web`
     ///
     /// Example results filter values of `discussions,videos` returns only `query`, `discussions`, and `video` responses.
-    #[clap(long, verbatim_doc_comment, value_parser=crate::value_parsers::result_filter_value_parser)]
-    result_filter: Option<String>,
+    #[clap(long, verbatim_doc_comment)]
+    result_filter: Option<ResultFilter>,
 
     /// Goggles act as a custom re-ranking on top of Brave's search index. For more details refer to
     /// the [Goggles repository.](https://github.com/brave/goggles-quickstart)
     #[clap(long)]
     goggles_id: Option<String>,
 
+    /// A local goggle file, sent inline to re-rank the results. The goggle is checked before the
+    /// search, see `bravecli goggle lint`.
+    #[clap(long, value_name = "PATH")]
+    goggle: Option<PathBuf>,
+
     /// The measurement units.
     ///
     /// If not provided, units are derived from search country. Possible values are:
@@ -123,7 +180,7 @@ pub struct Cli {
     /// - `metric`: The standardized measurement system.
     /// - `imperial`: The British Imperial system of units.
     #[clap(long, verbatim_doc_comment)]
-    units: Option<String>,
+    units: Option<Units>,
 
     /// A snippet is an excerpt from a page you get as a result of the query, and `extra_snippets`
     /// allow you to get up to `5` additional, alternative `excerpts`.
@@ -139,36 +196,210 @@ pub struct Cli {
     /// Summarizer version `2023-08-25` is deprecated.
     #[clap(long)]
     summary: bool,
+
+    /// Fetch every available page of web results, starting at `offset`, and print the
+    /// deduplicated list of results.
+    #[clap(long)]
+    all: bool,
+
+    /// The maximum number of web results to fetch when using `--all`.
+    #[clap(long, requires = "all")]
+    limit: Option<usize>,
+
+    /// The output format.
+    ///
+    /// - `json`: The raw search response.
+    /// - `jsonl`: One result per line, as returned by the API.
+    /// - `table`: The rank, type, title, domain and age of each result.
+    /// - `markdown`: A numbered list of linked titles, followed by their descriptions.
+    /// - `csv`: The rank, type, title, url, domain, age and description of each result.
+    /// - `urls`: Just the url of each result, to pipe into other tools.
+    ///
+    /// Every format but `json` lists the web, news, video, FAQ and discussion results, following
+    /// Brave's ranking. Defaults to `json`.
+    #[clap(long, verbatim_doc_comment, value_enum)]
+    format: Option<Format>,
+
+    /// A comma delimited list of the fields to print for each result, e.g. `title,url,page_age`.
+    ///
+    /// Fields are named as in the API response, and nested ones are reached with dots, e.g.
+    /// `meta_url.hostname`. `rank` is the position of the result. Missing fields are left empty.
+    #[clap(long, value_delimiter = ',')]
+    fields: Vec<String>,
+
+    /// Only keep the results of this domain, or its subdomains. Can be repeated.
+    #[clap(long, value_delimiter = ',')]
+    domain: Vec<String>,
+
+    /// Drop the results of this domain, or its subdomains. Can be repeated.
+    #[clap(long, value_delimiter = ',')]
+    exclude_domain: Vec<String>,
+
+    /// Only keep the web results in this language, e.g. `en`.
+    ///
+    /// Unlike `--search-lang`, the results are filtered after they are received.
+    #[clap(long)]
+    lang: Option<String>,
+
+    /// Only keep the web results marked as family friendly.
+    #[clap(long)]
+    family_friendly: bool,
+
+    /// Only keep the web results whose page is at least this old, e.g. `12h`, `7d`, `2w`, `6m`
+    /// or `1y`, based on their `page_age`.
+    #[clap(long, value_parser = parse_age)]
+    min_age: Option<Duration>,
+
+    /// Only keep the web results whose page is at most this old, e.g. `12h`, `7d`, `2w`, `6m` or
+    /// `1y`, based on their `page_age`.
+    #[clap(long, value_parser = parse_age)]
+    max_age: Option<Duration>,
+}
+
+impl Cli {
+    /// Fills in the options that weren't given with the defaults of the profile.
+    pub fn defaults(mut self, defaults: &Defaults) -> Self {
+        self.version = self.version.or_else(|| defaults.web_search_version.clone());
+        self.country = self.country.or_else(|| defaults.country.clone());
+        self.search_lang = self.search_lang.or_else(|| defaults.search_lang.clone());
+        self.safesearch = self.safesearch.or(defaults.safesearch);
+        self.format = self.format.or(defaults.format);
+        self
+    }
+
+    /// The feature the searches use, which decides the token they're sent with.
+    pub fn feature(&self) -> Feature {
+        if self.summary {
+            Feature::Summarizer
+        } else {
+            Feature::WebSearch
+        }
+    }
+
+    /// Takes the client-side filters out of the arguments.
+    fn take_filter(&mut self) -> Filter {
+        let mut filter = Filter::new().family_friendly(self.family_friendly);
+        for domain in self.domain.drain(..) {
+            filter = filter.domain(&domain);
+        }
+        for domain in self.exclude_domain.drain(..) {
+            filter = filter.exclude_domain(&domain);
+        }
+        if let Some(lang) = self.lang.take() {
+            filter = filter.lang(&lang);
+        }
+        if let Some(age) = self.min_age.take() {
+            filter = filter.min_age(age);
+        }
+        if let Some(age) = self.max_age.take() {
+            filter = filter.max_age(age);
+        }
+        filter
+    }
 }
 
-impl From<Cli> for WebSearchParams {
-    fn from(cli: Cli) -> Self {
-        Self {
-            q: cli.q,
-            country: cli.country,
-            search_lang: cli.search_lang,
-            ui_lang: cli.ui_lang,
-            count: cli.count,
-            offset: cli.offset,
-            safesearch: cli.safesearch,
-            freshness: cli.freshness,
-            text_decorations: if cli.text_decorations { Some("1".to_string()) } else { None },
-            spellcheck: if cli.spellcheck { Some("1".to_string()) } else { None },
-            result_filter: cli.result_filter,
-            goggles_id: cli.goggles_id,
-            units: cli.units,
-            extra_snippets: if cli.extra_snippets { Some("1".to_string()) } else { None },
-            summary: if cli.extra_snippets { Some("1".to_string()) } else { None },
+impl Cli {
+    /// The params of the search for `q`.
+    fn params(self, q: &str) -> Result<WebSearchParams> {
+        let mut builder = WebSearchParams::builder(q);
+
+        if let Some(country) = self.country {
+            builder = builder.country(country);
+        }
+        if let Some(search_lang) = self.search_lang {
+            builder = builder.search_lang(search_lang);
+        }
+        if let Some(ui_lang) = self.ui_lang {
+            builder = builder.ui_lang(ui_lang);
+        }
+        if let Some(count) = self.count {
+            builder = builder.count(count);
+        }
+        if let Some(offset) = self.offset {
+            builder = builder.offset(offset);
+        }
+        if let Some(safesearch) = self.safesearch {
+            builder = builder.safesearch(safesearch);
         }
+        if let Some(freshness) = self.freshness {
+            builder = builder.freshness(freshness);
+        }
+        if self.text_decorations {
+            builder = builder.text_decorations(true);
+        }
+        if self.spellcheck {
+            builder = builder.spellcheck(true);
+        }
+        if let Some(result_filter) = self.result_filter {
+            builder = builder.result_filter(result_filter);
+        }
+        if let Some(goggles_id) = self.goggles_id {
+            builder = builder.goggles_id(&goggles_id);
+        }
+        if let Some(path) = self.goggle {
+            builder = builder.goggles(&crate::goggle::read(&path)?);
+        }
+        if let Some(units) = self.units {
+            builder = builder.units(units);
+        }
+        if self.extra_snippets {
+            builder = builder.extra_snippets(true);
+        }
+        if self.summary {
+            builder = builder.summary(true);
+        }
+
+        Ok(builder.build()?)
     }
 }
 
 pub fn run(mut cli: Cli, client: Brave) -> Result<()> {
+    if let Some(path) = cli.batch.take() {
+        return run_batch(cli, &client, &path);
+    }
+
     let version = cli.version.take();
-    let params: WebSearchParams = cli.into();
+    let all = cli.all;
+    let limit = cli.limit.take();
+    let format = cli.format.unwrap_or(Format::Json);
+    let fields = std::mem::take(&mut cli.fields);
+    let filter = cli.take_filter();
+    let q = cli.q.take().unwrap_or_default();
+    let params = cli.params(&q)?;
 
-    let response = client.search(&params, version.as_deref())?;
-    println!("{}", serde_json::to_string_pretty(&response)?);
+    if all {
+        let results = client
+            .search_all(&params, version.as_deref())
+            .filter(|result| result.as_ref().map_or(true, |result| filter.matches(result)))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect::<brave::ApiResult<Vec<_>>>()?;
+        let rows = output::rank(results.iter().map(Row::from).collect());
+        output::print(format, &results, &rows, &fields)?;
+    } else {
+        let mut response = client.search(&params, version.as_deref())?;
+        filter.apply(&mut response);
+        output::print(format, &response, &output::rows(&response), &fields)?;
+    }
 
     Ok(())
 }
+
+fn run_batch(mut cli: Cli, client: &Brave, path: &Path) -> Result<()> {
+    let queries = batch::read_queries(path)?;
+    let Some(first) = queries.first() else {
+        log::warn!("No queries in {}", path.display());
+        return Ok(());
+    };
+
+    let options = batch::Options {
+        output: cli.output.take(),
+        concurrency: cli.concurrency,
+        rate_limit: cli.rate_limit,
+        version: cli.version.take(),
+    };
+    let filter = cli.take_filter();
+    // Every query shares the params, which are checked once with the first query.
+    let template = cli.params(first)?;
+
+    batch::run(client, queries, &template, &filter, &options)
+}
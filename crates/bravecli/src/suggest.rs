@@ -1,7 +1,14 @@
 use clap::Parser;
 use color_eyre::eyre::Result;
 
-use brave::{apis::SuggestSearchParams, suggest::Api, Brave};
+use brave::{
+    apis::{CountryCode, LanguageCode, SuggestSearchParams},
+    error::ValidationError,
+    suggest::Api,
+    Brave,
+};
+
+use crate::profile::Defaults;
 
 #[derive(Debug, Parser)]
 #[command(name = "suggest")]
@@ -10,7 +17,6 @@ pub struct Cli {
     /// The user's search query term.
     ///
     /// Maximum 400 characters and 50 words in the query.
-    #[clap(value_parser = crate::value_parsers::q_value_parser)]
     q: String,
 
     /// Suggest Search API Version.
@@ -26,7 +32,7 @@ pub struct Cli {
     /// of supported values, see [Country
     /// Codes](https://api.search.brave.com/app/documentation/web-search/codes#country-codes)
     #[clap(long)]
-    country: Option<String>,
+    country: Option<CountryCode>,
 
     /// The search language preference.
     ///
@@ -34,10 +40,10 @@ pub struct Cli {
     /// This is just a hint for calculating suggest responses. For a list of complete values, see [Language
     /// Codes.](https://api.search.brave.com/app/documentation/web-search/codes#language-codes)
     #[clap(long)]
-    lang: Option<String>,
+    lang: Option<LanguageCode>,
 
     // The number of suggestions returned. This is done as best effort. The maximum is 20.
-    #[clap(long, value_parser = clap::value_parser!(u16).range(1..20))]
+    #[clap(long)]
     count: Option<u16>,
 
     /// Whether to enhance suggestions with rich results. This is an extra option in plans which
@@ -46,21 +52,42 @@ pub struct Cli {
     rich: bool,
 }
 
-impl From<Cli> for SuggestSearchParams {
-    fn from(cli: Cli) -> Self {
-        Self {
-            q: cli.q,
-            country: cli.country,
-            lang: cli.lang,
-            count: cli.count,
-            rich: if cli.rich { Some("1".to_string()) } else { None },
+impl Cli {
+    /// Fills in the options that weren't given with the defaults of the profile.
+    pub fn defaults(mut self, defaults: &Defaults) -> Self {
+        self.version = self.version.or_else(|| defaults.suggest_version.clone());
+        self.country = self.country.or_else(|| defaults.country.clone());
+        self.lang = self.lang.or_else(|| defaults.search_lang.clone());
+        self
+    }
+}
+
+impl TryFrom<Cli> for SuggestSearchParams {
+    type Error = ValidationError;
+
+    fn try_from(cli: Cli) -> std::result::Result<Self, Self::Error> {
+        let mut builder = SuggestSearchParams::builder(&cli.q);
+
+        if let Some(country) = cli.country {
+            builder = builder.country(country);
+        }
+        if let Some(lang) = cli.lang {
+            builder = builder.lang(lang);
+        }
+        if let Some(count) = cli.count {
+            builder = builder.count(count);
         }
+        if cli.rich {
+            builder = builder.rich(true);
+        }
+
+        builder.build()
     }
 }
 
 pub fn run(mut cli: Cli, client: Brave) -> Result<()> {
     let version = cli.version.take();
-    let params: SuggestSearchParams = cli.into();
+    let params = SuggestSearchParams::try_from(cli)?;
 
     let response = client.suggest(&params, version.as_deref())?;
     println!("{}", serde_json::to_string_pretty(&response)?);
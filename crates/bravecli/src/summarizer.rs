@@ -1,7 +1,19 @@
-use clap::Parser;
+use std::io::Write;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
 use color_eyre::eyre::Result;
 
-use brave::{apis::WebSearchParams, summarizer::Api, Brave};
+use brave::{
+    apis::{
+        CountryCode, Freshness, LanguageCode, ResultFilter, SafeSearch, Units, WebSearchParams,
+    },
+    error::ValidationError,
+    summarizer::{Api, SummaryEvent, DEFAULT_TIMEOUT},
+    Brave, StringOrSummaryEntity,
+};
+
+use crate::{output, profile::Defaults};
 
 #[derive(Debug, Parser)]
 #[command(name = "summarizer", verbatim_doc_comment)]
@@ -13,7 +25,6 @@ pub struct Cli {
     /// The user's search query term.
     ///
     /// Maximum 400 characters and 50 words in the query.
-    #[clap(value_parser = crate::value_parsers::q_value_parser)]
     q: String,
 
     /// Summarizer Search API Version.
@@ -29,7 +40,7 @@ pub struct Cli {
     /// of supported values, see [Country
     /// Codes](https://api.search.brave.com/app/documentation/web-search/codes#country-codes)
     #[clap(long)]
-    country: Option<String>,
+    country: Option<CountryCode>,
 
     /// The search language preference.
     ///
@@ -37,7 +48,7 @@ pub struct Cli {
     /// provided. For a list of possible values, see [Language
     /// Codes.](https://api.search.brave.com/app/documentation/web-search/codes#language-codes)
     #[clap(long = "lang")]
-    search_lang: Option<String>,
+    search_lang: Option<LanguageCode>,
 
     /// User interface language preferred in response.
     ///
@@ -45,13 +56,13 @@ pub struct Cli {
     /// 9110](https://www.rfc-editor.org/rfc/rfc9110.html#name-accept-language) for a list of
     /// supported values.
     #[clap(long = "ui-lang")]
-    ui_lang: Option<String>,
+    ui_lang: Option<LanguageCode>,
 
     /// The number of search results returned in response.
     ///
     /// The maximum is 20. The actual number delivered may be less than requested. Combine this
     /// parameter with `offset` to paginate search results.
-    #[clap(long, value_parser = clap::value_parser!(u16).range(1..20))]
+    #[clap(long)]
     count: Option<u16>,
 
     /// The zero based offset for search results per page (count) to skip before returning the
@@ -60,7 +71,7 @@ pub struct Cli {
     /// example, if your user interface displays 20 search results per page, set `count` to 20 and
     /// `offset` to `0` to show the first page of results. To get subsequent pages, increment
     /// `offset` by `1` (e.g. `0`, `1`, `2`.) The results may overlap across multiple pages.
-    #[clap(long, value_parser = clap::value_parser!(u16).range(1..9))]
+    #[clap(long)]
     offset: Option<u16>,
 
     /// Filters search results for adult content.
@@ -70,8 +81,8 @@ pub struct Cli {
     /// - `off`: Adult content is included where relevant.
     /// - `moderate`: Adult text but no adult images or videos.
     /// - `strict`: No adult content with adult text, images, and videos.
-    #[clap(long, verbatim_doc_comment, value_parser = ["off", "moderate", "strict"])]
-    safesearch: Option<String>,
+    #[clap(long, verbatim_doc_comment)]
+    safesearch: Option<SafeSearch>,
 
     /// Filters search results by when they were discovered.
     ///
@@ -80,9 +91,10 @@ pub struct Cli {
     /// - `pd`: Discovered within the last 24 hours.
     /// - `pw`: Discovered within the last 7 days.
     /// - `pm`: Discovered within the last 31 days.
-    /// - `YYYY-MM-DDtoYYYY-MM-DD`: A timeframe is also supported by specifying the data range (e.g. `2022-04-01-to2024-07-30`)
-    #[clap(long, verbatim_doc_comment, value_parser = crate::value_parsers::freshness_value_parser)]
-    freshness: Option<String>,
+    /// - `py`: Discovered within the last 365 days.
+    /// - `YYYY-MM-DDtoYYYY-MM-DD`: A timeframe is also supported by specifying the data range (e.g. `2022-04-01to2024-07-30`)
+    #[clap(long, verbatim_doc_comment)]
+    freshness: Option<Freshness>,
 
     /// Whether display strings (e.g. result snippets) should include decoration markers (e.g.
     /// highlighting characters.)
@@ -111,8 +123,8 @@ pub struct Cli {
     /// - `web`
     ///
     /// Example results filter values of `discussions,videos` returns only `query`, `discussions`, and `video` responses.
-    #[clap(long, verbatim_doc_comment, value_parser=crate::value_parsers::result_filter_value_parser)]
-    result_filter: Option<String>,
+    #[clap(long, verbatim_doc_comment)]
+    result_filter: Option<ResultFilter>,
 
     /// Goggles act as a custom re-ranking on top of Brave's search index. For more details refer to
     /// the [Goggles repository.](https://github.com/brave/goggles-quickstart)
@@ -126,7 +138,7 @@ pub struct Cli {
     /// - `metric`: The standardized measurement system.
     /// - `imperial`: The British Imperial system of units.
     #[clap(long, verbatim_doc_comment)]
-    units: Option<String>,
+    units: Option<Units>,
 
     /// A snippet is an excerpt from a page you get as a result of the query, and `extra_snippets`
     /// allow you to get up to `5` additional, alternative `excerpts`.
@@ -142,36 +154,144 @@ pub struct Cli {
     /// Summarizer version `2023-08-25` is deprecated.
     #[clap(long)]
     summary: bool,
+
+    /// The output format.
+    ///
+    /// - `json`: The raw summarizer response.
+    /// - `markdown`: The summary rendered as Markdown, with lists, linked entities, and footnote
+    ///   citations built from the summary references.
+    ///
+    /// Defaults to `json`.
+    #[clap(long, verbatim_doc_comment, value_enum)]
+    format: Option<Format>,
+
+    /// Print the summary incrementally as it's being generated, instead of waiting for it to
+    /// complete.
+    #[clap(long, conflicts_with_all = ["format", "timeout"])]
+    stream: bool,
+
+    /// The maximum number of seconds to wait for the summary to complete.
+    #[clap(long, default_value_t = DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Markdown,
+}
+
+impl Cli {
+    /// Fills in the options that weren't given with the defaults of the profile. The format of
+    /// the profile is only used if the summarizer supports it.
+    pub fn defaults(mut self, defaults: &Defaults) -> Self {
+        self.version = self.version.or_else(|| defaults.web_search_version.clone());
+        self.country = self.country.or_else(|| defaults.country.clone());
+        self.search_lang = self.search_lang.or_else(|| defaults.search_lang.clone());
+        self.safesearch = self.safesearch.or(defaults.safesearch);
+        self.format = self.format.or(match defaults.format {
+            Some(output::Format::Json) => Some(Format::Json),
+            Some(output::Format::Markdown) => Some(Format::Markdown),
+            _ => None,
+        });
+        self
+    }
 }
 
-impl From<Cli> for WebSearchParams {
-    fn from(cli: Cli) -> Self {
-        Self {
-            q: cli.q,
-            country: cli.country,
-            search_lang: cli.search_lang,
-            ui_lang: cli.ui_lang,
-            count: cli.count,
-            offset: cli.offset,
-            safesearch: cli.safesearch,
-            freshness: cli.freshness,
-            text_decorations: if cli.text_decorations { Some("1".to_string()) } else { None },
-            spellcheck: if cli.spellcheck { Some("1".to_string()) } else { None },
-            result_filter: cli.result_filter,
-            goggles_id: cli.goggles_id,
-            units: cli.units,
-            extra_snippets: if cli.extra_snippets { Some("1".to_string()) } else { None },
-            summary: Some("1".to_string()),
+impl TryFrom<Cli> for WebSearchParams {
+    type Error = ValidationError;
+
+    fn try_from(cli: Cli) -> std::result::Result<Self, Self::Error> {
+        let mut builder = WebSearchParams::builder(&cli.q);
+
+        if let Some(country) = cli.country {
+            builder = builder.country(country);
+        }
+        if let Some(search_lang) = cli.search_lang {
+            builder = builder.search_lang(search_lang);
+        }
+        if let Some(ui_lang) = cli.ui_lang {
+            builder = builder.ui_lang(ui_lang);
+        }
+        if let Some(count) = cli.count {
+            builder = builder.count(count);
         }
+        if let Some(offset) = cli.offset {
+            builder = builder.offset(offset);
+        }
+        if let Some(safesearch) = cli.safesearch {
+            builder = builder.safesearch(safesearch);
+        }
+        if let Some(freshness) = cli.freshness {
+            builder = builder.freshness(freshness);
+        }
+        if cli.text_decorations {
+            builder = builder.text_decorations(true);
+        }
+        if cli.spellcheck {
+            builder = builder.spellcheck(true);
+        }
+        if let Some(result_filter) = cli.result_filter {
+            builder = builder.result_filter(result_filter);
+        }
+        if let Some(goggles_id) = cli.goggles_id {
+            builder = builder.goggles_id(&goggles_id);
+        }
+        if let Some(units) = cli.units {
+            builder = builder.units(units);
+        }
+        if cli.extra_snippets {
+            builder = builder.extra_snippets(true);
+        }
+        builder = builder.summary(true);
+
+        builder.build()
     }
 }
 
 pub fn run(mut cli: Cli, client: Brave) -> Result<()> {
     let version = cli.version.take();
-    let params: WebSearchParams = cli.into();
+    let format = cli.format.unwrap_or(Format::Json);
+    let stream = cli.stream;
+    let timeout = Duration::from_secs(cli.timeout);
+    let params = WebSearchParams::try_from(cli)?;
 
-    let response = client.summarize(&params, version.as_deref())?;
-    println!("{}", serde_json::to_string_pretty(&response)?);
+    if stream {
+        return print_stream(&client, &params, version.as_deref());
+    }
+
+    let response = client.summarize_with_timeout(&params, version.as_deref(), timeout)?;
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&response)?),
+        Format::Markdown => print!("{}", response.to_markdown()),
+    }
+
+    Ok(())
+}
+
+fn print_stream(client: &Brave, params: &WebSearchParams, version: Option<&str>) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+
+    for event in client.summarize_stream(params, version)? {
+        let SummaryEvent::Message(message) = event? else {
+            continue;
+        };
+
+        match (message.r#type.as_str(), message.data) {
+            ("token", Some(StringOrSummaryEntity::String(text))) => write!(stdout, "{text}")?,
+            ("enum_start" | "enum_end", _) => writeln!(stdout)?,
+            ("enum_item", Some(StringOrSummaryEntity::SummaryEntity(entity))) => {
+                let name = entity.name.unwrap_or_default();
+                match entity.text {
+                    Some(text) => writeln!(stdout, "- {name}: {text}")?,
+                    None => writeln!(stdout, "- {name}")?,
+                }
+            }
+            _ => {}
+        }
+        stdout.flush()?;
+    }
+    writeln!(stdout)?;
 
     Ok(())
 }
@@ -0,0 +1,259 @@
+//! Renders the state of the app into frames of ANSI escape sequences.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::{App, Focus, Tab};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+
+/// Width from which the preview is shown next to the results instead of below them.
+const SIDE_BY_SIDE_WIDTH: usize = 100;
+
+/// Renders the app on a screen of the given size.
+pub fn frame(app: &App, (width, height): (usize, usize)) -> String {
+    let mut lines = Vec::with_capacity(height);
+
+    let status = if app.busy.is_empty() {
+        app.status.clone()
+    } else {
+        format!("{}…", app.busy.join(", "))
+    };
+    lines.push(format!("{REVERSE}{BOLD}{}{RESET}", fit(&format!(" bravecli  {status}"), width)));
+
+    let prompt = " Search: ";
+    let (query, _) = input_window(&app.query, app.cursor, width.saturating_sub(prompt.len()));
+    lines.push(fit(&format!("{prompt}{query}"), width));
+    lines.push(tabs(app, width));
+
+    let body = height.saturating_sub(lines.len() + 1);
+    let (results, preview) = if width >= SIDE_BY_SIDE_WIDTH {
+        let list_width = width * 45 / 100;
+        let results = results(app, list_width, body);
+        let preview = preview(app, width - list_width - 1, body);
+        let rows = results
+            .into_iter()
+            .zip(preview)
+            .map(|(left, right)| format!("{left}{DIM}│{RESET}{right}"))
+            .collect();
+        (rows, Vec::new())
+    } else {
+        let list_height = body / 2;
+        let mut rows = results(app, width, list_height);
+        rows.push(format!("{DIM}{}{RESET}", "─".repeat(width)));
+        (rows, preview(app, width, body.saturating_sub(list_height + 1)))
+    };
+    lines.extend(results);
+    lines.extend(preview);
+
+    if app.focus == Focus::Input && !app.suggestions.is_empty() {
+        overlay_suggestions(app, &mut lines, width);
+    }
+
+    lines.truncate(height.saturating_sub(1));
+    while lines.len() < height.saturating_sub(1) {
+        lines.push(" ".repeat(width));
+    }
+    lines.push(format!("{DIM}{}{RESET}", fit(help(app.focus), width)));
+
+    let mut frame = String::new();
+    for (row, line) in lines.iter().enumerate() {
+        frame.push_str(&format!("\x1b[{};1H{line}", row + 1));
+    }
+    if app.focus == Focus::Input {
+        let (_, cursor) = input_window(&app.query, app.cursor, width.saturating_sub(prompt.len()));
+        frame.push_str(&format!("\x1b[2;{}H\x1b[?25h", prompt.len() + cursor + 1));
+    } else {
+        frame.push_str("\x1b[?25l");
+    }
+    frame
+}
+
+fn tabs(app: &App, width: usize) -> String {
+    let mut line = String::from(" ");
+    let mut used = 1;
+    for tab in Tab::ALL {
+        let label = format!(" {} ({}) ", tab.label(), app.rows(tab).len());
+        used += label.width() + 1;
+        if tab == app.tab {
+            line.push_str(&format!("{REVERSE}{label}{RESET} "));
+        } else {
+            line.push_str(&format!("{label} "));
+        }
+    }
+    line.push_str(&" ".repeat(width.saturating_sub(used)));
+    line
+}
+
+fn results(app: &App, width: usize, height: usize) -> Vec<String> {
+    let rows = app.rows(app.tab);
+    let selected = app.selected[app.tab.index()];
+    if rows.is_empty() {
+        let message = if app.searched.is_empty() { "" } else { " No results" };
+        return pad_lines(vec![fit(message, width)], width, height);
+    }
+
+    // Each result takes two lines: its title, and its domain and age.
+    let visible = (height / 2).max(1);
+    let first = selected.saturating_sub(visible - 1);
+    let mut lines = Vec::new();
+    for (i, row) in rows.iter().enumerate().skip(first).take(visible) {
+        let title = format!(" {:>2}. {}", row.rank, row.title);
+        let details = [row.domain.as_str(), row.age.as_str()]
+            .into_iter()
+            .filter(|detail| !detail.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ");
+        let details = format!("     {details}");
+
+        if i == selected && app.focus == Focus::Results {
+            lines.push(format!("{REVERSE}{}{RESET}", fit(&title, width)));
+            lines.push(format!("{REVERSE}{DIM}{}{RESET}", fit(&details, width)));
+        } else if i == selected {
+            lines.push(format!("{BOLD}{}{RESET}", fit(&title, width)));
+            lines.push(format!("{DIM}{}{RESET}", fit(&details, width)));
+        } else {
+            lines.push(fit(&title, width));
+            lines.push(format!("{DIM}{}{RESET}", fit(&details, width)));
+        }
+    }
+    pad_lines(lines, width, height)
+}
+
+fn preview(app: &App, width: usize, height: usize) -> Vec<String> {
+    let Some(preview) = &app.preview else {
+        return pad_lines(Vec::new(), width, height);
+    };
+
+    let mut lines = vec![format!("{BOLD}{}{RESET}", fit(&format!(" {}", preview.title), width))];
+    let text = wrap(&preview.text, width.saturating_sub(2));
+    let scroll = preview.scroll.min(text.len().saturating_sub(1));
+    lines.extend(
+        text.iter()
+            .skip(scroll)
+            .take(height.saturating_sub(1))
+            .map(|line| fit(&format!(" {line}"), width)),
+    );
+    pad_lines(lines, width, height)
+}
+
+/// Draws the suggestions as a dropdown below the search box.
+fn overlay_suggestions(app: &App, lines: &mut [String], width: usize) {
+    let dropdown_width = app
+        .suggestions
+        .iter()
+        .map(|suggestion| suggestion.width() + 2)
+        .max()
+        .unwrap_or_default()
+        .clamp(20, 60)
+        .min(width.saturating_sub(9));
+    let first_line = 2;
+    for (i, suggestion) in app.suggestions.iter().enumerate() {
+        let Some(line) = lines.get_mut(first_line + i) else {
+            break;
+        };
+        let cell = fit(&format!(" {suggestion}"), dropdown_width);
+        let style = if app.suggestion == Some(i) { REVERSE } else { "" };
+        *line = format!(
+            "{}{style}{DIM}▏{RESET}{style}{cell}{RESET}{}",
+            " ".repeat(8),
+            " ".repeat(width.saturating_sub(dropdown_width + 9))
+        );
+    }
+}
+
+fn help(focus: Focus) -> &'static str {
+    match focus {
+        Focus::Input => {
+            " enter search · ↑↓ suggestions · tab complete · esc results · ctrl-c quit"
+        }
+        Focus::Results => {
+            " ↑↓ move · ←→ tabs · enter page · s summary · o open · y copy url · pgup/pgdn scroll · / search · q quit"
+        }
+    }
+}
+
+/// The part of the query that fits in the search box, and the position of the cursor in it.
+fn input_window(query: &str, cursor: usize, width: usize) -> (String, usize) {
+    let chars = query.chars().collect::<Vec<_>>();
+    let width = width.max(1);
+    let first = cursor.saturating_sub(width - 1);
+    let visible = chars.iter().skip(first).take(width).collect::<String>();
+    let cursor = chars[first..cursor.min(chars.len())].iter().collect::<String>().width();
+    (visible, cursor)
+}
+
+fn pad_lines(mut lines: Vec<String>, width: usize, height: usize) -> Vec<String> {
+    lines.truncate(height);
+    lines.resize(height, " ".repeat(width));
+    lines
+}
+
+/// Truncates or pads the text to exactly `width` columns. Control characters are replaced by
+/// spaces.
+fn fit(text: &str, width: usize) -> String {
+    let text = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect::<String>();
+    if text.width() <= width {
+        let padding = width - text.width();
+        return text + &" ".repeat(padding);
+    }
+
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or_default();
+        if used + c_width >= width {
+            break;
+        }
+        fitted.push(c);
+        used += c_width;
+    }
+    if width > 0 {
+        fitted.push('…');
+        used += 1;
+    }
+
+    fitted + &" ".repeat(width.saturating_sub(used))
+}
+
+/// Wraps the text on word boundaries to lines of at most `width` columns.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let word_width = word.width();
+            if used > 0 && used + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            if used > 0 {
+                line.push(' ');
+                used += 1;
+            }
+            if word_width > width {
+                // Splits words longer than a line, like long urls.
+                for c in word.chars() {
+                    let c_width = c.width().unwrap_or_default();
+                    if used + c_width > width {
+                        lines.push(std::mem::take(&mut line));
+                        used = 0;
+                    }
+                    line.push(c);
+                    used += c_width;
+                }
+            } else {
+                line.push_str(word);
+                used += word_width;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
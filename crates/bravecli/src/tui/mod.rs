@@ -0,0 +1,664 @@
+//! An interactive terminal UI to search the web: the query is autocompleted while typing, the
+//! results are split in tabs by type, and the selected result can be previewed, summarized,
+//! opened in the browser or have its url copied.
+
+mod draw;
+mod terminal;
+
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use color_eyre::eyre::Result;
+
+use brave::{
+    apis::{SuggestSearchParamsBuilder, WebSearchParamsBuilder},
+    suggest::Api as _,
+    summarizer::Api as _,
+    web_search::Api as _,
+    Brave, SuggestSearchParams, WebSearchApiResponse, WebSearchParams,
+};
+
+use crate::output::{self, Row};
+use crate::profile::Defaults;
+use terminal::{Key, Terminal};
+
+/// Time to wait after the last key press before asking for suggestions.
+const SUGGEST_DELAY: Duration = Duration::from_millis(250);
+/// Time between redraws while waiting for events, to follow terminal resizes.
+const TICK: Duration = Duration::from_millis(250);
+/// Maximum number of suggestions shown under the search box.
+const MAX_SUGGESTIONS: usize = 8;
+/// Number of lines scrolled by page up and page down.
+const SCROLL: usize = 10;
+/// Width the fetched pages are rendered to.
+const PAGE_WIDTH: usize = 100;
+
+#[derive(Debug, Parser)]
+#[command(name = "tui")]
+#[command(about = "Search the web interactively")]
+pub struct Cli {
+    /// The query to search for on start.
+    q: Option<String>,
+
+    /// The number of web results to ask for. The maximum is 20.
+    #[clap(long)]
+    count: Option<u16>,
+
+    /// The maximum number of seconds to wait for a summary to complete.
+    #[clap(long, default_value_t = 30)]
+    summary_timeout: u64,
+}
+
+/// The tabs of the results pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Web,
+    News,
+    Videos,
+    Discussions,
+}
+
+impl Tab {
+    const ALL: [Tab; 4] = [Tab::Web, Tab::News, Tab::Videos, Tab::Discussions];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Tab::Web => "Web",
+            Tab::News => "News",
+            Tab::Videos => "Videos",
+            Tab::Discussions => "Discussions",
+        }
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Where the keys go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Input,
+    Results,
+}
+
+/// Something that happened while the app was waiting, tagged with the request that caused it.
+#[derive(Debug)]
+pub enum Event {
+    Key(Key),
+    Suggestions(u64, brave::ApiResult<Vec<String>>),
+    Results(u64, String, brave::ApiResult<Box<WebSearchApiResponse>>),
+    Preview(u64, Preview, &'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct Preview {
+    title: String,
+    text: String,
+    scroll: usize,
+}
+
+struct App {
+    search: Brave,
+    suggest: Option<Brave>,
+    summary_timeout: Duration,
+    count: Option<u16>,
+    defaults: Defaults,
+    sender: Sender<Event>,
+
+    query: String,
+    /// Position of the cursor in the query, in characters.
+    cursor: usize,
+    focus: Focus,
+    suggestions: Vec<String>,
+    suggestion: Option<usize>,
+    /// When to ask for suggestions, once the user stops typing.
+    suggest_at: Option<Instant>,
+
+    /// The query of the results shown.
+    searched: String,
+    tab: Tab,
+    rows: [Vec<Row>; 4],
+    selected: [usize; 4],
+    preview: Option<Preview>,
+
+    status: String,
+    /// Requests in flight, e.g. `searching`.
+    busy: Vec<&'static str>,
+    /// Id of the last request of each kind, to ignore the responses of older ones.
+    suggest_id: u64,
+    search_id: u64,
+    preview_id: u64,
+    quit: bool,
+}
+
+/// Runs the TUI until the user quits. Suggestions are disabled without a suggest client.
+pub fn run(cli: Cli, search: Brave, suggest: Option<Brave>, defaults: Defaults) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut app = App {
+        search,
+        summary_timeout: Duration::from_secs(cli.summary_timeout),
+        count: cli.count,
+        defaults,
+        sender: sender.clone(),
+        query: String::new(),
+        cursor: 0,
+        focus: Focus::Input,
+        suggestions: Vec::new(),
+        suggestion: None,
+        suggest_at: None,
+        searched: String::new(),
+        tab: Tab::Web,
+        rows: Default::default(),
+        selected: [0; 4],
+        preview: None,
+        status: if suggest.is_some() {
+            String::new()
+        } else {
+            "Suggestions need a Suggest subscription token".to_string()
+        },
+        suggest,
+        busy: Vec::new(),
+        suggest_id: 0,
+        search_id: 0,
+        preview_id: 0,
+        quit: false,
+    };
+    if let Some(q) = cli.q {
+        app.cursor = q.chars().count();
+        app.query = q;
+        app.run_search();
+    }
+
+    let mut terminal = Terminal::enter()?;
+    terminal::read_keys(sender);
+
+    while !app.quit {
+        terminal.draw(&draw::frame(&app, terminal.size()))?;
+
+        let timeout = app
+            .suggest_at
+            .map_or(TICK, |at| at.saturating_duration_since(Instant::now()).min(TICK));
+        match receiver.recv_timeout(timeout) {
+            Ok(event) => app.handle(event),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if app.suggest_at.is_some_and(|at| at <= Instant::now()) {
+            app.suggest_at = None;
+            app.run_suggest();
+        }
+    }
+
+    Ok(())
+}
+
+impl App {
+    fn rows(&self, tab: Tab) -> &[Row] {
+        &self.rows[tab.index()]
+    }
+
+    fn selected_row(&self) -> Option<&Row> {
+        self.rows(self.tab).get(self.selected[self.tab.index()])
+    }
+
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::Key(Key::Ctrl('c')) => self.quit = true,
+            Event::Key(key) => match self.focus {
+                Focus::Input => self.input_key(key),
+                Focus::Results => self.results_key(key),
+            },
+            Event::Suggestions(id, suggestions) => {
+                self.done("suggesting");
+                if id != self.suggest_id {
+                    return;
+                }
+                match suggestions {
+                    Ok(suggestions) => {
+                        self.suggestions = suggestions;
+                        self.suggestion = None;
+                    }
+                    Err(err) => self.status = format!("Suggestions failed: {err}"),
+                }
+            }
+            Event::Results(id, query, response) => {
+                self.done("searching");
+                if id != self.search_id {
+                    return;
+                }
+                match response {
+                    Ok(response) => self.show_results(query, &response),
+                    Err(err) => self.status = format!("Search failed: {err}"),
+                }
+            }
+            Event::Preview(id, preview, task) => {
+                self.done(task);
+                if id == self.preview_id {
+                    self.preview = Some(preview);
+                }
+            }
+        }
+    }
+
+    fn input_key(&mut self, key: Key) {
+        match key {
+            Key::Char(c) => {
+                let at = self.byte_index(self.cursor);
+                self.query.insert(at, c);
+                self.cursor += 1;
+                self.edited();
+            }
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.query.remove(self.byte_index(self.cursor));
+                self.edited();
+            }
+            Key::Delete if self.cursor < self.query.chars().count() => {
+                self.query.remove(self.byte_index(self.cursor));
+                self.edited();
+            }
+            Key::Ctrl('u') => {
+                self.query.clear();
+                self.cursor = 0;
+                self.edited();
+            }
+            Key::Left | Key::Ctrl('b') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right | Key::Ctrl('f') => {
+                self.cursor = (self.cursor + 1).min(self.query.chars().count());
+            }
+            Key::Home | Key::Ctrl('a') => self.cursor = 0,
+            Key::End | Key::Ctrl('e') => self.cursor = self.query.chars().count(),
+            Key::Down if !self.suggestions.is_empty() => {
+                let last = self.suggestions.len() - 1;
+                self.suggestion = Some(self.suggestion.map_or(0, |i| (i + 1).min(last)));
+            }
+            Key::Up => self.suggestion = self.suggestion.and_then(|i| i.checked_sub(1)),
+            Key::Tab => {
+                let suggestion = self.suggestion.or((!self.suggestions.is_empty()).then_some(0));
+                if let Some(suggestion) = suggestion.and_then(|i| self.suggestions.get(i)) {
+                    self.query = suggestion.clone();
+                    self.cursor = self.query.chars().count();
+                    self.edited();
+                }
+            }
+            Key::Enter => {
+                if let Some(suggestion) = self.suggestion.and_then(|i| self.suggestions.get(i)) {
+                    self.query = suggestion.clone();
+                    self.cursor = self.query.chars().count();
+                }
+                self.run_search();
+            }
+            Key::Esc if !self.suggestions.is_empty() => self.clear_suggestions(),
+            Key::Esc if !self.searched.is_empty() => self.focus = Focus::Results,
+            Key::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    fn results_key(&mut self, key: Key) {
+        let selected = &mut self.selected[self.tab.index()];
+        let last = self.rows[self.tab.index()].len().saturating_sub(1);
+        match key {
+            Key::Char('q') | Key::Esc => self.quit = true,
+            Key::Char('/' | 'i') => self.focus = Focus::Input,
+            Key::Down | Key::Char('j') if *selected < last => {
+                *selected += 1;
+                self.describe();
+            }
+            Key::Up | Key::Char('k') if *selected > 0 => {
+                *selected -= 1;
+                self.describe();
+            }
+            Key::Home | Key::Char('g') => {
+                *selected = 0;
+                self.describe();
+            }
+            Key::End | Key::Char('G') => {
+                *selected = last;
+                self.describe();
+            }
+            Key::Right | Key::Tab | Key::Char('l') => {
+                self.tab = self.tab.next();
+                self.describe();
+            }
+            Key::Left | Key::BackTab | Key::Char('h') => {
+                self.tab = self.tab.previous();
+                self.describe();
+            }
+            Key::Char(c @ '1'..='4') => {
+                self.tab = Tab::ALL[usize::from(c as u8 - b'1')];
+                self.describe();
+            }
+            Key::PageDown | Key::Ctrl('d') | Key::Char(' ') => self.scroll(true),
+            Key::PageUp | Key::Ctrl('u') => self.scroll(false),
+            Key::Enter | Key::Char('p') => self.fetch_page(),
+            Key::Char('s') => self.summarize(),
+            Key::Char('o') => self.open(),
+            Key::Char('y' | 'c') => self.copy(),
+            _ => {}
+        }
+    }
+
+    /// Schedules suggestions for the new query.
+    fn edited(&mut self) {
+        self.suggestion = None;
+        if self.suggest.is_some() && !self.query.trim().is_empty() {
+            self.suggest_at = Some(Instant::now() + SUGGEST_DELAY);
+        } else {
+            self.clear_suggestions();
+        }
+    }
+
+    fn clear_suggestions(&mut self) {
+        self.suggestions.clear();
+        self.suggestion = None;
+        self.suggest_at = None;
+        // Ignores the suggestions in flight.
+        self.suggest_id += 1;
+    }
+
+    fn run_suggest(&mut self) {
+        let Some(client) = self.suggest.clone() else {
+            return;
+        };
+        self.suggest_id += 1;
+        self.busy("suggesting");
+
+        let (id, sender) = (self.suggest_id, self.sender.clone());
+        let params = self.suggest_params(&self.query).build();
+        let version = self.defaults.suggest_version.clone();
+        std::thread::spawn(move || {
+            let suggestions = params
+                .map_err(Into::into)
+                .and_then(|params| client.suggest(&params, version.as_deref()))
+                .map(|r| {
+                    r.queries().into_iter().take(MAX_SUGGESTIONS).map(ToString::to_string).collect()
+                });
+            let _ = sender.send(Event::Suggestions(id, suggestions));
+        });
+    }
+
+    fn run_search(&mut self) {
+        let query = self.query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        self.clear_suggestions();
+        self.search_id += 1;
+        self.busy("searching");
+
+        let (id, client, sender) = (self.search_id, self.search.clone(), self.sender.clone());
+        let mut builder = self.search_params(&query);
+        if let Some(count) = self.count {
+            builder = builder.count(count);
+        }
+        let params = builder.build();
+        let version = self.defaults.web_search_version.clone();
+        std::thread::spawn(move || {
+            let response = params
+                .map_err(Into::into)
+                .and_then(|params| client.search(&params, version.as_deref()))
+                .map(Box::new);
+            let _ = sender.send(Event::Results(id, query, response));
+        });
+    }
+
+    /// The params of a search for the query, with the defaults of the profile.
+    fn search_params(&self, query: &str) -> WebSearchParamsBuilder {
+        let mut builder = WebSearchParams::builder(query);
+        if let Some(country) = &self.defaults.country {
+            builder = builder.country(country.clone());
+        }
+        if let Some(search_lang) = &self.defaults.search_lang {
+            builder = builder.search_lang(search_lang.clone());
+        }
+        if let Some(safesearch) = self.defaults.safesearch {
+            builder = builder.safesearch(safesearch);
+        }
+        builder
+    }
+
+    /// The params of the suggestions for the query, with the defaults of the profile.
+    fn suggest_params(&self, query: &str) -> SuggestSearchParamsBuilder {
+        let mut builder = SuggestSearchParams::builder(query);
+        if let Some(country) = &self.defaults.country {
+            builder = builder.country(country.clone());
+        }
+        if let Some(lang) = &self.defaults.search_lang {
+            builder = builder.lang(lang.clone());
+        }
+        builder
+    }
+
+    fn show_results(&mut self, query: String, response: &WebSearchApiResponse) {
+        let sections = [
+            response.web_results().iter().map(Row::from).collect(),
+            response.news_results().iter().map(Row::from).collect(),
+            response.video_results().iter().map(Row::from).collect(),
+            response.discussion_results().iter().map(Row::from).collect(),
+        ];
+        self.rows = sections.map(output::rank);
+        self.selected = [0; 4];
+        self.tab = Tab::ALL.into_iter().find(|tab| !self.rows(*tab).is_empty()).unwrap_or(Tab::Web);
+        self.status = format!("Results for \"{query}\"");
+        self.searched = query;
+        self.focus = Focus::Results;
+        self.describe();
+    }
+
+    /// Previews the description of the selected result.
+    fn describe(&mut self) {
+        // Ignores the previews in flight for the previous selection.
+        self.preview_id += 1;
+        self.preview = self.selected_row().map(|row| Preview {
+            title: row.title.clone(),
+            text: [row.url.as_str(), row.age.as_str(), "", row.description.as_str()].join("\n"),
+            scroll: 0,
+        });
+    }
+
+    fn scroll(&mut self, down: bool) {
+        if let Some(preview) = self.preview.as_mut() {
+            preview.scroll =
+                if down { preview.scroll + SCROLL } else { preview.scroll.saturating_sub(SCROLL) };
+        }
+    }
+
+    /// Previews the text of the page of the selected result.
+    fn fetch_page(&mut self) {
+        let Some((title, url)) = self
+            .selected_row()
+            .filter(|row| !row.url.is_empty())
+            .map(|row| (row.title.clone(), row.url.clone()))
+        else {
+            return;
+        };
+        self.preview_id += 1;
+        self.busy("fetching page");
+
+        let (id, client, sender) = (self.preview_id, self.search.clone(), self.sender.clone());
+        std::thread::spawn(move || {
+            let text = fetch(&client, &url)
+                .and_then(|html| {
+                    html2text::config::plain()
+                        .string_from_read(html.as_bytes(), PAGE_WIDTH)
+                        .map_err(|err| err.to_string())
+                })
+                .unwrap_or_else(|err| format!("Can't fetch {url}: {err}"));
+            let _ = sender.send(Event::Preview(
+                id,
+                Preview { title, text, scroll: 0 },
+                "fetching page",
+            ));
+        });
+    }
+
+    /// Previews the summary of the results.
+    fn summarize(&mut self) {
+        if self.searched.is_empty() {
+            return;
+        }
+        self.preview_id += 1;
+        self.busy("summarizing");
+
+        let (id, client, sender) = (self.preview_id, self.search.clone(), self.sender.clone());
+        let (query, timeout) = (self.searched.clone(), self.summary_timeout);
+        let params = self.search_params(&query).build();
+        let version = self.defaults.web_search_version.clone();
+        std::thread::spawn(move || {
+            let text = params
+                .map_err(Into::into)
+                .and_then(|params| {
+                    client.summarize_with_timeout(&params, version.as_deref(), timeout)
+                })
+                .map_or_else(
+                    |err| format!("Can't summarize: {err}"),
+                    |response| response.to_markdown(),
+                );
+            let preview = Preview { title: format!("Summary of \"{query}\""), text, scroll: 0 };
+            let _ = sender.send(Event::Preview(id, preview, "summarizing"));
+        });
+    }
+
+    fn open(&mut self) {
+        let Some(url) = self.selected_row().map(|row| row.url.clone()) else {
+            return;
+        };
+        self.status = match open_in_browser(&url) {
+            Ok(()) => format!("Opened {url}"),
+            Err(err) => format!("Can't open {url}: {err}"),
+        };
+    }
+
+    /// Copies the url of the selected result with an OSC 52 escape sequence, which most
+    /// terminals support, even over SSH.
+    fn copy(&mut self) {
+        let Some(url) = self.selected_row().map(|row| row.url.clone()) else {
+            return;
+        };
+        let sequence = format!("\x1b]52;c;{}\x07", base64(url.as_bytes()));
+        self.status = match std::io::Write::write_all(&mut std::io::stdout(), sequence.as_bytes()) {
+            Ok(()) => format!("Copied {url}"),
+            Err(err) => format!("Can't copy {url}: {err}"),
+        };
+    }
+
+    fn busy(&mut self, task: &'static str) {
+        self.busy.push(task);
+    }
+
+    fn done(&mut self, task: &'static str) {
+        if let Some(i) = self.busy.iter().position(|busy| *busy == task) {
+            self.busy.remove(i);
+        }
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.query.char_indices().nth(cursor).map_or(self.query.len(), |(i, _)| i)
+    }
+}
+
+/// Fetches the html of a page with the agent of the client, recording it to or replaying it from
+/// the cassette of the client like the API requests.
+fn fetch(client: &Brave, url: &str) -> std::result::Result<String, String> {
+    let request = cassette::Request::new("GET", url, None);
+    let cassette = client.cassette.as_ref();
+    if let Some(cassette) = cassette.filter(|cassette| cassette.is_replay()) {
+        return cassette
+            .load(&request)
+            .map(|response| response.body)
+            .map_err(|err| err.to_string());
+    }
+
+    let html = client
+        .agent()
+        .get(url)
+        .call()
+        .map_err(|err| err.to_string())?
+        .into_string()
+        .map_err(|err| err.to_string())?;
+    if let Some(cassette) = cassette {
+        cassette
+            .save(&request, &cassette::Response::new(200, &html))
+            .map_err(|err| err.to_string())?;
+    }
+
+    Ok(html)
+}
+
+/// Opens the url with `$BROWSER`, or the default browser of the system.
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let opener = std::env::var("BROWSER").ok().filter(|browser| !browser.is_empty());
+    let opener =
+        opener.as_deref().unwrap_or(if cfg!(target_os = "macos") { "open" } else { "xdg-open" });
+
+    std::process::Command::new(opener)
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 63]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use brave::Auth;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+        assert_eq!(base64(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    }
+
+    #[test]
+    fn pages_are_replayed_from_the_cassette() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://example.invalid/page";
+        let recorder = cassette::Cassette::new(cassette::Mode::Record, dir.path());
+        let request = cassette::Request::new("GET", url, None);
+        recorder.save(&request, &cassette::Response::new(200, "<p>Recorded</p>")).unwrap();
+
+        let replayer = cassette::Cassette::new(cassette::Mode::Replay, dir.path());
+        let client = Brave::new(Auth::new("token"), "https://api.invalid").cassette(Some(replayer));
+        assert_eq!(fetch(&client, url).unwrap(), "<p>Recorded</p>");
+
+        let err = fetch(&client, "https://example.invalid/other").unwrap_err();
+        assert!(err.contains("https://example.invalid/other"), "{err}");
+    }
+}
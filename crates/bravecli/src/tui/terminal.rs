@@ -0,0 +1,213 @@
+//! Raw mode terminal handling, and decoding of the keys read from stdin.
+
+use std::io::{Read, Write};
+use std::sync::mpsc::Sender;
+
+use color_eyre::eyre::Result;
+use rustix::termios::{self, OptionalActions, Termios};
+
+use super::Event;
+
+/// A key pressed by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Esc,
+    Backspace,
+    Delete,
+    Tab,
+    BackTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+/// The terminal in raw mode, showing the alternate screen. The original mode and screen are
+/// restored when it's dropped.
+pub struct Terminal {
+    original: Termios,
+    stdout: std::io::Stdout,
+}
+
+impl Terminal {
+    pub fn enter() -> Result<Self> {
+        let stdin = rustix::stdio::stdin();
+        let original = termios::tcgetattr(stdin)?;
+        let mut raw = original.clone();
+        raw.make_raw();
+        termios::tcsetattr(stdin, OptionalActions::Flush, &raw)?;
+
+        let mut stdout = std::io::stdout();
+        // Alternate screen, hidden cursor.
+        write!(stdout, "\x1b[?1049h\x1b[?25l")?;
+        stdout.flush()?;
+
+        Ok(Self { original, stdout })
+    }
+
+    /// The number of columns and rows of the terminal.
+    pub fn size(&self) -> (usize, usize) {
+        termios::tcgetwinsize(rustix::stdio::stdout())
+            .map(|size| (usize::from(size.ws_col), usize::from(size.ws_row)))
+            .ok()
+            .filter(|(columns, rows)| *columns > 0 && *rows > 0)
+            .unwrap_or((80, 24))
+    }
+
+    /// Writes a whole frame at once, to avoid flickering.
+    pub fn draw(&mut self, frame: &str) -> Result<()> {
+        self.stdout.write_all(frame.as_bytes())?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = write!(self.stdout, "\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = self.stdout.flush();
+        let _ = termios::tcsetattr(rustix::stdio::stdin(), OptionalActions::Flush, &self.original);
+    }
+}
+
+/// Reads the keys pressed by the user in the background, until the receiver is dropped.
+pub fn read_keys(sender: Sender<Event>) {
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut buffer = [0; 64];
+        let mut pending = Vec::new();
+        while let Ok(read) = stdin.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buffer[..read]);
+            let (keys, decoded) = decode(&pending);
+            pending.drain(..decoded);
+            for key in keys {
+                if sender.send(Event::Key(key)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Decodes the keys of a chunk of input, and returns them with the number of bytes decoded. An
+/// escape sequence or a character cut at the end of the chunk is left for the next read to
+/// complete.
+fn decode(bytes: &[u8]) -> (Vec<Key>, usize) {
+    let mut keys = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let key = match bytes[i] {
+            0x1b if matches!(bytes.get(i + 1), Some(b'[' | b'O')) => {
+                let start = i + 2;
+                let Some(end) = bytes[start..]
+                    .iter()
+                    .position(|b| (0x40..=0x7e).contains(b))
+                    .map(|end| start + end)
+                else {
+                    break;
+                };
+                let params = std::str::from_utf8(&bytes[start..end]).unwrap_or_default();
+                i = end + 1;
+                match (bytes.get(end), params) {
+                    (Some(b'A'), _) => Some(Key::Up),
+                    (Some(b'B'), _) => Some(Key::Down),
+                    (Some(b'C'), _) => Some(Key::Right),
+                    (Some(b'D'), _) => Some(Key::Left),
+                    (Some(b'H'), _) | (Some(b'~'), "1" | "7") => Some(Key::Home),
+                    (Some(b'F'), _) | (Some(b'~'), "4" | "8") => Some(Key::End),
+                    (Some(b'Z'), _) => Some(Key::BackTab),
+                    (Some(b'~'), "3") => Some(Key::Delete),
+                    (Some(b'~'), "5") => Some(Key::PageUp),
+                    (Some(b'~'), "6") => Some(Key::PageDown),
+                    _ => None,
+                }
+            }
+            byte => {
+                i += 1;
+                match byte {
+                    0x1b => Some(Key::Esc),
+                    b'\r' | b'\n' => Some(Key::Enter),
+                    b'\t' => Some(Key::Tab),
+                    0x7f | 0x08 => Some(Key::Backspace),
+                    0x01..=0x1a => Some(Key::Ctrl(char::from(b'a' + byte - 1))),
+                    byte if byte.is_ascii() => Some(Key::Char(char::from(byte))),
+                    _ => {
+                        // A multi-byte UTF-8 character.
+                        let len = match byte {
+                            0xc0..=0xdf => 2,
+                            0xe0..=0xef => 3,
+                            0xf0..=0xf7 => 4,
+                            _ => 1,
+                        };
+                        let end = i - 1 + len;
+                        if end > bytes.len() {
+                            i -= 1;
+                            break;
+                        }
+                        let c = std::str::from_utf8(&bytes[i - 1..end])
+                            .ok()
+                            .and_then(|s| s.chars().next());
+                        i = end;
+                        c.map(Key::Char)
+                    }
+                }
+            }
+        };
+        keys.extend(key);
+    }
+
+    (keys, i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_and_escape_sequences_are_decoded() {
+        let (keys, decoded) = decode(b"a\x1b[A\x1b[3~\x1bOH\x1b[Z\r\x7f\x03\x1b");
+        assert_eq!(
+            keys,
+            [
+                Key::Char('a'),
+                Key::Up,
+                Key::Delete,
+                Key::Home,
+                Key::BackTab,
+                Key::Enter,
+                Key::Backspace,
+                Key::Ctrl('c'),
+                Key::Esc,
+            ]
+        );
+        assert_eq!(decoded, 18);
+
+        assert_eq!(decode("é€".as_bytes()), (vec![Key::Char('é'), Key::Char('€')], 5));
+        assert_eq!(decode(b"\x1b[99~\x80x"), (vec![Key::Char('x')], 7));
+    }
+
+    #[test]
+    fn incomplete_sequences_are_left_for_the_next_read() {
+        assert_eq!(decode(b"a\x1b["), (vec![Key::Char('a')], 1));
+        assert_eq!(decode(b"\x1bO"), (vec![], 0));
+        assert_eq!(decode(b"\x1b[5"), (vec![], 0));
+        assert_eq!(decode(&"x€".as_bytes()[..3]), (vec![Key::Char('x')], 1));
+
+        let mut pending = b"\x1b[".to_vec();
+        let (_, decoded) = decode(&pending);
+        pending.drain(..decoded);
+        pending.extend_from_slice(b"6~q");
+        assert_eq!(decode(&pending), (vec![Key::PageDown, Key::Char('q')], 5));
+    }
+}
@@ -13,9 +13,9 @@ async fn main() -> color_eyre::eyre::Result<()> {
     color_eyre::install()?;
     env_logger::init();
 
-    let key = std::env::var("ANTHROPIC_API_KEY")?;
+    let auth = anthropic::Auth::from_env().map_err(color_eyre::eyre::Error::msg)?;
 
-    let client = Anthropic::new(anthropic::Auth::new(&key), "https://api.anthropic.com/v1/");
+    let client = Anthropic::new(auth, "https://api.anthropic.com/v1/");
 
     let messages = vec![Message {
         role: Role::User,
@@ -1,6 +1,10 @@
+use cassette::Cassette;
+use http_agent::Client;
 use serde::{Deserialize, Serialize};
 use ureq::{Agent, AgentBuilder};
 
+use crate::error::Error;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Auth {
     pub api_key: String,
@@ -19,12 +23,17 @@ impl Auth {
         Self { api_key: api_key.to_string(), version: None }
     }
 
+    /// The key isn't required while replaying a cassette, see `Cassette::from_env`.
+    ///
     /// # Errors
     ///
     /// Will return `Err` if the environment variable `ANTHROPIC_API_KEY` is not defined.
     pub fn from_env() -> Result<Self, String> {
-        let api_key = std::env::var("ANTHROPIC_API_KEY")
-            .map_err(|_| "Missing ANTHROPIC_API_KEY".to_string())?;
+        let api_key = match std::env::var("ANTHROPIC_API_KEY") {
+            Ok(key) => key,
+            Err(_) if Cassette::from_env().is_some_and(|c| c.is_replay()) => String::new(),
+            Err(_) => return Err("Missing ANTHROPIC_API_KEY".to_string()),
+        };
         let version = std::env::var("ANTHROPIC_API_VERSION").ok();
         Ok(Self { api_key, version })
     }
@@ -34,18 +43,69 @@ impl Auth {
 pub struct Anthropic {
     pub auth: Auth,
     pub api_url: String,
+    /// Records or replays every request when set. See `Anthropic::cassette`.
+    pub cassette: Option<Cassette>,
     pub(crate) agent: Agent,
 }
 
 impl Clone for Anthropic {
     fn clone(&self) -> Self {
-        Self { auth: self.auth.clone(), api_url: self.api_url.clone(), agent: self.agent.clone() }
+        Self {
+            auth: self.auth.clone(),
+            api_url: self.api_url.clone(),
+            cassette: self.cassette.clone(),
+            agent: self.agent.clone(),
+        }
     }
 }
 
 impl Anthropic {
+    /// Creates a new client. A cassette is enabled if `CASSETTE_MODE` is set, see
+    /// `Cassette::from_env`.
     #[must_use]
     pub fn new(auth: Auth, api_url: &str) -> Self {
-        Self { auth, api_url: api_url.to_string(), agent: AgentBuilder::new().build() }
+        Self::with_agent(auth, api_url, AgentBuilder::new().build())
+    }
+
+    /// Creates a builder to set up the timeouts, proxy and TLS settings of the client, which
+    /// apply to streams too.
+    #[must_use]
+    pub fn builder(auth: Auth, api_url: &str) -> ClientBuilder {
+        ClientBuilder::new(auth, api_url)
+    }
+
+    /// Sets the cassette used to record every request and its response to disk, or to replay
+    /// them without reaching the network. `None` disables it.
+    #[must_use]
+    pub fn cassette(mut self, cassette: Option<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+}
+
+/// Builds an `Anthropic` client with custom HTTP settings. The `timeout` includes the time spent
+/// reading a stream, so keep it above the duration of the longest message.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use anthropic::{Anthropic, Auth};
+///
+/// let client = Anthropic::builder(Auth::new("api-key"), "https://api.anthropic.com/v1/")
+///     .timeout_connect(Duration::from_secs(5))
+///     .timeout_read(Duration::from_secs(30))
+///     .proxy("http://proxy.example.com:3128")
+///     .root_certificates(std::fs::read("internal-ca.pem").unwrap())
+///     .build()
+///     .unwrap();
+/// ```
+pub type ClientBuilder = http_agent::ClientBuilder<Anthropic>;
+
+impl Client for Anthropic {
+    type Auth = Auth;
+    type Error = Error;
+
+    fn with_agent(auth: Auth, api_url: &str, agent: Agent) -> Self {
+        Self { auth, api_url: api_url.to_string(), cassette: Cassette::from_env(), agent }
     }
 }
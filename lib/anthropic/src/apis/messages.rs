@@ -2,8 +2,8 @@
 
 //! Messages API
 
-use eventsource_client as es;
 use futures::stream::{Stream, StreamExt};
+use sse::Sse;
 use std::collections::HashMap;
 
 use crate::requests::Requests;
@@ -110,7 +110,7 @@ pub struct Delta {
     pub end_turn: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageEventType {
     #[default]
@@ -196,14 +196,13 @@ impl MessageApi for Anthropic {
             serde_json::to_value(message_body).map_err(error::Error::SerializeError)?;
         log::debug!("request_body: {:#?}", request_body);
 
-        let original_stream = self.stream(MESSAGES_CREATE, request_body).map_err(|e| {
+        let original_stream = self.stream(MESSAGES_CREATE, request_body).inspect_err(|e| {
             log::error!("Error creating stream: {:#?}", e);
-            error::Error::SseStreamCreation(Box::new(e))
         })?;
 
         let mapped_stream = original_stream.map(|item| {
             item.map(|event| match event {
-                es::SSE::Event(ev) => match serde_json::from_str::<MessageEvent>(&ev.data) {
+                Sse::Event(ev) => match serde_json::from_str::<MessageEvent>(&ev.data) {
                     Ok(ev) => ev,
                     Err(e) => {
                         log::error!("Error parsing event: {:#?}", ev);
@@ -213,14 +212,14 @@ impl MessageApi for Anthropic {
                         me
                     }
                 },
-                es::SSE::Comment(comment) => MessageEvent::with_comment(comment),
+                Sse::Comment(comment) => MessageEvent::with_comment(comment),
             })
-            .map_err(error::Error::from)
         });
 
         Ok(mapped_stream)
     }
 
+    #[allow(clippy::unnecessary_option_map_or_else)]
     fn message_delta_stream(
         &self,
         message_body: &MessageBody,
@@ -231,14 +230,13 @@ impl MessageApi for Anthropic {
             serde_json::to_value(message_body).map_err(error::Error::SerializeError)?;
         log::debug!("request_body: {:#?}", request_body);
 
-        let original_stream = self.stream(MESSAGES_CREATE, request_body).map_err(|e| {
+        let original_stream = self.stream(MESSAGES_CREATE, request_body).inspect_err(|e| {
             log::error!("Error creating stream: {:#?}", e);
-            error::Error::SseStreamCreation(Box::new(e))
         })?;
 
         let mapped_stream = original_stream.map(|item| {
             item.map(|event| match event {
-                es::SSE::Event(ev) => match serde_json::from_str::<MessageEvent>(&ev.data) {
+                Sse::Event(ev) => match serde_json::from_str::<MessageEvent>(&ev.data) {
                     Ok(ev) => {
                         if matches!(ev.r#type, MessageEventType::ContentBlockDelta) {
                             if let Some(delta) = ev.delta {
@@ -256,12 +254,11 @@ impl MessageApi for Anthropic {
                         String::default()
                     }
                 },
-                es::SSE::Comment(comment) => {
+                Sse::Comment(comment) => {
                     log::debug!("Comment: {:#?}", comment);
                     String::default()
                 }
             })
-            .map_err(error::Error::from)
         });
 
         Ok(mapped_stream)
@@ -1,5 +1,3 @@
-use eventsource_client as es;
-
 /// Error type returned from this library's functions
 #[derive(Debug)]
 pub enum Error {
@@ -9,8 +7,6 @@ pub enum Error {
     StreamClosed,
     /// An invalid request parameter.
     InvalidParameter(Box<dyn std::error::Error + Send + Sync + 'static>),
-    /// An error when creating the SSE stream.
-    SseStreamCreation(Box<dyn std::error::Error + Send + Sync + 'static>),
     /// The HTTP response stream ended.
     Eof,
     /// The HTTP response stream ended unexpectedly.
@@ -31,20 +27,23 @@ pub enum Error {
     DeserializeError(serde_json::error::Error),
     /// An Error occurred when deserializing an object from JSON.
     DeserializeIntoJson(std::io::Error),
+    /// An Error occurred when recording or replaying a request.
+    CassetteError(cassette::Error),
+    /// An invalid client setting, like a malformed proxy URL or root certificate.
+    ConfigError(String),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Error::{
-            ApiError, DeserializeError, DeserializeIntoJson, Eof, InvalidEvent, InvalidLine,
-            InvalidParameter, RequestError, SerializeError, SseStreamCreation, StreamClosed,
-            TimedOut, UnexpectedEof, Unknown,
+            ApiError, CassetteError, ConfigError, DeserializeError, DeserializeIntoJson, Eof,
+            InvalidEvent, InvalidLine, InvalidParameter, RequestError, SerializeError,
+            StreamClosed, TimedOut, UnexpectedEof, Unknown,
         };
 
         match self {
             TimedOut => write!(f, "timed out"),
             StreamClosed => write!(f, "stream closed"),
-            SseStreamCreation(err) => write!(f, "sse stream creation error: {err}"),
             InvalidParameter(err) => write!(f, "invalid parameter: {err}"),
             Eof => write!(f, "eof"),
             UnexpectedEof => write!(f, "unexpected eof"),
@@ -56,23 +55,34 @@ impl std::fmt::Display for Error {
             SerializeError(err) => write!(f, "serialize error: {err}"),
             DeserializeError(err) => write!(f, "deserialize error: {err}"),
             DeserializeIntoJson(err) => write!(f, "deserialize into error: {err}"),
+            CassetteError(err) => write!(f, "cassette error: {err}"),
+            ConfigError(s) => write!(f, "config error: {s}"),
         }
     }
 }
 
-impl From<es::Error> for Error {
-    fn from(error: es::Error) -> Self {
+impl From<sse::Error> for Error {
+    fn from(error: sse::Error) -> Self {
         match error {
-            es::Error::TimedOut => Self::TimedOut,
-            es::Error::StreamClosed => Self::StreamClosed,
-            es::Error::InvalidParameter(error) => Self::InvalidParameter(error),
-            es::Error::Eof => Self::Eof,
-            es::Error::UnexpectedEof => Self::UnexpectedEof,
-            es::Error::InvalidLine(line) => Self::InvalidLine(line),
-            es::Error::InvalidEvent => Self::InvalidEvent,
-            _ => Self::Unknown(Box::new(error)),
+            sse::Error::Io(error) => match error.kind() {
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => Self::TimedOut,
+                _ => Self::Unknown(Box::new(error)),
+            },
+            sse::Error::UnexpectedEof => Self::UnexpectedEof,
         }
     }
 }
 
+impl From<http_agent::Error> for Error {
+    fn from(error: http_agent::Error) -> Self {
+        Self::ConfigError(error.to_string())
+    }
+}
+
+impl From<cassette::Error> for Error {
+    fn from(error: cassette::Error) -> Self {
+        Self::CassetteError(error)
+    }
+}
+
 impl std::error::Error for Error {}
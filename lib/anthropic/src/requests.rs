@@ -1,6 +1,8 @@
-use eventsource_client as es;
-use futures::stream::Stream;
-use std::time::Duration;
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::sink::SinkExt;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use sse::{Events, Sse};
 
 use crate::anthropic::Anthropic;
 use crate::error;
@@ -12,93 +14,161 @@ use log::{debug, error, info};
 #[cfg(test)]
 use std::{eprintln as error, println as info, println as debug};
 
+/// Number of events buffered while the consumer of a stream catches up.
+const STREAM_BUFFER: usize = 32;
+
 pub trait Requests {
     /// # Errors
     ///
     /// Will return `Err` if the POST request fails, or we are unable to deserialize the response.
     fn post(&self, sub_url: &str, body: Json) -> ApiResult<Json>;
-    /// # Errors
+    /// Sends a POST request that responds with a stream of server-sent events.
     ///
-    /// Will return `Err` if:
+    /// The request is sent in the background with the same agent as `post`, so it shares its
+    /// timeouts, proxy and TLS settings. Messages can't be resumed, so instead of reconnecting and
+    /// sending the request again, the stream ends when the server closes the connection, or right
+    /// after the first error.
     ///
-    /// - The headers can't be loaded to the request.
-    /// - The body can't be loaded to the request.
-    /// - The POST request to start the stream fails.
-    /// - The stream connection fails to reconnect.
-    /// - A stream can't be created.
-    fn stream(
-        &self,
-        sub_url: &str,
-        body: Json,
-    ) -> Result<impl Stream<Item = Result<es::SSE, es::Error>>, es::Error>;
+    /// # Errors
+    ///
+    /// Will return `Err` if the stream is replayed from a cassette that doesn't hold it. Any
+    /// other error, like a failed request, is returned by the stream.
+    fn stream(&self, sub_url: &str, body: Json) -> ApiResult<impl Stream<Item = ApiResult<Sse>>>;
 }
 
 impl Requests for Anthropic {
     fn post(&self, sub_url: &str, body: Json) -> ApiResult<Json> {
         info!("===> 🚀\n\tPost api: {sub_url}, body: {body}");
 
-        let response = self
-            .agent
-            .post(&(self.api_url.clone() + sub_url))
-            .set(
-                "anthropic-version",
-                &self.auth.version.clone().unwrap_or("2023-06-01".to_string()),
-            )
-            .set("content-type", "application/json")
-            .set("x-api-key", &self.auth.api_key)
-            .send_json(body);
+        let url = self.api_url.clone() + sub_url;
+        let recorded = cassette::Request::new("POST", &url, Some(body.clone()));
+        if let Some(cassette) = self.cassette.as_ref().filter(|cassette| cassette.is_replay()) {
+            return deal_recording(&cassette.load(&recorded)?, sub_url);
+        }
 
-        deal_response(response, sub_url)
-    }
+        let response = self.request(&url).send_json(body);
 
-    fn stream(
-        &self,
-        sub_url: &str,
-        body: Json,
-    ) -> Result<impl Stream<Item = Result<es::SSE, es::Error>>, es::Error> {
-        let client = es::ClientBuilder::for_url(&(self.api_url.clone() + sub_url))?
-            .header("anthropic-version", "2023-06-01")?
-            .header("anthropic-beta", "messages-2023-12-15")?
-            .header("content-type", "application/json")?
-            .header("x-api-key", &self.auth.api_key)?
-            .method("POST".into())
-            .body(body.to_string())
-            .reconnect(
-                es::ReconnectOptions::reconnect(true)
-                    .retry_initial(false)
-                    .delay(Duration::from_secs(1))
-                    .backoff_factor(2)
-                    .delay_max(Duration::from_secs(60))
-                    .build(),
-            )
-            .build();
-
-        Ok(tail(&client))
+        let Some(cassette) = &self.cassette else {
+            return response
+                .map_err(|err| deal_error(err, sub_url))
+                .and_then(|response| deal_response(response, sub_url));
+        };
+
+        let response = match response {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => return Err(deal_error(err, sub_url)),
+        };
+        let status = response.status();
+        let body = response.into_string().map_err(error::Error::DeserializeIntoJson)?;
+        let recording = cassette::Response::new(status, &body);
+        cassette.save(&recorded, &recording)?;
+
+        deal_recording(&recording, sub_url)
     }
-}
 
-fn deal_response(response: Result<ureq::Response, ureq::Error>, sub_url: &str) -> ApiResult<Json> {
-    match response {
-        Ok(resp) => {
-            let json = resp.into_json::<Json>().map_err(error::Error::DeserializeIntoJson)?;
-            debug!("<== ✔️\n\tDone api: {sub_url}, resp: {json}");
-            Ok(json)
+    fn stream(&self, sub_url: &str, body: Json) -> ApiResult<impl Stream<Item = ApiResult<Sse>>> {
+        info!("===> 🚀\n\tStream api: {sub_url}, body: {body}");
+
+        let url = self.api_url.clone() + sub_url;
+        let recorded = cassette::Request::new("POST", &url, Some(body.clone()));
+        if let Some(cassette) = self.cassette.as_ref().filter(|cassette| cassette.is_replay()) {
+            let recording = cassette.load(&recorded)?;
+            let events = Events::new(std::io::Cursor::new(recording.body))
+                .map(|event| event.map_err(Error::from))
+                .collect::<Vec<_>>();
+            let events: BoxStream<'static, _> = stream::iter(events).boxed();
+            return Ok(events);
         }
-        Err(err) => match err {
-            ureq::Error::Status(status, response) => {
-                let error_msg =
-                    response.into_json::<Json>().map_err(error::Error::DeserializeIntoJson)?;
-                error!("<== ❌\n\tError api: {sub_url}, status: {status}, error: {error_msg}");
-                Err(Error::ApiError(format!("{error_msg}")))
+
+        let request = self
+            .request(&url)
+            .set("anthropic-beta", "messages-2023-12-15")
+            .set("accept", "text/event-stream");
+        let recorder = self.cassette.clone().map(|cassette| (cassette, recorded));
+        let sub_url = sub_url.to_string();
+        let (mut sender, receiver) = mpsc::channel(STREAM_BUFFER);
+
+        std::thread::spawn(move || {
+            let response = match request.send_json(body) {
+                Ok(response) => response,
+                Err(err) => {
+                    let _ = block_on(sender.send(Err(deal_error(err, &sub_url))));
+                    return;
+                }
+            };
+            debug!("<== ✔️\n\tStream open api: {sub_url}");
+
+            let mut events = Events::new(response.into_reader());
+            if recorder.is_some() {
+                events = events.record();
+            }
+            for event in events.by_ref() {
+                let event = event.map_err(Error::from);
+                let failed = event.is_err();
+                // The consumer dropped the stream.
+                if block_on(sender.send(event)).is_err() || failed {
+                    return;
+                }
             }
-            ureq::Error::Transport(e) => {
-                error!("<== ❌\n\tError api: {sub_url}, error: {:?}", e.to_string());
-                Err(Error::RequestError(e.to_string()))
+
+            if let (Some((cassette, request)), Some(body)) = (recorder, events.recording()) {
+                if let Err(err) = cassette.save(&request, &cassette::Response::new(200, body)) {
+                    error!("<== ❌\n\tFailed to record stream: {err}");
+                }
             }
-        },
+        });
+
+        let events: BoxStream<'static, _> = receiver.boxed();
+        Ok(events)
     }
 }
 
-fn tail(client: &impl es::Client) -> impl Stream<Item = Result<es::SSE, es::Error>> {
-    client.stream()
+impl Anthropic {
+    fn request(&self, url: &str) -> ureq::Request {
+        self.agent
+            .post(url)
+            .set("anthropic-version", self.auth.version.as_deref().unwrap_or("2023-06-01"))
+            .set("content-type", "application/json")
+            .set("x-api-key", &self.auth.api_key)
+    }
+}
+
+fn deal_response(response: ureq::Response, sub_url: &str) -> ApiResult<Json> {
+    let json = response.into_json::<Json>().map_err(error::Error::DeserializeIntoJson)?;
+    debug!("<== ✔️\n\tDone api: {sub_url}, resp: {json}");
+    Ok(json)
+}
+
+fn deal_error(err: ureq::Error, sub_url: &str) -> Error {
+    match err {
+        ureq::Error::Status(status, response) => {
+            let body = match response.into_string() {
+                Ok(body) => body,
+                Err(e) => return error::Error::DeserializeIntoJson(e),
+            };
+            // Proxies and load balancers may answer with something other than JSON.
+            let error_msg = match serde_json::from_str::<Json>(&body) {
+                Ok(json) => json.to_string(),
+                Err(_) => format!("{status}: {body}"),
+            };
+            error!("<== ❌\n\tError api: {sub_url}, status: {status}, error: {error_msg}");
+            Error::ApiError(error_msg)
+        }
+        ureq::Error::Transport(e) => {
+            error!("<== ❌\n\tError api: {sub_url}, error: {:?}", e.to_string());
+            Error::RequestError(e.to_string())
+        }
+    }
+}
+
+/// Rebuilds the result of a request out of its recorded response.
+fn deal_recording(recording: &cassette::Response, sub_url: &str) -> ApiResult<Json> {
+    let response = ureq::Response::new(recording.status, recording.status_text(), &recording.body)
+        .map_err(|err| deal_error(err, sub_url))?;
+
+    if recording.status >= 400 {
+        Err(deal_error(ureq::Error::Status(recording.status, response), sub_url))
+    } else {
+        deal_response(response, sub_url)
+    }
 }
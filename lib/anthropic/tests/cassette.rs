@@ -0,0 +1,95 @@
+//! Records interactions with a local mock of the Anthropic API, and replays them offline.
+
+mod common;
+
+use anthropic::{
+    error::Error,
+    messages::{MessageApi, MessageBody, MessageEventType},
+    Anthropic,
+};
+use cassette::{Cassette, Mode};
+use common::{body, fixture, MockServer};
+use futures::stream::StreamExt;
+
+async fn event_types(client: &Anthropic) -> Vec<MessageEventType> {
+    client
+        .message_stream(&body(true))
+        .unwrap()
+        .map(|event| event.map(|event| event.r#type).unwrap())
+        .collect()
+        .await
+}
+
+#[test]
+fn messages_are_replayed_without_the_network() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/v1/messages", 200, &fixture("message_create.json"));
+    let client = server.client();
+
+    let recorder = client.clone().cassette(Some(Cassette::new(Mode::Record, dir.path())));
+    let recorded = recorder.message_create(&body(false)).unwrap();
+    drop(server);
+
+    let replayer = client.cassette(Some(Cassette::new(Mode::Replay, dir.path())));
+    let replayed = replayer.message_create(&body(false)).unwrap();
+    assert_eq!(recorded.id, replayed.id);
+    assert_eq!(recorded.content[0].text, replayed.content[0].text);
+
+    let err = replayer.message_create(&MessageBody::new("claude-3-haiku", vec![], 1)).unwrap_err();
+    assert!(matches!(err, Error::CassetteError(cassette::Error::NotRecorded(..))));
+}
+
+#[test]
+fn api_errors_are_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/v1/messages", 529, &fixture("error_overloaded.json"));
+    let client = server.client();
+
+    let recorder = client.clone().cassette(Some(Cassette::new(Mode::Record, dir.path())));
+    assert!(recorder.message_create(&body(false)).is_err());
+    drop(server);
+
+    let replayer = client.cassette(Some(Cassette::new(Mode::Replay, dir.path())));
+    let err = replayer.message_create(&body(false)).unwrap_err();
+    assert!(matches!(err, Error::ApiError(ref msg) if msg.contains("overloaded_error")));
+}
+
+#[tokio::test]
+async fn streams_are_replayed_without_the_network() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream.txt"));
+    let client = server.client();
+
+    let recorder = client.clone().cassette(Some(Cassette::new(Mode::Record, dir.path())));
+    let recorded = event_types(&recorder).await;
+    drop(server);
+
+    let replayer = client.cassette(Some(Cassette::new(Mode::Replay, dir.path())));
+    assert_eq!(event_types(&replayer).await, recorded);
+
+    let text = replayer
+        .message_delta_stream(&body(true))
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<String, _>>()
+        .unwrap();
+    assert_eq!(text, "The capital is Washington, D.C.");
+}
+
+#[tokio::test]
+async fn interrupted_streams_are_not_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream_disconnect.txt"));
+
+    let recorder = server.client().cassette(Some(Cassette::new(Mode::Record, dir.path())));
+    let events = recorder.message_stream(&body(true)).unwrap().collect::<Vec<_>>().await;
+
+    assert!(matches!(events.last(), Some(Err(Error::UnexpectedEof))));
+    assert!(!dir.path().exists() || std::fs::read_dir(dir.path()).unwrap().count() == 0);
+}
@@ -0,0 +1,60 @@
+//! Checks that streams use the HTTP settings of clients made with `ClientBuilder`. The settings
+//! themselves are tested by the `http-agent` crate.
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use anthropic::{error::Error, messages::MessageApi, Anthropic, Auth};
+use common::{body, fixture, MockServer, API_KEY};
+use futures::stream::StreamExt;
+
+/// Starts a server that reads a single request, answers with the start of a response, and then
+/// stalls.
+fn stalled_server(response: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind stalled server");
+    let url = format!("http://{}/v1/", listener.local_addr().expect("tcp listener"));
+    std::thread::spawn(move || {
+        if let Ok((stream, _)) = listener.accept() {
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            let _ = reader.get_mut().write_all(response.as_bytes());
+            std::thread::sleep(Duration::from_secs(5));
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn streams_use_the_same_settings() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream.txt"));
+
+    let client = Anthropic::builder(Auth::new(API_KEY), &server.url())
+        .user_agent("acme-chat/1.0")
+        .build()
+        .unwrap();
+    let deltas = client.message_delta_stream(&body(true)).unwrap().collect::<Vec<_>>().await;
+
+    assert!(deltas.iter().all(Result::is_ok));
+    assert_eq!(server.requests()[0].header("user-agent"), Some("acme-chat/1.0"));
+}
+
+#[tokio::test]
+async fn stalled_streams_time_out() {
+    let response = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n\
+                    event: ping\ndata: {\"type\": \"ping\"}\n\n";
+    let client = Anthropic::builder(Auth::new(API_KEY), &stalled_server(response))
+        .timeout_read(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let events = client.message_stream(&body(true)).unwrap().collect::<Vec<_>>().await;
+
+    assert!(matches!(events[..], [Ok(_), Err(Error::TimedOut)]));
+}
@@ -0,0 +1,58 @@
+//! Helpers shared by the integration tests: recorded fixtures, the message they send, and a local
+//! mock of the Anthropic API that serves canned JSON responses and scripted event streams.
+
+#![allow(dead_code)]
+
+use std::ops::Deref;
+
+use anthropic::{messages::MessageBody, Anthropic, Auth, Message, Role};
+
+pub const API_KEY: &str = "test-api-key";
+
+/// Reads a recorded fixture from `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"))
+}
+
+/// The message sent by the tests: a single question, streamed or not.
+pub fn body(stream: bool) -> MessageBody {
+    let messages = vec![Message {
+        role: Role::User,
+        content: "What is the capital of the United States?".to_string(),
+    }];
+
+    if stream {
+        MessageBody::with_stream("claude-3-opus-20240229", messages, 100)
+    } else {
+        MessageBody::new("claude-3-opus-20240229", messages, 100)
+    }
+}
+
+/// A local mock of the Anthropic API, see [`mock_server::MockServer`].
+pub struct MockServer(mock_server::MockServer);
+
+impl MockServer {
+    pub fn start() -> Self {
+        let server = mock_server::MockServer::with_base_path("/v1/");
+        server.not_found(|path| {
+            format!(
+                r#"{{"type": "error", "error": {{"type": "not_found_error", "message": "no mock for {path}"}}}}"#
+            )
+        });
+        Self(server)
+    }
+
+    /// A client pointing to the mock API.
+    pub fn client(&self) -> Anthropic {
+        Anthropic::new(Auth::new(API_KEY), &self.url())
+    }
+}
+
+impl Deref for MockServer {
+    type Target = mock_server::MockServer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
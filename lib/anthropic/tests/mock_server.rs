@@ -0,0 +1,208 @@
+//! Exercises the `Anthropic` client end to end against a local mock of the Anthropic API.
+
+mod common;
+
+use anthropic::{
+    error::Error,
+    messages::{MessageApi, MessageEvent, MessageEventType},
+    Anthropic, Auth,
+};
+use common::{body, fixture, MockServer, API_KEY};
+use futures::stream::StreamExt;
+
+async fn events(client: &Anthropic) -> Vec<Result<MessageEvent, Error>> {
+    client.message_stream(&body(true)).unwrap().collect().await
+}
+
+async fn deltas(client: &Anthropic) -> Vec<Result<String, Error>> {
+    client.message_delta_stream(&body(true)).unwrap().collect().await
+}
+
+#[test]
+fn message_create_sends_the_body_and_headers() {
+    let server = MockServer::start();
+    server.mock("/v1/messages", 200, &fixture("message_create.json"));
+
+    let response = server.client().message_create(&body(false)).unwrap();
+
+    assert_eq!(response.id, "msg_01XFDUDYJgAACzvnptvVoYEL");
+    assert_eq!(
+        response.content[0].text.as_deref(),
+        Some("The capital of the United States is Washington, D.C.")
+    );
+    assert_eq!(response.stop_reason.as_deref(), Some("end_turn"));
+    assert_eq!(response.usage.output_tokens, Some(15));
+
+    let [request] = &server.requests()[..] else {
+        panic!("expected a single request");
+    };
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/v1/messages");
+    assert_eq!(request.header("x-api-key"), Some(API_KEY));
+    assert_eq!(request.header("anthropic-version"), Some("2023-06-01"));
+    assert_eq!(request.header("content-type"), Some("application/json"));
+
+    let json = request.json();
+    assert_eq!(json["model"], "claude-3-opus-20240229");
+    assert_eq!(json["max_tokens"], 100);
+    assert_eq!(json["messages"][0]["role"], "user");
+    assert!(json.get("stream").is_none());
+}
+
+#[test]
+fn message_create_uses_the_configured_version() {
+    let server = MockServer::start();
+    server.mock("/v1/messages", 200, &fixture("message_create.json"));
+
+    let mut auth = Auth::new(API_KEY);
+    auth.version = Some("2024-01-01".to_string());
+    Anthropic::new(auth, &server.url()).message_create(&body(false)).unwrap();
+
+    assert_eq!(server.requests()[0].header("anthropic-version"), Some("2024-01-01"));
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let server = MockServer::start();
+    server.mock("/v1/messages", 529, &fixture("error_overloaded.json"));
+
+    let err = server.client().message_create(&body(false)).unwrap_err();
+
+    assert!(matches!(err, Error::ApiError(ref msg) if msg.contains("overloaded_error")));
+}
+
+#[test]
+fn non_json_errors_keep_the_status() {
+    let server = MockServer::start();
+    server.mock("/v1/messages", 502, "<html>Bad Gateway</html>");
+
+    let err = server.client().message_create(&body(false)).unwrap_err();
+
+    assert!(matches!(err, Error::ApiError(ref msg) if msg == "502: <html>Bad Gateway</html>"));
+}
+
+#[test]
+fn transport_errors_are_reported() {
+    let url = MockServer::start().url();
+    let client = Anthropic::new(Auth::new(API_KEY), &url);
+
+    let err = client.message_create(&body(false)).unwrap_err();
+
+    assert!(matches!(err, Error::RequestError(_)));
+}
+
+#[tokio::test]
+async fn message_stream_maps_every_event() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream.txt"));
+
+    let events = events(&server.client()).await.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+    let types = events.iter().map(|event| event.r#type).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            MessageEventType::MessageStart,
+            MessageEventType::ContentBlockStart,
+            MessageEventType::Ping,
+            MessageEventType::Comment,
+            MessageEventType::ContentBlockDelta,
+            MessageEventType::ContentBlockDelta,
+            MessageEventType::ContentBlockStop,
+            MessageEventType::MessageDelta,
+            MessageEventType::MessageStop,
+        ]
+    );
+
+    let start = events[0].message.as_ref().unwrap();
+    assert_eq!(start.usage.input_tokens, Some(25));
+    assert_eq!(events[3].comment.as_deref(), Some(" keep-alive"));
+    let stop = &events[7];
+    assert_eq!(stop.delta.as_ref().unwrap().stop_reason.as_deref(), Some("end_turn"));
+    assert_eq!(stop.usage.as_ref().unwrap().output_tokens, Some(15));
+
+    // The stream ends with the connection, without sending the request again.
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn message_stream_sends_the_body_and_headers() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream.txt"));
+
+    let mut auth = Auth::new(API_KEY);
+    auth.version = Some("2024-01-01".to_string());
+    events(&Anthropic::new(auth, &server.url())).await;
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/v1/messages");
+    assert_eq!(request.header("x-api-key"), Some(API_KEY));
+    assert_eq!(request.header("anthropic-version"), Some("2024-01-01"));
+    assert_eq!(request.json()["stream"], true);
+}
+
+#[tokio::test]
+async fn message_delta_stream_yields_the_text() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream.txt"));
+
+    let text = deltas(&server.client()).await.into_iter().collect::<Result<String, _>>().unwrap();
+
+    assert_eq!(text, "The capital is Washington, D.C.");
+}
+
+#[tokio::test]
+async fn error_events_are_passed_through() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream_error.txt"));
+    let client = server.client();
+
+    let events = events(&client).await.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(events.last().unwrap().r#type, MessageEventType::Error);
+
+    let text = deltas(&client).await.into_iter().collect::<Result<String, _>>().unwrap();
+    assert_eq!(text, "The capital");
+}
+
+#[tokio::test]
+async fn malformed_events_are_skipped() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream_malformed.txt"));
+    let client = server.client();
+
+    let events = events(&client).await.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(events[0].r#type, MessageEventType::Error);
+    assert!(events[0].comment.as_ref().unwrap().starts_with("fail to deserialize event"));
+    assert_eq!(events[2].r#type, MessageEventType::MessageStop);
+
+    let text = deltas(&client).await.into_iter().collect::<Result<String, _>>().unwrap();
+    assert_eq!(text, " is Washington, D.C.");
+}
+
+#[tokio::test]
+async fn disconnects_end_the_stream_with_an_error() {
+    let server = MockServer::start();
+    server.mock_sse("/v1/messages", &fixture("message_stream_disconnect.txt"));
+
+    let deltas = deltas(&server.client()).await;
+
+    assert_eq!(deltas.len(), 2);
+    assert!(matches!(deltas[0], Ok(ref text) if text == "The capital"));
+    assert!(matches!(deltas[1], Err(Error::UnexpectedEof)));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn stream_status_errors_are_reported() {
+    let server = MockServer::start();
+    server.mock("/v1/messages", 529, &fixture("error_overloaded.json"));
+
+    let events = events(&server.client()).await;
+
+    let [Err(Error::ApiError(msg))] = &events[..] else {
+        panic!("expected a single error, got {events:?}");
+    };
+    assert!(msg.contains("overloaded_error"));
+    assert_eq!(server.requests().len(), 1);
+}
@@ -7,9 +7,9 @@ fn main() -> color_eyre::eyre::Result<()> {
     color_eyre::install()?;
     env_logger::init();
 
-    let key = std::env::var("BRAVE_SUBSCRIPTION_TOKEN")?;
+    let auth = brave::Auth::from_env().map_err(color_eyre::eyre::Error::msg)?;
 
-    let client = Brave::new(brave::Auth::new(&key), "https://api.search.brave.com/res/v1");
+    let client = Brave::new(auth, "https://api.search.brave.com/res/v1");
 
     let params = WebSearchParams::new("capital of the United States");
     let response = client.search(&params, None)?;
@@ -0,0 +1,187 @@
+//! Fluent builders for the search API parameters.
+
+use crate::error::ValidationError;
+use crate::{
+    CountryCode, Freshness, LanguageCode, ResultFilter, SafeSearch, SuggestSearchParams, Units,
+    WebSearchParams,
+};
+
+/// Builder for `WebSearchParams`.
+///
+/// The parameters are validated when calling `build`.
+#[derive(Debug, Clone, Default)]
+pub struct WebSearchParamsBuilder {
+    params: WebSearchParams,
+}
+
+impl WebSearchParamsBuilder {
+    #[must_use]
+    pub fn new(q: &str) -> Self {
+        Self { params: WebSearchParams::new(q) }
+    }
+
+    /// The search query country, where the results come from.
+    #[must_use]
+    pub fn country(mut self, country: CountryCode) -> Self {
+        self.params.country = Some(country);
+        self
+    }
+
+    /// The search language preference.
+    #[must_use]
+    pub fn search_lang(mut self, search_lang: LanguageCode) -> Self {
+        self.params.search_lang = Some(search_lang);
+        self
+    }
+
+    /// User interface language preferred in response.
+    #[must_use]
+    pub fn ui_lang(mut self, ui_lang: LanguageCode) -> Self {
+        self.params.ui_lang = Some(ui_lang);
+        self
+    }
+
+    /// The number of search results returned in response. The maximum is `20`.
+    #[must_use]
+    pub fn count(mut self, count: u16) -> Self {
+        self.params.count = Some(count);
+        self
+    }
+
+    /// The zero based page offset. The maximum is `9`.
+    #[must_use]
+    pub fn offset(mut self, offset: u16) -> Self {
+        self.params.offset = Some(offset);
+        self
+    }
+
+    /// Filters search results for adult content.
+    #[must_use]
+    pub fn safesearch(mut self, safesearch: SafeSearch) -> Self {
+        self.params.safesearch = Some(safesearch);
+        self
+    }
+
+    /// Filters search results by when they were discovered.
+    #[must_use]
+    pub fn freshness(mut self, freshness: Freshness) -> Self {
+        self.params.freshness = Some(freshness);
+        self
+    }
+
+    /// Whether display strings should include decoration markers.
+    #[must_use]
+    pub fn text_decorations(mut self, text_decorations: bool) -> Self {
+        self.params.text_decorations = Some(text_decorations);
+        self
+    }
+
+    /// Whether to spellcheck provided query.
+    #[must_use]
+    pub fn spellcheck(mut self, spellcheck: bool) -> Self {
+        self.params.spellcheck = Some(spellcheck);
+        self
+    }
+
+    /// The result types to include in the search response.
+    #[must_use]
+    pub fn result_filter(mut self, result_filter: ResultFilter) -> Self {
+        self.params.result_filter = Some(result_filter);
+        self
+    }
+
+    /// The url of a hosted Goggle used to re-rank the results.
+    #[must_use]
+    pub fn goggles_id(mut self, goggles_id: &str) -> Self {
+        self.params.goggles_id = Some(goggles_id.to_string());
+        self
+    }
+
+    /// An inline goggle definition used to re-rank the results, checked on `build`.
+    #[must_use]
+    pub fn goggles(mut self, goggles: &str) -> Self {
+        self.params.goggles = Some(goggles.to_string());
+        self
+    }
+
+    /// The measurement units.
+    #[must_use]
+    pub fn units(mut self, units: Units) -> Self {
+        self.params.units = Some(units);
+        self
+    }
+
+    /// Whether to get up to 5 additional, alternative excerpts for each result.
+    #[must_use]
+    pub fn extra_snippets(mut self, extra_snippets: bool) -> Self {
+        self.params.extra_snippets = Some(extra_snippets);
+        self
+    }
+
+    /// Whether to generate a summary key in the web search results.
+    #[must_use]
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.params.summary = Some(summary);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the query, `count` or `offset` are outside of the limits supported
+    /// by the API, or the inline goggle doesn't parse.
+    pub fn build(self) -> Result<WebSearchParams, ValidationError> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
+
+/// Builder for `SuggestSearchParams`.
+///
+/// The parameters are validated when calling `build`.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestSearchParamsBuilder {
+    params: SuggestSearchParams,
+}
+
+impl SuggestSearchParamsBuilder {
+    #[must_use]
+    pub fn new(q: &str) -> Self {
+        Self { params: SuggestSearchParams::new(q) }
+    }
+
+    /// The search query country.
+    #[must_use]
+    pub fn country(mut self, country: CountryCode) -> Self {
+        self.params.country = Some(country);
+        self
+    }
+
+    /// The search language preference.
+    #[must_use]
+    pub fn lang(mut self, lang: LanguageCode) -> Self {
+        self.params.lang = Some(lang);
+        self
+    }
+
+    /// The number of suggestions returned. The maximum is `20`.
+    #[must_use]
+    pub fn count(mut self, count: u16) -> Self {
+        self.params.count = Some(count);
+        self
+    }
+
+    /// Whether to enhance suggestions with rich results.
+    #[must_use]
+    pub fn rich(mut self, rich: bool) -> Self {
+        self.params.rich = Some(rich);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the query or `count` are outside of the limits supported by the API.
+    pub fn build(self) -> Result<SuggestSearchParams, ValidationError> {
+        self.params.validate()?;
+        Ok(self.params)
+    }
+}
@@ -0,0 +1,211 @@
+//! Markdown rendering of summarizer responses.
+
+use std::fmt::Write;
+
+use super::{StringOrSummaryEntity, SummarizerSearchApiResponse, SummaryEntity, SummaryMessage};
+
+impl SummarizerSearchApiResponse {
+    /// Reconstructs the summary as Markdown.
+    ///
+    /// Token messages are rendered as plain text, `enum_start`/`enum_item`/`enum_end` messages as
+    /// ordered or unordered lists of linked entities, and the `enrichments.context` references as
+    /// numbered footnotes. Entities whose url matches one of the references are followed by an
+    /// inline citation, and the remaining references are cited at the end of the summary.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut renderer = Renderer::new(self);
+        let mut out = String::new();
+
+        if let Some(ref title) = self.title {
+            let _ = writeln!(out, "# {}\n", title.trim());
+        }
+
+        out.push_str(renderer.render(self.summary.as_deref().unwrap_or_default()).trim_end());
+        let uncited = (0..renderer.references.len())
+            .filter(|i| !renderer.cited.contains(i))
+            .map(|i| format!("[^{}]", i + 1))
+            .collect::<String>();
+        if !uncited.is_empty() {
+            out.push(' ');
+            out.push_str(&uncited);
+        }
+        out.push('\n');
+
+        if !renderer.references.is_empty() {
+            out.push('\n');
+            for (i, (title, url)) in renderer.references.iter().enumerate() {
+                let _ = writeln!(out, "[^{}]: [{}]({url})", i + 1, escape(title));
+            }
+        }
+
+        if let Some(ref followups) = self.followups {
+            if !followups.is_empty() {
+                out.push_str("\n## Follow-ups\n\n");
+                for followup in followups {
+                    let _ = writeln!(out, "- {followup}");
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum List {
+    Ordered(usize),
+    Unordered,
+}
+
+struct Renderer<'a> {
+    /// Footnote references as `(title, url)` pairs, in the order they'll be numbered.
+    references: Vec<(String, String)>,
+    /// Indices of the references that have already been cited inline.
+    cited: Vec<usize>,
+    /// Entities that can be linked from the token text, as `(name, url)` pairs.
+    entities: Vec<(&'a str, &'a str)>,
+    list: Option<List>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(response: &'a SummarizerSearchApiResponse) -> Self {
+        let enrichments = response.enrichments.as_ref();
+
+        let references = response
+            .references()
+            .iter()
+            .filter_map(|context| {
+                let url = context.url.clone()?;
+                let title = context.title.clone().unwrap_or_else(|| url.clone());
+                Some((title, url))
+            })
+            .collect();
+
+        let entities = enrichments
+            .and_then(|e| e.entities.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|entity| Some((entity.name.as_deref()?, entity.url.as_deref()?)))
+            .collect();
+
+        Self { references, cited: Vec::new(), entities, list: None }
+    }
+
+    fn render(&mut self, messages: &[SummaryMessage]) -> String {
+        let mut out = String::new();
+
+        for message in messages {
+            match (message.r#type.as_str(), message.data.as_ref()) {
+                ("token", Some(StringOrSummaryEntity::String(text))) => {
+                    out.push_str(&self.link_entities(text));
+                }
+                ("enum_start", data) => {
+                    self.list = match data {
+                        Some(StringOrSummaryEntity::String(kind)) if kind == "ol" => {
+                            Some(List::Ordered(1))
+                        }
+                        _ => Some(List::Unordered),
+                    };
+                    if !out.is_empty() && !out.ends_with("\n\n") {
+                        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+                    }
+                }
+                ("enum_item", Some(StringOrSummaryEntity::SummaryEntity(entity))) => {
+                    let marker = match self.list {
+                        Some(List::Ordered(n)) => {
+                            self.list = Some(List::Ordered(n + 1));
+                            format!("{n}.")
+                        }
+                        _ => "-".to_string(),
+                    };
+                    let _ = writeln!(out, "{marker} {}", self.entity_item(entity));
+                }
+                ("enum_end", _) => {
+                    self.list = None;
+                    out.push('\n');
+                }
+                (_, Some(StringOrSummaryEntity::String(text))) => out.push_str(text),
+                _ => {}
+            }
+        }
+
+        out
+    }
+
+    fn entity_item(&mut self, entity: &SummaryEntity) -> String {
+        let name = entity.name.as_deref().unwrap_or_default().trim();
+        let mut item = match entity.url.as_deref() {
+            Some(url) if !name.is_empty() => format!("**[{}]({url})**", escape(name)),
+            _ if !name.is_empty() => format!("**{name}**"),
+            _ => String::new(),
+        };
+
+        if let Some(citation) = entity.url.as_deref().and_then(|url| self.cite(url)) {
+            item.push_str(&citation);
+        }
+
+        if let Some(text) = entity.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            if !item.is_empty() {
+                item.push_str(": ");
+            }
+            item.push_str(&text.replace('\n', " "));
+        }
+
+        item
+    }
+
+    /// Returns the footnote marker for the reference with the given url, if any.
+    fn cite(&mut self, url: &str) -> Option<String> {
+        let i = self.references.iter().position(|(_, u)| u == url)?;
+        if !self.cited.contains(&i) {
+            self.cited.push(i);
+        }
+        Some(format!("[^{}]", i + 1))
+    }
+
+    /// Links the first appearance of each known entity in the text. Every entity is linked at
+    /// most once across the whole summary.
+    ///
+    /// Matches are found on the original text, so names never match inside an inserted link.
+    /// An appearance overlapping the one of an earlier entity is skipped for the next one.
+    fn link_entities(&mut self, text: &str) -> String {
+        // `(start, end, entity)` of every appearance to link.
+        let mut matches: Vec<(usize, usize, usize)> = Vec::new();
+
+        for (i, (name, _)) in self.entities.iter().enumerate() {
+            if name.is_empty() {
+                continue;
+            }
+            let found = text
+                .match_indices(name)
+                .map(|(start, _)| (start, start + name.len()))
+                .find(|&(start, end)| matches.iter().all(|&(s, e, _)| end <= s || e <= start));
+            if let Some((start, end)) = found {
+                matches.push((start, end, i));
+            }
+        }
+        matches.sort_unstable();
+
+        let mut out = String::with_capacity(text.len());
+        let mut position = 0;
+        for &(start, end, i) in &matches {
+            let (name, url) = self.entities[i];
+            out.push_str(&text[position..start]);
+            let _ = write!(out, "[{}]({url})", escape(name));
+            position = end;
+        }
+        out.push_str(&text[position..]);
+
+        let mut linked = matches.into_iter().map(|(_, _, i)| i).collect::<Vec<_>>();
+        linked.sort_unstable();
+        for i in linked.into_iter().rev() {
+            self.entities.remove(i);
+        }
+
+        out
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('[', "\\[").replace(']', "\\]")
+}
@@ -3,14 +3,24 @@ use std::collections::HashMap;
 use std::fmt;
 
 use serde::{self, Deserialize, Deserializer, Serialize};
+use serde_json::Map;
 use std::result::Result as StdResult;
 
+use crate::error::ValidationError;
+use crate::Json;
+
+pub mod builders;
+pub mod markdown;
+pub mod params;
 pub mod suggest;
 pub mod summarizer;
 pub mod web_search;
 
+pub use builders::*;
+pub use params::*;
+
 /// Web Search API query parameters
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WebSearchParams {
     /// The user's search query term.
     ///
@@ -22,21 +32,21 @@ pub struct WebSearchParams {
     /// The country string is limited to 2 character country codes of supported countries. For
     /// a list of supported values, see Country Codes.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub country: Option<String>,
+    pub country: Option<CountryCode>,
 
     /// The search language preference.
     ///
     /// The 2 or more character language code for which the search results are provided. For a
     /// list of possible values, see Language Codes.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub search_lang: Option<String>,
+    pub search_lang: Option<LanguageCode>,
 
     /// User interface language preferred in response.
     ///
     /// Usually of the format `<language_code>-<country_code>`, see RFC 9110. For a list of supported values, see
     /// UI Language Codes.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ui_lang: Option<String>,
+    pub ui_lang: Option<LanguageCode>,
 
     /// The number of search results returned in response.
     ///
@@ -66,7 +76,7 @@ pub struct WebSearchParams {
     /// - moderate: Adult text but no adult images or videos.
     /// - strict: No adult content with adult text, images, and videos.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub safesearch: Option<String>,
+    pub safesearch: Option<SafeSearch>,
 
     /// Filters search results by when they were discovered.
     ///
@@ -79,16 +89,18 @@ pub struct WebSearchParams {
     /// - YYYY-MM-DDtoYYYY-MM-DD: A timeframe is also supported by specifying the date range e.g.
     ///   `2022-04-01to2022-07-30`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub freshness: Option<String>,
+    pub freshness: Option<Freshness>,
 
     /// Whether display strings (e.g. result snippets) should include decoration markers (e.g.
     /// highlighting characters).
-    pub text_decorations: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_decorations: Option<bool>,
 
     /// Whether to spellcheck provided query. If the spellchecker is enabled, the modified query
     /// is always used for search. The modified query can be found in `altered` key
     /// from the query response model.
-    pub spellcheck: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spellcheck: Option<bool>,
 
     /// A comma delimited string of result types to include in the search response.
     ///
@@ -111,13 +123,18 @@ pub struct WebSearchParams {
     /// Example result filter value of `discussions,videos` returns only
     /// `query`, `discussions`, and `videos` responses.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub result_filter: Option<String>,
+    pub result_filter: Option<ResultFilter>,
 
     /// Goggles act as a custom re-ranking on top of Brave's search index. For more details,
     /// refer to the Goggles repository.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub goggles_id: Option<String>,
 
+    /// An inline goggle definition, e.g. the content of a local `.goggle` file, used to re-rank
+    /// the results without hosting the goggle. See the `goggles` module for its syntax.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goggles: Option<String>,
+
     /// The measurement units.
     ///
     /// If not provided, units are derived from search country. Possible values are:
@@ -125,18 +142,20 @@ pub struct WebSearchParams {
     /// - metric: The standardized measurement system.
     /// - imperial: The British Imperial system of units.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub units: Option<String>,
+    pub units: Option<Units>,
 
     /// A snippet is an excerpt from a page you get as a result of the query, and `extra_snippets`
     /// allow you to get up to 5 additional, alternative excerpts. Only available under
     /// `Free AI`, `Base AI`, `Pro AI`, `Base Data`, `Pro Data` and `Custom` plans.
-    pub extra_snippets: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_snippets: Option<bool>,
 
     /// This parameter enables summary key generation in web search results. This param is only
     /// required when enabling summarizer version `2024-04-23` onwards.
     ///
     /// Summarizer version `2023-08-25` is deprecated.
-    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<bool>,
 }
 
 impl WebSearchParams {
@@ -145,6 +164,30 @@ impl WebSearchParams {
         Self { q: q.to_string(), ..Default::default() }
     }
 
+    /// Creates a builder that validates the parameters on `build`.
+    #[must_use]
+    pub fn builder(q: &str) -> WebSearchParamsBuilder {
+        WebSearchParamsBuilder::new(q)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the query, `count` or `offset` are outside of the limits supported
+    /// by the API, or the inline goggle doesn't parse.
+    pub fn validate(&self) -> StdResult<(), ValidationError> {
+        validate_query(&self.q)?;
+        if let Some(count) = self.count {
+            validate_count(count)?;
+        }
+        if let Some(offset) = self.offset {
+            validate_offset(offset)?;
+        }
+        if let Some(ref goggles) = self.goggles {
+            crate::goggles::Goggle::parse(goggles)?;
+        }
+        Ok(())
+    }
+
     #[must_use]
     pub fn to_query_params(&self) -> Vec<(String, String)> {
         let mut params = Vec::new();
@@ -153,13 +196,13 @@ impl WebSearchParams {
         params.push(("q".to_string(), self.q.clone()));
 
         if let Some(ref country) = self.country {
-            params.push(("country".to_string(), country.clone()));
+            params.push(("country".to_string(), country.to_string()));
         }
         if let Some(ref search_lang) = self.search_lang {
-            params.push(("search_lang".to_string(), search_lang.clone()));
+            params.push(("search_lang".to_string(), search_lang.to_string()));
         }
         if let Some(ref ui_lang) = self.ui_lang {
-            params.push(("ui_lang".to_string(), ui_lang.clone()));
+            params.push(("ui_lang".to_string(), ui_lang.to_string()));
         }
         if let Some(count) = self.count {
             params.push(("count".to_string(), count.to_string()));
@@ -167,32 +210,35 @@ impl WebSearchParams {
         if let Some(offset) = self.offset {
             params.push(("offset".to_string(), offset.to_string()));
         }
-        if let Some(ref safesearch) = self.safesearch {
-            params.push(("safesearch".to_string(), safesearch.clone()));
+        if let Some(safesearch) = self.safesearch {
+            params.push(("safesearch".to_string(), safesearch.to_string()));
         }
         if let Some(ref freshness) = self.freshness {
-            params.push(("freshness".to_string(), freshness.clone()));
+            params.push(("freshness".to_string(), freshness.to_string()));
         }
-        if let Some(ref text_decorations) = self.text_decorations {
-            params.push(("text_decorations".to_string(), text_decorations.to_string()));
+        if let Some(text_decorations) = self.text_decorations {
+            params.push(("text_decorations".to_string(), params::flag(text_decorations)));
         }
-        if let Some(ref spellcheck) = self.spellcheck {
-            params.push(("spellcheck".to_string(), spellcheck.to_string()));
+        if let Some(spellcheck) = self.spellcheck {
+            params.push(("spellcheck".to_string(), params::flag(spellcheck)));
         }
         if let Some(ref result_filter) = self.result_filter {
-            params.push(("result_filter".to_string(), result_filter.clone()));
+            params.push(("result_filter".to_string(), result_filter.to_string()));
         }
         if let Some(ref goggles_id) = self.goggles_id {
             params.push(("goggles_id".to_string(), goggles_id.clone()));
         }
-        if let Some(ref units) = self.units {
-            params.push(("units".to_string(), units.clone()));
+        if let Some(ref goggles) = self.goggles {
+            params.push(("goggles".to_string(), goggles.clone()));
+        }
+        if let Some(units) = self.units {
+            params.push(("units".to_string(), units.to_string()));
         }
-        if let Some(ref extra_snippets) = self.extra_snippets {
-            params.push(("extra_snippets".to_string(), extra_snippets.to_string()));
+        if let Some(extra_snippets) = self.extra_snippets {
+            params.push(("extra_snippets".to_string(), params::flag(extra_snippets)));
         }
-        if let Some(ref summary) = self.summary {
-            params.push(("summary".to_string(), summary.to_string()));
+        if let Some(summary) = self.summary {
+            params.push(("summary".to_string(), params::flag(summary)));
         }
 
         params
@@ -203,7 +249,7 @@ impl WebSearchParams {
 /// relevant keys based on the plan subscribed, query relevance or applied `result_filter` as a query
 /// parameter. The API can also respond back with an error response based on invalid subscription
 /// keys and rate limit events.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebSearchApiResponse {
     /// The type of web search api result. The value is always search.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -238,19 +284,68 @@ pub struct WebSearchApiResponse {
     /// Summary key to get summary results for the query.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summarizer: Option<Summarizer>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
+}
+
+impl WebSearchApiResponse {
+    /// The web search results, or an empty slice if there are none.
+    #[must_use]
+    pub fn web_results(&self) -> &[Result] {
+        self.web.as_ref().and_then(|web| web.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The news results, or an empty slice if there are none.
+    #[must_use]
+    pub fn news_results(&self) -> &[NewsResult] {
+        self.news.as_ref().and_then(|news| news.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The video results, or an empty slice if there are none.
+    #[must_use]
+    pub fn video_results(&self) -> &[VideoResult] {
+        self.videos.as_ref().and_then(|videos| videos.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The frequently asked questions, or an empty slice if there are none.
+    #[must_use]
+    pub fn faq_results(&self) -> &[QA] {
+        self.faq.as_ref().and_then(|faq| faq.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The discussion results, or an empty slice if there are none.
+    #[must_use]
+    pub fn discussion_results(&self) -> &[DiscussionResult] {
+        self.discussions.as_ref().and_then(|d| d.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The location results, or an empty slice if there are none.
+    #[must_use]
+    pub fn location_results(&self) -> &[LocationResult] {
+        self.locations.as_ref().and_then(|l| l.results.as_deref()).unwrap_or_default()
+    }
+
+    /// The urls of the web search results, in order.
+    #[must_use]
+    pub fn urls(&self) -> Vec<&str> {
+        self.web_results().iter().filter_map(|result| result.url.as_deref()).collect()
+    }
 }
 
 /// A reference for the summary.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Summarizer {
     /// The value is always summarizer.
+    #[serde(default)]
     pub r#type: String,
     /// The key for the summarizer api.
+    #[serde(default)]
     pub key: String,
 }
 
 /// A model representing information gathered around the requested query.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Query {
     /// The original query that was requested.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -327,10 +422,13 @@ pub struct Query {
     /// Any reddit cluster associated with the query.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reddit_cluster: Option<String>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// A model representing a discussion cluster relevant to the query.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Discussions {
     /// The type identifying a discussion cluster. Currently the value is always search.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -344,18 +442,30 @@ pub struct Discussions {
 }
 
 /// A discussion result. These are forum posts and discussions that are relevant to the search query.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiscussionResult {
     /// The discussion result type identifier. The value is always discussion.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
+    /// The title of the forum post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The url of the forum post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// A description for the forum post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// The enriched aggregated data for the relevant forum post.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<ForumData>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// Defines a result from a discussion forum.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ForumData {
     /// The name of the forum.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -378,7 +488,7 @@ pub struct ForumData {
 }
 
 /// Frequently asked questions relevant to the search query term.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FAQ {
     /// The FAQ result type identifier. The value is always faq.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -389,7 +499,7 @@ pub struct FAQ {
 }
 
 /// A question answer result.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QA {
     /// The question being asked.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -406,10 +516,13 @@ pub struct QA {
     /// Aggregated information about the url.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta_url: Option<MetaUrl>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// Aggregated information about a url.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MetaUrl {
     /// The protocol scheme extracted from the url.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -428,7 +541,7 @@ pub struct MetaUrl {
     pub path: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// A model representing a collection of web search results.
 pub struct Search {
     /// A type identifying web search results. The value is always search.
@@ -442,7 +555,7 @@ pub struct Search {
     pub family_friendly: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Aggregated information on a web search result, relevant to the query.
 pub struct SearchResult {
     /// A type identifying a web search result. The value is always `search_result`.
@@ -531,7 +644,7 @@ pub struct SearchResult {
     pub extra_snippets: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// A model representing a web search result.
 pub struct Result {
     /// The title of the web page.
@@ -562,9 +675,93 @@ pub struct Result {
     /// Whether the web page is family friendly.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub family_friendly: Option<bool>,
+    /// A type identifying a web search result. The value is always `search_result`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    /// A sub type identifying the web search result type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,
+    /// Gathered information on a web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deep_results: Option<DeepResult>,
+    /// A list of schemas extracted from the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<Vec<Vec<Json>>>,
+    /// Aggregated information on the url associated with the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta_url: Option<MetaUrl>,
+    /// The thumbnail of the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<Thumbnail>,
+    /// A string representing the age of the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<String>,
+    /// The location details if the query relates to a restaurant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<LocationResult>,
+    /// The video associated with the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoData>,
+    /// The movie associated with the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movie: Option<MovieData>,
+    /// Any frequently asked questions associated with the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub faq: Option<FAQ>,
+    /// Any question answer information associated with the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qa: Option<QAPage>,
+    /// Any book information associated with the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub book: Option<Book>,
+    /// Rating found for the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<Rating>,
+    /// An article found for the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub article: Option<Article>,
+    /// The main product and a review that is found on the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<ProductReview>,
+    /// A list of products and reviews that are found on the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_cluster: Option<Vec<ProductReview>>,
+    /// A type representing a cluster. The value can be `product_cluster`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_type: Option<String>,
+    /// A list of web search results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<Vec<Result>>,
+    /// Aggregated information on the creative work found on the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creative_work: Option<CreativeWork>,
+    /// Aggregated information on music recording found on the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub music_recording: Option<MusicRecording>,
+    /// Aggregated information on the review found on the web search result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review: Option<Review>,
+    /// Aggregated information on a software product found on the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub software: Option<Software>,
+    /// Aggregated information on a recipe found on the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<Recipe>,
+    /// Aggregated information on an organization found on the web search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<Organization>,
+    /// The content type associated with the search result page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// A list of extra alternate snippets for the web page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_snippets: Option<Vec<String>>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Aggregated information on an entity from a knowledge graph.
 pub struct GraphInfobox {
     /// The infobox result type identifier. The value is always infobox.
@@ -612,9 +809,12 @@ pub struct GraphInfobox {
     /// Any movie data relevant to the entity. Appears only when the result is a movie.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub movie: Option<MovieData>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GenericInfobox {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
@@ -622,7 +822,7 @@ pub struct GenericInfobox {
     pub found_in_urls: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QAInfobox {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
@@ -632,7 +832,7 @@ pub struct QAInfobox {
     pub meta_url: Option<MetaUrl>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfoboxWithLocation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
@@ -646,14 +846,14 @@ pub struct InfoboxWithLocation {
     pub location: Option<LocationResult>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfoboxPlace {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
 }
 
 /// Aggregated result from a question answer page.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QAPage {
     /// The question that is being asked.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -664,7 +864,7 @@ pub struct QAPage {
 }
 
 /// A response representing an answer to a question on a forum.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Answer {
     /// The main content of the answer.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -681,7 +881,7 @@ pub struct Answer {
 }
 
 /// Aggregated details representing a picture thumbnail.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Thumbnail {
     /// The served url of the picture thumbnail.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -710,11 +910,20 @@ pub struct Thumbnail {
 }
 
 /// A result that is location relevant.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LocationResult {
     /// Location result type identifier. The value is always `location_result`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
+    /// The title of the location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The url of the location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// A description for the location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// The complete url of the provider.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider_url: Option<String>,
@@ -731,7 +940,8 @@ pub struct LocationResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postal_address: Option<PostalAddress>,
     /// The opening hours, if it is a business, associated with the location.
-    pub opening_hours: OpeningHours,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opening_hours: Option<OpeningHours>,
     /// The contact of the business associated with the location.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contact: Option<Contact>,
@@ -753,10 +963,13 @@ pub struct LocationResult {
     /// A bunch of pictures associated with the business.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pictures: Option<PictureResults>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// A model representing location results.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Locations {
     /// Location type identifier. The value is always locations.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -767,7 +980,7 @@ pub struct Locations {
 }
 
 /// The ranking order of results on a search result page.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MixedResponse {
     /// The type representing the model mixed. The value is always mixed.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -784,7 +997,7 @@ pub struct MixedResponse {
 }
 
 /// The ranking order of results on a search result page.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ResultReference {
     /// The type of the result.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -798,7 +1011,7 @@ pub struct ResultReference {
 }
 
 /// A model representing video results.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Videos {
     /// The type representing the videos. The value is always videos.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -812,7 +1025,7 @@ pub struct Videos {
 }
 
 /// A model representing news results.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct News {
     /// The type representing the news. The value is always news.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -826,8 +1039,17 @@ pub struct News {
 }
 
 /// A model representing news results.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NewsResult {
+    /// The title of the news article.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The url of the news article.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// A description for the news article.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// The aggregated information on the url representing a news result
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta_url: Option<MetaUrl>,
@@ -846,10 +1068,13 @@ pub struct NewsResult {
     /// A list of extra alternate snippets for the news search result.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_snippets: Option<Vec<String>>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// A model representing a list of pictures.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PictureResults {
     /// A url to view more pictures.
     #[serde(rename = "viewMoreUrl")]
@@ -861,7 +1086,7 @@ pub struct PictureResults {
 }
 
 /// A model representing an action to be taken.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Action {
     /// The type representing the action.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -872,7 +1097,7 @@ pub struct Action {
 }
 
 /// A model representing a postal address of a location
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PostalAddress {
     /// The type identifying a postal address. The value is always `PostalAddress`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -903,7 +1128,7 @@ pub struct PostalAddress {
 }
 
 /// Opening hours of a business at a particular location.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OpeningHours {
     /// The current day opening hours. Can have two sets of opening hours.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -914,7 +1139,7 @@ pub struct OpeningHours {
 }
 
 /// A model representing the opening hours for a particular day for a business at a particular location.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DayOpeningHours {
     /// A short string representing the day of the week.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -931,7 +1156,7 @@ pub struct DayOpeningHours {
 }
 
 /// A model representing contact information for an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Contact {
     /// The email address.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -942,7 +1167,7 @@ pub struct Contact {
 }
 
 /// A model representing the data provider associated with the entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DataProvider {
     /// The type representing the source of data. This is usually external.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -962,7 +1187,7 @@ pub struct DataProvider {
 }
 
 /// A profile of an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Profile {
     /// The name of the profile.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -979,7 +1204,7 @@ pub struct Profile {
 }
 
 /// A model representing a unit of measurement.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Unit {
     /// The quantity of the unit.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -990,7 +1215,7 @@ pub struct Unit {
 }
 
 /// Aggregated data for a movie result.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MovieData {
     /// Name of the movie.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1028,7 +1253,7 @@ pub struct MovieData {
 }
 
 /// A model describing a generic thing.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Thing {
     /// A type identifying a thing. The value is always thing.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1045,15 +1270,24 @@ pub struct Thing {
 }
 
 /// A model describing a person entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Person {
     /// A type identifying a person. The value is always person.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
+    /// The name of the person.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// A url for the person.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Thumbnail associated with the person.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<Thumbnail>,
 }
 
 /// The rating associated with an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Rating {
     /// The current value of the rating.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1073,7 +1307,7 @@ pub struct Rating {
 }
 
 /// A model representing a book result.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Book {
     /// The title of the book.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1092,14 +1326,14 @@ pub struct Book {
     pub pages: Option<i32>,
     /// The publisher of the book.
     #[serde(skip_serializing_if = "Option::is_none")]
-    publisher: Option<Person>,
+    pub publisher: Option<Person>,
     /// A gathered rating from different sources associated with the book.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rating: Option<Rating>,
 }
 
 /// A model representing the price for an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Price {
     /// The price value in a given currency.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1110,7 +1344,7 @@ pub struct Price {
 }
 
 /// A model representing an article.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Article {
     /// The author of the article.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1120,7 +1354,7 @@ pub struct Article {
     pub date: Option<String>,
     /// The name of the publisher for the article.
     #[serde(skip_serializing_if = "Option::is_none")]
-    publisher: Option<Organization>,
+    pub publisher: Option<Organization>,
     /// A thumbnail associated with the article.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail: Option<Thumbnail>,
@@ -1130,7 +1364,7 @@ pub struct Article {
 }
 
 /// A way to contact an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactPoint {
     /// A type string identifying a contact point. The value is always `contact_point`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1144,7 +1378,7 @@ pub struct ContactPoint {
 }
 
 /// An entity responsible for another entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Organization {
     /// A type string identifying an organization. The value is always organization.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1155,7 +1389,7 @@ pub struct Organization {
 }
 
 /// Aggregated information on a how to.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HowTo {
     /// The how to text.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1172,7 +1406,7 @@ pub struct HowTo {
 }
 
 /// Aggregated information on a recipe.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Recipe {
     /// The title of the recipe.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1227,17 +1461,20 @@ pub struct Recipe {
     pub video: Option<VideoData>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")] // This tells serde to use the `type` field as a discriminator
 pub enum ProductReview {
     #[serde(rename = "Product")]
     Product(Product),
     #[serde(rename = "Review")]
     Review(Review),
+    /// A type that is not modeled yet.
+    #[serde(other)]
+    Unknown,
 }
 
 /// A model representing a product.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Product {
     /// The name of the product.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1260,7 +1497,7 @@ pub struct Product {
 }
 
 /// A model representing a review for an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Review {
     /// The review title for the review.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1277,7 +1514,7 @@ pub struct Review {
 }
 
 /// An offer associated with a product.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Offer {
     /// The url where the offer can be found.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1291,7 +1528,7 @@ pub struct Offer {
 }
 
 /// The reviews associated with an entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reviews {
     /// A list of trip advisor reviews for the entity.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1305,7 +1542,7 @@ pub struct Reviews {
 }
 
 /// A model representing a Tripadvisor review.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TripAdvisorReview {
     /// The title of the review.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1331,7 +1568,7 @@ pub struct TripAdvisorReview {
 }
 
 /// A creative work relevant to the query. An example can be enriched metadata for an app.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreativeWork {
     /// The name of the creative work.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1345,7 +1582,7 @@ pub struct CreativeWork {
 }
 
 /// Result classified as a music label or a song.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MusicRecording {
     /// The name of the song or album.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1359,7 +1596,7 @@ pub struct MusicRecording {
 }
 
 /// A model representing a software entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Software {
     /// The name of the software product.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1397,7 +1634,7 @@ pub struct Software {
 }
 
 /// Aggregated deep results from news, social, videos and images.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeepResult {
     /// A list of news results associated with the result.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1417,11 +1654,20 @@ pub struct DeepResult {
 }
 
 /// A model representing a video result.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VideoResult {
     /// The type identifying the video result. The value is always `video_result`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
+    /// The title of the video.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The url of the video.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// A description for the video.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// Meta data for the video.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub video: Option<VideoData>,
@@ -1434,10 +1680,13 @@ pub struct VideoResult {
     /// A string representing the age of the video.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub age: Option<String>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 /// A model representing metadata gathered for a video.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VideoData {
     /// A time string representing the duration of the video. The format can be HH:MM:SS or MM:SS.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1450,14 +1699,14 @@ pub struct VideoData {
     pub creator: Option<String>,
     /// The publisher of the video.
     #[serde(skip_serializing_if = "Option::is_none")]
-    publisher: Option<String>,
+    pub publisher: Option<String>,
     /// A thumbnail associated with the video.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail: Option<Thumbnail>,
 }
 
 /// A result which can be used as a button.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ButtonResult {
     /// A type identifying button result. The value is always `button_result`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1471,7 +1720,7 @@ pub struct ButtonResult {
 }
 
 /// Represents a knowledge graph entity.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KnowledgeGraphEntity {
     /// A short title describing the entity.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1488,7 +1737,7 @@ pub struct KnowledgeGraphEntity {
 }
 
 /// Represents an entity profile from a knowledge graph.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KnowledgeGraphProfile {
     /// The url representing the profile.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1499,7 +1748,7 @@ pub struct KnowledgeGraphProfile {
 }
 
 /// A model representing a URL.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct URL {
     /// The original source URL.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1519,7 +1768,7 @@ pub struct URL {
 }
 
 /// A mobile friendly representation of the URL.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MobileUrlItem {
     /// The original source URL.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1536,7 +1785,7 @@ pub struct MobileUrlItem {
 }
 
 /// A model describing an image
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Image {
     /// The thumbnail associated with the image.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1550,7 +1799,7 @@ pub struct Image {
 }
 
 /// A model representing a language.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Language {
     /// The main language seen in the string.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1558,7 +1807,7 @@ pub struct Language {
 }
 
 /// Metadata on an image.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImageProperties {
     /// The image URL.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1581,7 +1830,7 @@ pub struct ImageProperties {
 }
 
 /// A result from the summarizer.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummarizerResult {
     /// The type of summarizer search api result. The value is always `summarizer_result`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1598,7 +1847,7 @@ pub struct SummarizerResult {
 }
 
 /// An answer from the summarized content.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummarizerAnswer {
     /// The text from the summary, which is the answer to the query.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1609,7 +1858,7 @@ pub struct SummarizerAnswer {
 }
 
 /// Index based location in a text.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextLocation {
     /// The 0 based index, where the important part of the text starts.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1620,7 +1869,7 @@ pub struct TextLocation {
 }
 
 /// Index based location in a text for a particular reference.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReferenceSource {
     /// The type of search api result reference source.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1642,12 +1891,14 @@ pub struct ReferenceSource {
 /// include the summarized content or answer based on the key. The API can also respond back with an
 /// error response based on the incomplete summarization request, invalid subscription keys, and
 /// rate limit events. Access to Summarizer requires a subscription to Pro AI plan.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummarizerSearchApiResponse {
     /// The type of summarizer search API result. The value is always `summarizer`.
+    #[serde(default)]
     pub r#type: String,
     /// The current status of summarizer for the given key. The value can be either failed or
     /// complete.
+    #[serde(default)]
     pub status: String,
     /// Summarizer search results relevant to the query key. Available in API version `2023-08-25`.
     /// This version is now deprecated.
@@ -1665,9 +1916,27 @@ pub struct SummarizerSearchApiResponse {
     /// Details on the entities in the summary message. Available from API version `2024-04-23`
     /// onwards.
     pub entities_info: Option<HashMap<String, SummaryEntityInfo>>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
+}
+
+impl SummarizerSearchApiResponse {
+    /// Whether the summary has been completely generated.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.status == "complete"
+    }
+
+    /// The references the summary was built from, or an empty slice if there are none.
+    #[must_use]
+    pub fn references(&self) -> &[SummaryContext] {
+        self.enrichments.as_ref().and_then(|e| e.context.as_deref()).unwrap_or_default()
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum StringOrSummaryEntity {
     String(String),
     SummaryEntity(SummaryEntity),
@@ -1708,13 +1977,13 @@ impl<'de> Deserialize<'de> for StringOrSummaryEntity {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryImage {
     /// Text associated with the image.
     pub text: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryEntity {
     /// A unique identifier for the entity.
     pub uuid: Option<String>,
@@ -1730,12 +1999,13 @@ pub struct SummaryEntity {
     pub highlight: Option<Vec<TextLocation>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryMessage {
     /// The type of subset of a summary message. The value can be token (a text excerpt from the
     /// summary), `enum_item` (a summary entity), `enum_start` (describes the beginning of summary
     /// entities, which means the following item(s) in the summary list will be entities), or
     /// `enum_end` (the end of summary entities).
+    #[serde(default)]
     pub r#type: String,
     /// The summary entity or the explanation for the type field. For type `enum_start` the value can
     /// be ol or ul, which means an ordered list or an unordered list of entities follows
@@ -1744,7 +2014,7 @@ pub struct SummaryMessage {
     pub data: Option<StringOrSummaryEntity>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryEntityInfo {
     /// The name of the provider.
     pub provider: Option<String>,
@@ -1753,10 +2023,10 @@ pub struct SummaryEntityInfo {
 }
 
 /// Enrichments associated with the summary message.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryEnrichments {
     /// The raw summary message.
-    pub raw: String,
+    pub raw: Option<String>,
     /// The images associated with the summary.
     pub images: Option<Vec<SummaryImage>>,
     /// The answers in the summary message.
@@ -1768,29 +2038,29 @@ pub struct SummaryEnrichments {
 }
 
 /// The answer if the query is a question.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryAnswer {
     /// The answer text.
-    answer: Option<String>,
+    pub answer: Option<String>,
     /// A score associated with the answer.
-    score: Option<f32>,
+    pub score: Option<f32>,
     /// The location of the answer in the summary message.
-    highlight: Option<TextLocation>,
+    pub highlight: Option<TextLocation>,
 }
 
 /// A reference for the summary.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummaryContext {
     /// A title for the reference.
-    title: Option<String>,
+    pub title: Option<String>,
     /// The url where the reference can be found.
-    url: Option<String>,
+    pub url: Option<String>,
     /// Details on the url associated with the reference.
-    meta_url: MetaUrl,
+    pub meta_url: Option<MetaUrl>,
 }
 
 /// Parameters supported by the Suggest Search API.
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct SuggestSearchParams {
     /// The user's search query term.
     ///
@@ -1802,21 +2072,21 @@ pub struct SuggestSearchParams {
     /// The country string is limited to 2 character country code of supported countries. For a list
     /// of supported values, see [Country
     /// Codes](https://api.search.brave.com/app/documentation/web-search/codes#country-codes)
-    pub country: Option<String>,
+    pub country: Option<CountryCode>,
 
     /// The search language preference.
     ///
     /// The 2 or more character language code for which the suggest search results are provided.
     /// This is just a hint for calculating suggest responses. For a list of complete values, see [Language
     /// Codes.](https://api.search.brave.com/app/documentation/web-search/codes#language-codes)
-    pub lang: Option<String>,
+    pub lang: Option<LanguageCode>,
 
     // The number of suggestions returned. This is done as best effort. The maximum is 20.
     pub count: Option<u16>,
 
     /// Whether to enhance suggestions with rich results. This is an extra option in plans which
     /// needs to be enabled.
-    pub rich: Option<String>,
+    pub rich: Option<bool>,
 }
 
 impl SuggestSearchParams {
@@ -1825,6 +2095,23 @@ impl SuggestSearchParams {
         Self { q: q.to_string(), ..Default::default() }
     }
 
+    /// Creates a builder that validates the parameters on `build`.
+    #[must_use]
+    pub fn builder(q: &str) -> SuggestSearchParamsBuilder {
+        SuggestSearchParamsBuilder::new(q)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the query or `count` are outside of the limits supported by the API.
+    pub fn validate(&self) -> StdResult<(), ValidationError> {
+        validate_query(&self.q)?;
+        if let Some(count) = self.count {
+            validate_count(count)?;
+        }
+        Ok(())
+    }
+
     #[must_use]
     pub fn to_query_params(&self) -> Vec<(String, String)> {
         let mut params = Vec::new();
@@ -1833,16 +2120,16 @@ impl SuggestSearchParams {
         params.push(("q".to_string(), self.q.clone()));
 
         if let Some(ref country) = self.country {
-            params.push(("country".to_string(), country.clone()));
+            params.push(("country".to_string(), country.to_string()));
         }
         if let Some(ref lang) = self.lang {
-            params.push(("lang".to_string(), lang.clone()));
+            params.push(("lang".to_string(), lang.to_string()));
         }
         if let Some(count) = self.count {
             params.push(("count".to_string(), count.to_string()));
         }
-        if let Some(ref rich) = self.rich {
-            params.push(("rich".to_string(), rich.to_string()));
+        if let Some(rich) = self.rich {
+            params.push(("rich".to_string(), params::flag(rich)));
         }
 
         params
@@ -1851,34 +2138,58 @@ impl SuggestSearchParams {
 
 /// Top level response model for successful Suggest API requests. The API can also respond back with
 /// an error response based on invalid subscription keys and rate limit events.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SuggestSearchApiResponse {
     /// The type of search api result. The value is always suggest.
-    r#type: String,
+    #[serde(default)]
+    pub r#type: String,
     /// Suggest search query string. Only the original query is returned.
-    query: Query,
+    #[serde(default)]
+    pub query: Query,
     /// The list of suggestions for the given query.
-    results: Vec<SuggestResult>,
+    #[serde(default)]
+    pub results: Vec<SuggestResult>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
+}
+
+impl SuggestSearchApiResponse {
+    /// The suggested queries, in order.
+    #[must_use]
+    pub fn queries(&self) -> Vec<&str> {
+        self.results.iter().filter_map(|result| result.query.as_deref()).collect()
+    }
 }
 
 /// Suggestions for a query.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SuggestResult {
     /// Suggested query completion.
-    query: Option<String>,
+    pub query: Option<String>,
     /// Whether the suggested enriched query is an entity.
-    is_entity: Option<bool>,
+    pub is_entity: Option<bool>,
     /// The suggested query enriched title
-    title: Option<String>,
+    pub title: Option<String>,
     /// The suggested enriched description.
-    description: Option<String>,
+    pub description: Option<String>,
     /// The suggested query enriched image url.
-    img: Option<String>,
+    pub img: Option<String>,
+    /// Fields returned by the API that are not modeled yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Json>,
 }
 
 // Brave Web Search API
-const WEB_SEARCH: &str = "web/search";
+pub const WEB_SEARCH: &str = "web/search";
 // Brave Summarizer API
-const SUMMARIZER: &str = "summarizer/search";
+pub const SUMMARIZER: &str = "summarizer/search";
+pub const SUMMARIZER_SUMMARY: &str = "summarizer/summary";
+pub const SUMMARIZER_TITLE: &str = "summarizer/title";
+pub const SUMMARIZER_ENRICHMENTS: &str = "summarizer/enrichments";
+pub const SUMMARIZER_FOLLOWUPS: &str = "summarizer/followups";
+pub const SUMMARIZER_ENTITY_INFO: &str = "summarizer/entity_info";
 // Brave Suggest API
-const SUGGEST: &str = "suggest/search";
+pub const SUGGEST: &str = "suggest/search";
+// Brave Spellcheck API
+pub const SPELLCHECK: &str = "spellcheck/search";
@@ -0,0 +1,479 @@
+//! Typed values for the Brave Search API query parameters.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+
+/// Maximum number of characters allowed in a query.
+pub const MAX_QUERY_CHARS: usize = 400;
+/// Maximum number of words allowed in a query.
+pub const MAX_QUERY_WORDS: usize = 50;
+/// Maximum number of results that can be requested on a single call.
+pub const MAX_COUNT: u16 = 20;
+/// Maximum zero based page offset.
+pub const MAX_OFFSET: u16 = 9;
+
+/// Checks the query against the length limits imposed by the API.
+///
+/// # Errors
+///
+/// Will return `Err` if the query is empty, or has more than 400 characters or 50 words.
+pub fn validate_query(q: &str) -> Result<(), ValidationError> {
+    if q.trim().is_empty() {
+        return Err(ValidationError::EmptyQuery);
+    }
+    let chars = q.chars().count();
+    if chars > MAX_QUERY_CHARS {
+        return Err(ValidationError::QueryTooLong(chars));
+    }
+    let words = q.split_whitespace().count();
+    if words > MAX_QUERY_WORDS {
+        return Err(ValidationError::TooManyWords(words));
+    }
+    Ok(())
+}
+
+/// Checks that `count` is between `1` and `20`.
+///
+/// # Errors
+///
+/// Will return `Err` if `count` is outside of the supported range.
+pub fn validate_count(count: u16) -> Result<(), ValidationError> {
+    if (1..=MAX_COUNT).contains(&count) {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidCount(count))
+    }
+}
+
+/// Checks that `offset` is not greater than `9`.
+///
+/// # Errors
+///
+/// Will return `Err` if `offset` is outside of the supported range.
+pub fn validate_offset(offset: u16) -> Result<(), ValidationError> {
+    if offset <= MAX_OFFSET {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidOffset(offset))
+    }
+}
+
+/// Filters search results for adult content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SafeSearch {
+    /// Adult content is included where relevant.
+    Off,
+    /// Adult text but no adult images or videos.
+    #[default]
+    Moderate,
+    /// No adult content with adult text, images, and videos.
+    Strict,
+}
+
+impl SafeSearch {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Moderate => "moderate",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl fmt::Display for SafeSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SafeSearch {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "moderate" => Ok(Self::Moderate),
+            "strict" => Ok(Self::Strict),
+            _ => Err(ValidationError::InvalidSafeSearch(s.to_string())),
+        }
+    }
+}
+
+/// The measurement units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// The standardized measurement system.
+    Metric,
+    /// The British Imperial system of units.
+    Imperial,
+}
+
+impl Units {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Imperial => "imperial",
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Units {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "metric" => Ok(Self::Metric),
+            "imperial" => Ok(Self::Imperial),
+            _ => Err(ValidationError::InvalidUnits(s.to_string())),
+        }
+    }
+}
+
+/// A timeframe used to filter search results by when they were discovered.
+///
+/// Both dates use the `YYYY-MM-DD` format.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    from: String,
+    to: String,
+}
+
+impl DateRange {
+    /// # Errors
+    ///
+    /// Will return `Err` if any of the dates doesn't follow the `YYYY-MM-DD` format, or isn't a
+    /// day of the calendar, or if `from` is after `to`.
+    pub fn new(from: &str, to: &str) -> Result<Self, ValidationError> {
+        // Dates of the same format compare in calendar order.
+        if is_date(from) && is_date(to) && from <= to {
+            Ok(Self { from: from.to_string(), to: to.to_string() })
+        } else {
+            Err(ValidationError::InvalidFreshness(format!("{from}to{to}")))
+        }
+    }
+
+    #[must_use]
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    #[must_use]
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    let shaped = s.len() == 10
+        && s.char_indices()
+            .all(|(i, c)| if i == 4 || i == 7 { c == '-' } else { c.is_ascii_digit() });
+    if !shaped {
+        return false;
+    }
+
+    let number = |range: std::ops::Range<usize>| s[range].parse::<u32>().unwrap_or_default();
+    let (year, month, day) = (number(0..4), number(5..7), number(8..10));
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Filters search results by when they were discovered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Freshness {
+    /// Discovered within the last 24 hours.
+    PastDay,
+    /// Discovered within the last 7 days.
+    PastWeek,
+    /// Discovered within the last 31 days.
+    PastMonth,
+    /// Discovered within the last 365 days.
+    PastYear,
+    /// Discovered within the given timeframe.
+    Range(DateRange),
+}
+
+impl fmt::Display for Freshness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PastDay => f.write_str("pd"),
+            Self::PastWeek => f.write_str("pw"),
+            Self::PastMonth => f.write_str("pm"),
+            Self::PastYear => f.write_str("py"),
+            Self::Range(range) => write!(f, "{}to{}", range.from, range.to),
+        }
+    }
+}
+
+impl FromStr for Freshness {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pd" => Ok(Self::PastDay),
+            "pw" => Ok(Self::PastWeek),
+            "pm" => Ok(Self::PastMonth),
+            "py" => Ok(Self::PastYear),
+            _ => s
+                .split_once("to")
+                .and_then(|(from, to)| DateRange::new(from, to).ok())
+                .map(Self::Range)
+                .ok_or_else(|| ValidationError::InvalidFreshness(s.to_string())),
+        }
+    }
+}
+
+/// A result type that can be included in the search response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultType {
+    Discussions,
+    Faq,
+    Infobox,
+    News,
+    Query,
+    Summarizer,
+    Videos,
+    Web,
+}
+
+impl ResultType {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Discussions => "discussions",
+            Self::Faq => "faq",
+            Self::Infobox => "infobox",
+            Self::News => "news",
+            Self::Query => "query",
+            Self::Summarizer => "summarizer",
+            Self::Videos => "videos",
+            Self::Web => "web",
+        }
+    }
+}
+
+impl fmt::Display for ResultType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ResultType {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "discussions" => Ok(Self::Discussions),
+            "faq" => Ok(Self::Faq),
+            "infobox" => Ok(Self::Infobox),
+            "news" => Ok(Self::News),
+            "query" => Ok(Self::Query),
+            "summarizer" => Ok(Self::Summarizer),
+            "videos" => Ok(Self::Videos),
+            "web" => Ok(Self::Web),
+            _ => Err(ValidationError::InvalidResultFilter(s.to_string())),
+        }
+    }
+}
+
+/// The set of result types to include in the search response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ResultFilter(BTreeSet<ResultType>);
+
+impl ResultFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a result type to the filter.
+    #[must_use]
+    pub fn with(mut self, result_type: ResultType) -> Self {
+        self.0.insert(result_type);
+        self
+    }
+
+    #[must_use]
+    pub fn contains(&self, result_type: ResultType) -> bool {
+        self.0.contains(&result_type)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ResultType> {
+        self.0.iter()
+    }
+}
+
+impl FromIterator<ResultType> for ResultFilter {
+    fn from_iter<I: IntoIterator<Item = ResultType>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for ResultFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self.0.iter().map(ResultType::as_str).collect::<Vec<&str>>();
+        f.write_str(&values.join(","))
+    }
+}
+
+impl FromStr for ResultFilter {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<ResultType>()
+                    .map_err(|_| ValidationError::InvalidResultFilter(s.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// A 2 character country code, or `ALL` for worldwide results.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CountryCode(String);
+
+impl CountryCode {
+    /// # Errors
+    ///
+    /// Will return `Err` if `code` isn't a 2 character country code or `ALL`.
+    pub fn new(code: &str) -> Result<Self, ValidationError> {
+        let upper = code.to_ascii_uppercase();
+        if upper == "ALL" || (upper.len() == 2 && upper.chars().all(|c| c.is_ascii_alphabetic())) {
+            Ok(Self(upper))
+        } else {
+            Err(ValidationError::InvalidCountry(code.to_string()))
+        }
+    }
+
+    /// Country code used to request worldwide results.
+    #[must_use]
+    pub fn all() -> Self {
+        Self("ALL".to_string())
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CountryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for CountryCode {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+/// A language code, like `en`, `pt-br` or `en-US`.
+///
+/// The primary subtag must be 2 or 3 letters long, and can be followed by any number of
+/// alphanumeric subtags separated by `-`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LanguageCode(String);
+
+impl LanguageCode {
+    /// # Errors
+    ///
+    /// Will return `Err` if `code` isn't a valid language code.
+    pub fn new(code: &str) -> Result<Self, ValidationError> {
+        let mut subtags = code.split('-');
+        let primary = subtags.next().unwrap_or_default();
+        let valid_primary =
+            (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic());
+        let valid_rest = subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        if valid_primary && valid_rest {
+            Ok(Self(code.to_string()))
+        } else {
+            Err(ValidationError::InvalidLanguage(code.to_string()))
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for LanguageCode {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+macro_rules! impl_string_conversions {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<String> for $t {
+                type Error = ValidationError;
+
+                fn try_from(value: String) -> Result<Self, Self::Error> {
+                    value.parse()
+                }
+            }
+
+            impl From<$t> for String {
+                fn from(value: $t) -> Self {
+                    value.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_string_conversions!(Freshness, ResultFilter, CountryCode, LanguageCode);
+
+/// The value of a boolean query parameter: `1` or `0`.
+pub(crate) fn flag(value: bool) -> String {
+    if value { "1" } else { "0" }.to_string()
+}
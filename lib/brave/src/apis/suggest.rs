@@ -3,14 +3,14 @@
 //! Suggest API
 
 use crate::query::Query;
-use crate::{error, ApiResult, Brave, SuggestSearchApiResponse, SuggestSearchParams};
+use crate::{de, ApiResult, Brave, SuggestSearchApiResponse, SuggestSearchParams};
 
 use super::SUGGEST;
 
 pub trait Api {
     /// # Errors
     ///
-    /// Will return `Err` if the POST request fails for some reason.
+    /// Will return `Err` if the parameters are invalid, or the request fails for some reason.
     fn suggest(
         &self,
         params: &SuggestSearchParams,
@@ -24,14 +24,13 @@ impl Api for Brave {
         params: &SuggestSearchParams,
         version: Option<&str>,
     ) -> ApiResult<SuggestSearchApiResponse> {
+        params.validate()?;
         let query_params = params.to_query_params();
         let res = self.query(
             SUGGEST,
             Some(query_params.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect()),
             version,
         )?;
-        let response: SuggestSearchApiResponse =
-            serde_json::from_value(res).map_err(error::Error::DeserializeError)?;
-        Ok(response)
+        de::from_json(res, self.strict)
     }
 }
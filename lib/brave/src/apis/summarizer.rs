@@ -2,48 +2,336 @@
 
 //! Summarizer API
 
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use sse::{Events, Sse};
+
 use crate::query::Query;
 use crate::{
-    error, ApiResult, Brave, SummarizerSearchApiResponse, WebSearchApiResponse, WebSearchParams,
+    de, error, ApiResult, Brave, Json, SummarizerSearchApiResponse, SummaryEnrichments,
+    SummaryEntityInfo, SummaryMessage, WebSearchApiResponse, WebSearchParams,
+};
+
+use super::{
+    SUMMARIZER, SUMMARIZER_ENRICHMENTS, SUMMARIZER_ENTITY_INFO, SUMMARIZER_FOLLOWUPS,
+    SUMMARIZER_SUMMARY, SUMMARIZER_TITLE, WEB_SEARCH,
 };
 
-use super::{SUMMARIZER, WEB_SEARCH};
+/// Default amount of time to wait for a summary to complete.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time to wait between requests while polling for a summary.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 pub trait Api {
+    /// Runs the search and polls the summarizer until the summary is complete, waiting at most
+    /// `DEFAULT_TIMEOUT`.
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if the POST request fails for some reason.
+    /// Will return `Err` if the parameters are invalid, the request fails for some reason, the
+    /// summary fails, or it doesn't complete in time.
     fn summarize(
         &self,
         params: &WebSearchParams,
         version: Option<&str>,
+    ) -> ApiResult<SummarizerSearchApiResponse> {
+        self.summarize_with_timeout(params, version, DEFAULT_TIMEOUT)
+    }
+
+    /// Runs the search and polls the summarizer until the summary is complete, waiting at most
+    /// `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the parameters are invalid, the request fails for some reason, the
+    /// summary fails, or it doesn't complete in time.
+    fn summarize_with_timeout(
+        &self,
+        params: &WebSearchParams,
+        version: Option<&str>,
+        timeout: Duration,
     ) -> ApiResult<SummarizerSearchApiResponse>;
+
+    /// Polls the summarizer for the summary of a previous search until it's complete, waiting at
+    /// most `timeout`. The key can be found with `WebSearchApiResponse::summary_key`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason, the summary fails, or it doesn't
+    /// complete in time.
+    fn summarize_key(
+        &self,
+        key: &str,
+        version: Option<&str>,
+        timeout: Duration,
+    ) -> ApiResult<SummarizerSearchApiResponse>;
+
+    /// Gets the summary message for the given summary key, along with the entities details if
+    /// `entity_info` is set.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason.
+    fn summary(
+        &self,
+        key: &str,
+        entity_info: bool,
+        version: Option<&str>,
+    ) -> ApiResult<SummarizerSearchApiResponse>;
+
+    /// Gets the title of the summary for the given summary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason.
+    fn summary_title(&self, key: &str, version: Option<&str>) -> ApiResult<Option<String>>;
+
+    /// Gets the enrichments of the summary for the given summary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason.
+    fn summary_enrichments(
+        &self,
+        key: &str,
+        version: Option<&str>,
+    ) -> ApiResult<Option<SummaryEnrichments>>;
+
+    /// Gets the followup queries of the summary for the given summary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason.
+    fn summary_followups(&self, key: &str, version: Option<&str>) -> ApiResult<Vec<String>>;
+
+    /// Gets the details of the entities in the summary for the given summary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails for some reason.
+    fn summary_entity_info(
+        &self,
+        key: &str,
+        version: Option<&str>,
+    ) -> ApiResult<HashMap<String, SummaryEntityInfo>>;
+
+    /// Runs the search and streams the summary as it's being generated. A stream cut in the
+    /// middle of an event ends with `Error::UnexpectedEof`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the parameters are invalid, or the request that opens the stream
+    /// fails for some reason.
+    fn summarize_stream(
+        &self,
+        params: &WebSearchParams,
+        version: Option<&str>,
+    ) -> ApiResult<SummaryStream<Box<dyn Read + Send + Sync + 'static>>>;
 }
 
 impl Api for Brave {
-    fn summarize(
+    fn summarize_with_timeout(
         &self,
         params: &WebSearchParams,
         version: Option<&str>,
+        timeout: Duration,
+    ) -> ApiResult<SummarizerSearchApiResponse> {
+        let key = summary_key(self, params, version)?;
+
+        self.summarize_key(&key, version, timeout)
+    }
+
+    fn summarize_key(
+        &self,
+        key: &str,
+        version: Option<&str>,
+        timeout: Duration,
     ) -> ApiResult<SummarizerSearchApiResponse> {
-        let query_params = params.to_query_params();
-        let res = self.query(
-            WEB_SEARCH,
-            Some(query_params.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect()),
+        let start = Instant::now();
+
+        loop {
+            let response: SummarizerSearchApiResponse =
+                keyed_query(self, SUMMARIZER, key, &[("entity_info", "1")], version)?;
+
+            if response.is_complete() {
+                return Ok(response);
+            }
+            if response.status == "failed" {
+                return Err(error::Error::ApiError(
+                    None,
+                    format!("Summarizer failed for key {key}"),
+                ));
+            }
+            log::debug!("Summarizer status: {}", response.status);
+
+            if start.elapsed() + POLL_INTERVAL > timeout {
+                return Err(error::Error::TimedOut);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn summarize_stream(
+        &self,
+        params: &WebSearchParams,
+        version: Option<&str>,
+    ) -> ApiResult<SummaryStream<Box<dyn Read + Send + Sync + 'static>>> {
+        let key = summary_key(self, params, version)?;
+        let events = self.query_stream(
+            SUMMARIZER,
+            Some(vec![("key", &key), ("entity_info", "1"), ("streaming", "1")]),
             version,
         )?;
-        let response: WebSearchApiResponse =
-            serde_json::from_value(res).map_err(error::Error::DeserializeError)?;
 
-        if let Some(summarizer) = response.summarizer {
-            let key = summarizer.key;
-            let res =
-                self.query(SUMMARIZER, Some(vec![("key", &key), ("entity_info", "1")]), version)?;
+        Ok(SummaryStream { events, strict: self.strict })
+    }
+
+    fn summary(
+        &self,
+        key: &str,
+        entity_info: bool,
+        version: Option<&str>,
+    ) -> ApiResult<SummarizerSearchApiResponse> {
+        let extra: &[(&str, &str)] = if entity_info { &[("entity_info", "1")] } else { &[] };
+
+        keyed_query(self, SUMMARIZER_SUMMARY, key, extra, version)
+    }
+
+    fn summary_title(&self, key: &str, version: Option<&str>) -> ApiResult<Option<String>> {
+        let response: SummarizerSearchApiResponse =
+            keyed_query(self, SUMMARIZER_TITLE, key, &[], version)?;
+
+        Ok(response.title)
+    }
+
+    fn summary_enrichments(
+        &self,
+        key: &str,
+        version: Option<&str>,
+    ) -> ApiResult<Option<SummaryEnrichments>> {
+        let response: SummarizerSearchApiResponse =
+            keyed_query(self, SUMMARIZER_ENRICHMENTS, key, &[], version)?;
+
+        Ok(response.enrichments)
+    }
+
+    fn summary_followups(&self, key: &str, version: Option<&str>) -> ApiResult<Vec<String>> {
+        let response: SummarizerSearchApiResponse =
+            keyed_query(self, SUMMARIZER_FOLLOWUPS, key, &[], version)?;
+
+        Ok(response.followups.unwrap_or_default())
+    }
+
+    fn summary_entity_info(
+        &self,
+        key: &str,
+        version: Option<&str>,
+    ) -> ApiResult<HashMap<String, SummaryEntityInfo>> {
+        let response: SummarizerSearchApiResponse =
+            keyed_query(self, SUMMARIZER_ENTITY_INFO, key, &[], version)?;
+
+        Ok(response.entities_info.unwrap_or_default())
+    }
+}
+
+impl WebSearchApiResponse {
+    /// Returns the key that can be used to get the summary of this search from the summarizer
+    /// api, if a summary was requested and is available.
+    #[must_use]
+    pub fn summary_key(&self) -> Option<&str> {
+        self.summarizer
+            .as_ref()
+            .map(|summarizer| summarizer.key.as_str())
+            .filter(|key| !key.is_empty())
+    }
+}
+
+/// Sends a request to one of the summarizer endpoints keyed by the summary key.
+fn keyed_query<T: DeserializeOwned>(
+    client: &Brave,
+    sub_url: &str,
+    key: &str,
+    extra: &[(&str, &str)],
+    version: Option<&str>,
+) -> ApiResult<T> {
+    let mut query_pairs = vec![("key", key)];
+    query_pairs.extend_from_slice(extra);
+
+    let res = client.query(sub_url, Some(query_pairs), version)?;
+    log::debug!("Summarizer response: {:#?}", res);
+
+    de::from_json(res, client.strict)
+}
+
+/// Runs the web search and returns the summary key from its response.
+fn summary_key(
+    client: &Brave,
+    params: &WebSearchParams,
+    version: Option<&str>,
+) -> ApiResult<String> {
+    params.validate()?;
+    let mut params = params.clone();
+    params.summary = Some(true);
+
+    let query_params = params.to_query_params();
+    let res = client.query(
+        WEB_SEARCH,
+        Some(query_params.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect()),
+        version,
+    )?;
+    let response: WebSearchApiResponse = de::from_json(res, client.strict)?;
+
+    response
+        .summary_key()
+        .map(ToString::to_string)
+        .ok_or_else(|| error::Error::ApiError(None, "No summarizer found".to_string()))
+}
+
+/// An update received while streaming a summary.
+#[derive(Debug)]
+pub enum SummaryEvent {
+    /// A new part of the summary message.
+    Message(SummaryMessage),
+    /// Any other payload sent by the API, like the title, enrichments or followups.
+    Other(Json),
+}
+
+/// Lazy iterator over the updates of a streamed summary. See `Api::summarize_stream`.
+#[derive(Debug)]
+pub struct SummaryStream<R: Read> {
+    events: Events<R>,
+    strict: bool,
+}
+
+impl<R: Read> Iterator for SummaryStream<R> {
+    type Item = ApiResult<SummaryEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = loop {
+            match self.events.next()? {
+                Ok(Sse::Event(event)) => break event,
+                Ok(Sse::Comment(_)) => {}
+                Err(err) => return Some(Err(err.into())),
+            }
+        };
+
+        let json: Json = match serde_json::from_str(&event.data) {
+            Ok(json) => json,
+            Err(err) => return Some(Err(error::Error::DeserializeError(err))),
+        };
+
+        let is_message = json
+            .get("type")
+            .and_then(Json::as_str)
+            .is_some_and(|t| matches!(t, "token" | "enum_start" | "enum_item" | "enum_end"));
 
-            log::debug!("Summarizer response: {:#?}", res);
-            Ok(serde_json::from_value(res).map_err(error::Error::DeserializeError)?)
+        if is_message {
+            Some(de::from_json(json, self.strict).map(SummaryEvent::Message))
         } else {
-            Err(error::Error::ApiError("No summarizer found".to_string()))
+            Some(Ok(SummaryEvent::Other(json)))
         }
     }
 }
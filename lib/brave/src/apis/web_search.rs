@@ -2,20 +2,53 @@
 
 //! Web Search API
 
+use std::collections::{BTreeSet, VecDeque};
+
 use crate::query::Query;
-use crate::{error, ApiResult, Brave, WebSearchApiResponse, WebSearchParams};
+use crate::{de, ApiResult, Brave, WebSearchApiResponse, WebSearchParams};
 
-use super::WEB_SEARCH;
+use super::{MAX_COUNT, MAX_OFFSET, WEB_SEARCH};
 
 pub trait Api {
     /// # Errors
     ///
-    /// Will return `Err` if the POST request fails for some reason.
+    /// Will return `Err` if the parameters are invalid, or the request fails for some reason.
     fn search(
         &self,
         params: &WebSearchParams,
         version: Option<&str>,
     ) -> ApiResult<WebSearchApiResponse>;
+
+    /// Returns a lazy iterator over the result pages of a web search.
+    ///
+    /// Pages are requested one at a time, starting at `params.offset`, until the API reports that
+    /// no more results are available or the maximum offset is reached. When `params.count` is not
+    /// set, the maximum of `20` results per page is requested.
+    fn search_pages<'a>(
+        &'a self,
+        params: &WebSearchParams,
+        version: Option<&str>,
+    ) -> Pages<'a, Self>
+    where
+        Self: Sized,
+    {
+        Pages::new(self, params, version)
+    }
+
+    /// Returns a lazy iterator over the web results of every page of a web search.
+    ///
+    /// Results are deduplicated by url, since pages may overlap. Use `Iterator::take` to limit the
+    /// number of results, and therefore the number of requests made.
+    fn search_all<'a>(
+        &'a self,
+        params: &WebSearchParams,
+        version: Option<&str>,
+    ) -> Results<'a, Self>
+    where
+        Self: Sized,
+    {
+        Results::new(self.search_pages(params, version))
+    }
 }
 
 impl Api for Brave {
@@ -24,14 +57,104 @@ impl Api for Brave {
         params: &WebSearchParams,
         version: Option<&str>,
     ) -> ApiResult<WebSearchApiResponse> {
+        params.validate()?;
         let query_params = params.to_query_params();
         let res = self.query(
             WEB_SEARCH,
             Some(query_params.iter().map(|(k, v)| (k.as_ref(), v.as_ref())).collect()),
             version,
         )?;
-        let response: WebSearchApiResponse =
-            serde_json::from_value(res).map_err(error::Error::DeserializeError)?;
-        Ok(response)
+        de::from_json(res, self.strict)
+    }
+}
+
+/// Iterator over the result pages of a web search. See `Api::search_pages`.
+#[derive(Debug)]
+pub struct Pages<'a, C: Api> {
+    client: &'a C,
+    params: WebSearchParams,
+    version: Option<String>,
+    offset: u16,
+    done: bool,
+}
+
+impl<'a, C: Api> Pages<'a, C> {
+    fn new(client: &'a C, params: &WebSearchParams, version: Option<&str>) -> Self {
+        let mut params = params.clone();
+        params.count = Some(params.count.unwrap_or(MAX_COUNT));
+
+        Self {
+            client,
+            offset: params.offset.unwrap_or_default(),
+            params,
+            version: version.map(ToString::to_string),
+            done: false,
+        }
+    }
+}
+
+impl<C: Api> Iterator for Pages<'_, C> {
+    type Item = ApiResult<WebSearchApiResponse>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset > MAX_OFFSET {
+            return None;
+        }
+
+        self.params.offset = Some(self.offset);
+        let response = match self.client.search(&self.params, self.version.as_deref()) {
+            Ok(response) => response,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+
+        let more_results_available =
+            response.query.as_ref().and_then(|q| q.more_results_available).unwrap_or(false);
+        let has_results =
+            response.web.as_ref().and_then(|w| w.results.as_ref()).is_some_and(|r| !r.is_empty());
+
+        self.done = !more_results_available || !has_results;
+        self.offset += 1;
+
+        Some(Ok(response))
+    }
+}
+
+/// Iterator over the deduplicated web results of every page of a web search. See
+/// `Api::search_all`.
+#[derive(Debug)]
+pub struct Results<'a, C: Api> {
+    pages: Pages<'a, C>,
+    buffer: VecDeque<crate::Result>,
+    seen: BTreeSet<String>,
+}
+
+impl<'a, C: Api> Results<'a, C> {
+    fn new(pages: Pages<'a, C>) -> Self {
+        Self { pages, buffer: VecDeque::new(), seen: BTreeSet::new() }
+    }
+}
+
+impl<C: Api> Iterator for Results<'_, C> {
+    type Item = ApiResult<crate::Result>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while let Some(result) = self.buffer.pop_front() {
+                match result.url {
+                    Some(ref url) if !self.seen.insert(url.clone()) => continue,
+                    _ => return Some(Ok(result)),
+                }
+            }
+
+            match self.pages.next()? {
+                Ok(response) => {
+                    self.buffer.extend(response.web.and_then(|w| w.results).unwrap_or_default());
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
     }
 }
@@ -1,30 +1,102 @@
+use std::collections::BTreeMap;
+
+use cassette::Cassette;
+
+use crate::cache::Cache;
+use crate::subscription::{Feature, Subscription};
+use crate::{error::Error, ApiResult};
+use http_agent::Client;
 use serde::{Deserialize, Serialize};
 use ureq::{Agent, AgentBuilder};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The subscription tokens of a client.
+///
+/// Every request is sent with the token of a subscription that covers it, see
+/// `Feature::subscriptions`, or with the catch-all `subscription_token` when none does.
+///
+/// ```
+/// use brave::{subscription::Subscription, Auth};
+///
+/// let auth = Auth::default()
+///     .token(Subscription::DataForAi, "ai-token")
+///     .token(Subscription::Suggest, "suggest-token");
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Auth {
-    pub subscription_token: String,
+    /// Token sent when no token of `tokens` covers the request, whatever its plan.
+    pub subscription_token: Option<String>,
+    /// Token of each subscription.
+    #[serde(default)]
+    pub tokens: BTreeMap<Subscription, String>,
 }
 
 impl Clone for Auth {
     fn clone(&self) -> Self {
-        Self { subscription_token: self.subscription_token.clone() }
+        Self { subscription_token: self.subscription_token.clone(), tokens: self.tokens.clone() }
     }
 }
 
 impl Auth {
+    /// A single token sent with every request, whatever its plan.
     #[must_use]
     pub fn new(subscription_token: &str) -> Self {
-        Self { subscription_token: subscription_token.to_string() }
+        Self { subscription_token: Some(subscription_token.to_string()), tokens: BTreeMap::new() }
+    }
+
+    /// Adds the token of a subscription, replacing the previous one.
+    #[must_use]
+    pub fn token(mut self, subscription: Subscription, token: &str) -> Self {
+        self.tokens.insert(subscription, token.to_string());
+        self
     }
 
+    /// Reads the catch-all token from `BRAVE_SUBSCRIPTION_TOKEN`, and the token of each
+    /// subscription from its variable, e.g. `BRAVE_SUGGEST_API_KEY`. See `Subscription::env`.
+    ///
+    /// The tokens aren't required while replaying a cassette, see `Cassette::from_env`.
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if the environment variable `BRAVE_SUBSCRIPTION_TOKEN` is not defined.
+    /// Will return `Err` if none of the environment variables is defined.
     pub fn from_env() -> Result<Self, String> {
-        let subscription_token = std::env::var("BRAVE_SUBSCRIPTION_TOKEN")
-            .map_err(|_| "Missing BRAVE_SUBSCRIPTION_TOKEN".to_string())?;
-        Ok(Self { subscription_token })
+        let mut auth = Self {
+            subscription_token: std::env::var("BRAVE_SUBSCRIPTION_TOKEN").ok(),
+            tokens: BTreeMap::new(),
+        };
+        for subscription in Subscription::ALL {
+            if let Ok(token) = std::env::var(subscription.env()) {
+                auth.tokens.insert(subscription, token);
+            }
+        }
+
+        if auth.subscription_token.is_none() && auth.tokens.is_empty() {
+            if !Cassette::from_env().is_some_and(|c| c.is_replay()) {
+                return Err("Missing BRAVE_SUBSCRIPTION_TOKEN".to_string());
+            }
+            auth.subscription_token = Some(String::new());
+        }
+        Ok(auth)
+    }
+
+    /// The token to send for a feature.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no subscription covers the feature, and there's no catch-all token.
+    pub fn token_for(&self, feature: Feature) -> ApiResult<&str> {
+        feature
+            .subscriptions()
+            .iter()
+            .find_map(|subscription| self.tokens.get(subscription))
+            .or(self.subscription_token.as_ref())
+            .map(String::as_str)
+            .ok_or(Error::NoSubscription(feature))
+    }
+
+    /// Whether a token can be sent for the feature.
+    #[must_use]
+    pub fn grants(&self, feature: Feature) -> bool {
+        self.token_for(feature).is_ok()
     }
 }
 
@@ -32,18 +104,102 @@ impl Auth {
 pub struct Brave {
     pub auth: Auth,
     pub api_url: String,
+    /// Whether responses are deserialized in strict mode. See `de::from_json`.
+    pub strict: bool,
+    /// Records or replays every request when set. See `Brave::cassette`.
+    pub cassette: Option<Cassette>,
+    /// Caches responses on disk when set. See `Brave::cache`.
+    pub cache: Option<Cache>,
     pub(crate) agent: Agent,
 }
 
 impl Clone for Brave {
     fn clone(&self) -> Self {
-        Self { auth: self.auth.clone(), api_url: self.api_url.clone(), agent: self.agent.clone() }
+        Self {
+            auth: self.auth.clone(),
+            api_url: self.api_url.clone(),
+            strict: self.strict,
+            cassette: self.cassette.clone(),
+            cache: self.cache.clone(),
+            agent: self.agent.clone(),
+        }
     }
 }
 
 impl Brave {
+    /// Creates a new client. A cassette is enabled if `CASSETTE_MODE` is set, see
+    /// `Cassette::from_env`.
     #[must_use]
     pub fn new(auth: Auth, api_url: &str) -> Self {
-        Self { auth, api_url: api_url.to_string(), agent: AgentBuilder::new().build() }
+        Self::with_agent(auth, api_url, AgentBuilder::new().build())
+    }
+
+    /// Creates a builder to set up the timeouts, proxy and TLS settings of the client.
+    #[must_use]
+    pub fn builder(auth: Auth, api_url: &str) -> ClientBuilder {
+        ClientBuilder::new(auth, api_url)
+    }
+
+    /// Enables or disables strict mode, which reports the exact path of any response value that
+    /// fails to deserialize.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets the cassette used to record every request and its response to disk, or to replay
+    /// them without reaching the network. `None` disables it.
+    #[must_use]
+    pub fn cassette(mut self, cassette: Option<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    /// Sets the on-disk cache used to serve repeated searches. `None` disables it.
+    #[must_use]
+    pub fn cache(mut self, cache: Option<Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The HTTP agent of the client, to send other requests, like fetching the pages of the
+    /// results, with the same timeouts, proxy and TLS settings.
+    #[must_use]
+    pub fn agent(&self) -> &Agent {
+        &self.agent
+    }
+}
+
+/// Builds a `Brave` client with custom HTTP settings.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use brave::{Auth, Brave};
+///
+/// let client = Brave::builder(Auth::new("token"), "https://api.search.brave.com/res/v1/")
+///     .timeout_connect(Duration::from_secs(5))
+///     .timeout_read(Duration::from_secs(30))
+///     .proxy("http://proxy.example.com:3128")
+///     .root_certificates(std::fs::read("internal-ca.pem").unwrap())
+///     .build()
+///     .unwrap();
+/// ```
+pub type ClientBuilder = http_agent::ClientBuilder<Brave>;
+
+impl Client for Brave {
+    type Auth = Auth;
+    type Error = Error;
+
+    fn with_agent(auth: Auth, api_url: &str, agent: Agent) -> Self {
+        Self {
+            auth,
+            api_url: api_url.to_string(),
+            strict: false,
+            cassette: Cassette::from_env(),
+            cache: None,
+            agent,
+        }
     }
 }
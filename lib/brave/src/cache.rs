@@ -0,0 +1,249 @@
+//! On-disk cache of API responses.
+//!
+//! Responses are stored one per file, keyed by endpoint, normalized query params and API version,
+//! and are served until their endpoint's TTL expires. Only endpoints with a TTL are cached: by
+//! default the web search and suggest endpoints. The summarizer endpoints are left out since they
+//! are polled until the summary completes.
+//!
+//! When the cache grows past its size limit, the least recently used responses are evicted.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Json, SUGGEST, WEB_SEARCH};
+
+/// Default amount of time a response is served from the cache.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+/// Default size limit of the cache, in bytes.
+pub const DEFAULT_MAX_SIZE: u64 = 100 * 1024 * 1024;
+/// Environment variable with the directory of the cache. See `Cache::from_env`.
+pub const DIR_ENV: &str = "BRAVE_CACHE_DIR";
+
+/// What identifies a cached response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Key {
+    pub endpoint: String,
+    /// Query params, sorted so their order doesn't matter.
+    pub params: Vec<(String, String)>,
+    pub version: Option<String>,
+}
+
+impl Key {
+    #[must_use]
+    pub fn new(endpoint: &str, params: &[(&str, &str)], version: Option<&str>) -> Self {
+        let mut params = params
+            .iter()
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect::<Vec<_>>();
+        params.sort();
+
+        Self { endpoint: endpoint.to_string(), params, version: version.map(ToString::to_string) }
+    }
+
+    fn file_name(&self) -> String {
+        let params = self.params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>();
+        let hash = cassette::fnv1a(
+            [self.endpoint.as_str(), self.version.as_deref().unwrap_or_default()]
+                .into_iter()
+                .chain(params.iter().map(String::as_str)),
+        );
+
+        format!("{}-{hash:016x}.json", self.endpoint.replace('/', "-"))
+    }
+}
+
+/// Whether the file name is one of `Key::file_name`, so the other files of the directory are left
+/// alone.
+fn is_entry_name(name: &str) -> bool {
+    let Some((endpoint, hash)) = name.strip_suffix(".json").and_then(|stem| stem.rsplit_once('-'))
+    else {
+        return false;
+    };
+
+    !endpoint.is_empty()
+        && endpoint
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_".contains(c))
+        && hash.len() == 16
+        && hash.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    key: Key,
+    /// Milliseconds since the unix epoch.
+    created_at: u64,
+    response: Json,
+}
+
+/// A directory of cached responses. See the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    pub dir: PathBuf,
+    /// TTL of each cached endpoint, e.g. `web/search`.
+    pub ttls: BTreeMap<String, Duration>,
+    /// Size limit of the cache, in bytes.
+    pub max_size: u64,
+    /// Skips the cached responses, and asks the API and any proxy in between for fresh ones with
+    /// `Cache-Control: no-cache`. Fresh responses are still stored.
+    pub bypass: bool,
+}
+
+impl Cache {
+    /// Creates a cache that stores the web search and suggest responses for `DEFAULT_TTL`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttls: [(WEB_SEARCH.to_string(), DEFAULT_TTL), (SUGGEST.to_string(), DEFAULT_TTL)]
+                .into_iter()
+                .collect(),
+            max_size: DEFAULT_MAX_SIZE,
+            bypass: false,
+        }
+    }
+
+    /// Creates a cache in the directory set by `BRAVE_CACHE_DIR`, if any.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        std::env::var(DIR_ENV).ok().filter(|dir| !dir.is_empty()).map(Self::new)
+    }
+
+    /// Sets the TTL of the endpoint. A zero TTL disables caching for it.
+    #[must_use]
+    pub fn ttl(mut self, endpoint: &str, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint.to_string(), ttl);
+        self
+    }
+
+    /// Sets the TTL of every cached endpoint.
+    #[must_use]
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.ttls.values_mut().for_each(|value| *value = ttl);
+        self
+    }
+
+    /// Sets the size limit of the cache, in bytes.
+    #[must_use]
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets whether cached responses are skipped. See `Cache::bypass`.
+    #[must_use]
+    pub fn bypass(mut self, bypass: bool) -> Self {
+        self.bypass = bypass;
+        self
+    }
+
+    /// Whether responses of the endpoint are cached.
+    #[must_use]
+    pub fn caches(&self, endpoint: &str) -> bool {
+        self.ttls.get(endpoint).is_some_and(|ttl| !ttl.is_zero())
+    }
+
+    /// Returns the cached response for the key, unless it's missing, expired or bypassed.
+    #[must_use]
+    pub fn get(&self, key: &Key) -> Option<Json> {
+        if self.bypass || !self.caches(&key.endpoint) {
+            return None;
+        }
+
+        let path = self.dir.join(key.file_name());
+        let entry: Entry = serde_json::from_slice(&std::fs::read(&path).ok()?)
+            .map_err(|err| log::warn!("Ignoring invalid cache entry {}: {err}", path.display()))
+            .ok()?;
+        if entry.key != *key {
+            return None;
+        }
+
+        let ttl = self.ttls.get(&key.endpoint).copied().unwrap_or_default();
+        if u128::from(now().saturating_sub(entry.created_at)) >= ttl.as_millis() {
+            log::debug!("Cache entry expired: {}", path.display());
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+
+        // Keeps track of the last use, to evict the least recently used entries first.
+        let _ = std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        log::debug!("Cache hit: {}", path.display());
+
+        Some(entry.response)
+    }
+
+    /// Stores the response for the key, and evicts old entries if the cache is too big.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the cache directory can't be written.
+    pub fn put(&self, key: &Key, response: &Json) -> std::io::Result<()> {
+        if !self.caches(&key.endpoint) {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir)?;
+        let entry = Entry { key: key.clone(), created_at: now(), response: response.clone() };
+        std::fs::write(self.dir.join(key.file_name()), serde_json::to_vec(&entry)?)?;
+
+        self.evict()
+    }
+
+    /// Removes every cached response.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the cache directory can't be read or cleaned up.
+    pub fn clear(&self) -> std::io::Result<()> {
+        for (path, _, _) in self.entries()? {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the least recently used entries until the cache fits its size limit.
+    fn evict(&self) -> std::io::Result<()> {
+        let mut entries = self.entries()?;
+        let mut size = entries.iter().map(|(_, size, _)| size).sum::<u64>();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        for (path, entry_size, _) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            log::debug!("Evicting cache entry: {}", path.display());
+            std::fs::remove_file(path)?;
+            size -= entry_size;
+        }
+        Ok(())
+    }
+
+    /// Lists the path, size and last modification time of the cache entries. Files not named
+    /// like an entry are skipped.
+    fn entries(&self) -> std::io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() && entry.file_name().to_str().is_some_and(is_entry_name) {
+                entries.push((entry.path(), metadata.len(), metadata.modified()?));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+fn now() -> u64 {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+}
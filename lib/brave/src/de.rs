@@ -0,0 +1,36 @@
+//! Deserialization of API responses.
+//!
+//! The response models default every field that may be missing, and capture the fields they don't
+//! model in an `extra` map, so new or missing fields in a response don't fail the whole request.
+//! Strict mode keeps the same models, but reports the exact path of the value that failed to
+//! parse, which is what you want when checking the models against recorded responses.
+
+use serde::de::DeserializeOwned;
+
+use crate::{error::Error, ApiResult, Json};
+
+/// Deserializes an API response, reporting the path of the offending value if `strict` is set.
+///
+/// # Errors
+///
+/// Will return `Err` if the value doesn't match the shape of `T`.
+pub fn from_json<T: DeserializeOwned>(json: Json, strict: bool) -> ApiResult<T> {
+    if strict {
+        from_json_strict(json)
+    } else {
+        serde_json::from_value(json).map_err(Error::DeserializeError)
+    }
+}
+
+/// Deserializes an API response, reporting the path of the offending value on failure.
+///
+/// # Errors
+///
+/// Will return `Err` with `Error::DeserializePathError` if the value doesn't match the shape of
+/// `T`.
+pub fn from_json_strict<T: DeserializeOwned>(json: Json) -> ApiResult<T> {
+    serde_path_to_error::deserialize(json).map_err(|err| {
+        let path = err.path().to_string();
+        Error::DeserializePathError(path, err.into_inner())
+    })
+}
@@ -0,0 +1,244 @@
+//! Plain text documents extracted from search responses, ready to be used as RAG context.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DiscussionResult, GraphInfobox, LocationResult, NewsResult, VideoResult, WebSearchApiResponse,
+    QA,
+};
+
+/// The section of the search response a document was extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Web,
+    News,
+    Video,
+    Faq,
+    Discussion,
+    Infobox,
+    Location,
+}
+
+/// A normalized, plain text document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    /// The url of the page the document was extracted from, if any.
+    pub source_url: Option<String>,
+    /// The title of the document.
+    pub title: String,
+    /// The plain text content of the document, stripped of any decoration markers.
+    pub text: String,
+    /// The section of the search response the document was extracted from.
+    pub kind: DocumentKind,
+}
+
+/// Converts a search response, or part of it, into plain text documents.
+pub trait ToDocuments {
+    /// Returns a document for each populated result. Results without any text are skipped.
+    fn to_documents(&self) -> Vec<Document>;
+}
+
+impl ToDocuments for WebSearchApiResponse {
+    fn to_documents(&self) -> Vec<Document> {
+        let mut documents = Vec::new();
+
+        if let Some(ref infobox) = self.infobox {
+            documents.extend(infobox.to_documents());
+        }
+        documents.extend(self.faq_results().iter().flat_map(ToDocuments::to_documents));
+        documents.extend(self.web_results().iter().flat_map(ToDocuments::to_documents));
+        documents.extend(self.discussion_results().iter().flat_map(ToDocuments::to_documents));
+        documents.extend(self.news_results().iter().flat_map(ToDocuments::to_documents));
+        documents.extend(self.video_results().iter().flat_map(ToDocuments::to_documents));
+        documents.extend(self.location_results().iter().flat_map(ToDocuments::to_documents));
+
+        documents
+    }
+}
+
+impl ToDocuments for crate::Result {
+    fn to_documents(&self) -> Vec<Document> {
+        let paragraphs = self
+            .description
+            .iter()
+            .chain(self.extra_snippets.iter().flatten())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        document(DocumentKind::Web, self.url.as_deref(), self.title.as_deref(), &paragraphs)
+    }
+}
+
+impl ToDocuments for NewsResult {
+    fn to_documents(&self) -> Vec<Document> {
+        let paragraphs = self
+            .description
+            .iter()
+            .chain(self.extra_snippets.iter().flatten())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        document(DocumentKind::News, self.url.as_deref(), self.title.as_deref(), &paragraphs)
+    }
+}
+
+impl ToDocuments for VideoResult {
+    fn to_documents(&self) -> Vec<Document> {
+        document(
+            DocumentKind::Video,
+            self.url.as_deref(),
+            self.title.as_deref(),
+            &[self.description.as_deref().unwrap_or_default()],
+        )
+    }
+}
+
+impl ToDocuments for QA {
+    fn to_documents(&self) -> Vec<Document> {
+        let title = self.question.as_deref().or(self.title.as_deref());
+        document(
+            DocumentKind::Faq,
+            self.url.as_deref(),
+            title,
+            &[self.answer.as_deref().unwrap_or_default()],
+        )
+    }
+}
+
+impl ToDocuments for DiscussionResult {
+    fn to_documents(&self) -> Vec<Document> {
+        let data = self.data.as_ref();
+        let title = data.and_then(|d| d.title.as_deref()).or(self.title.as_deref());
+        let question = data.and_then(|d| d.question.as_deref());
+        let top_comment = data.and_then(|d| d.top_comment.as_deref());
+
+        let mut paragraphs = Vec::new();
+        if let Some(question) = question {
+            paragraphs.push(format!("Question: {question}"));
+        }
+        if let Some(top_comment) = top_comment {
+            paragraphs.push(format!("Top comment: {top_comment}"));
+        }
+        if paragraphs.is_empty() {
+            paragraphs.extend(self.description.clone());
+        }
+
+        let paragraphs = paragraphs.iter().map(String::as_str).collect::<Vec<_>>();
+        document(DocumentKind::Discussion, self.url.as_deref(), title, &paragraphs)
+    }
+}
+
+impl ToDocuments for GraphInfobox {
+    fn to_documents(&self) -> Vec<Document> {
+        let facts = self
+            .attributes
+            .iter()
+            .flatten()
+            .filter_map(|attribute| match attribute.as_slice() {
+                [key, value, ..] => Some(format!("{key}: {value}")),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut paragraphs = self.long_desc.iter().map(String::as_str).collect::<Vec<_>>();
+        let facts = facts.join("\n");
+        paragraphs.push(&facts);
+
+        document(
+            DocumentKind::Infobox,
+            self.website_url.as_deref(),
+            self.label.as_deref(),
+            &paragraphs,
+        )
+    }
+}
+
+impl ToDocuments for LocationResult {
+    fn to_documents(&self) -> Vec<Document> {
+        let address = self.postal_address.as_ref().and_then(|a| a.display_address.as_deref());
+        let telephone = self.contact.as_ref().and_then(|c| c.telephone.as_deref());
+
+        let mut paragraphs = self.description.iter().cloned().collect::<Vec<_>>();
+        if let Some(address) = address {
+            paragraphs.push(format!("Address: {address}"));
+        }
+        if let Some(telephone) = telephone {
+            paragraphs.push(format!("Telephone: {telephone}"));
+        }
+        if let Some(ref price_range) = self.price_range {
+            paragraphs.push(format!("Price range: {price_range}"));
+        }
+
+        let paragraphs = paragraphs.iter().map(String::as_str).collect::<Vec<_>>();
+        document(DocumentKind::Location, self.url.as_deref(), self.title.as_deref(), &paragraphs)
+    }
+}
+
+/// Builds a document out of its parts, returning nothing if there is no text to show.
+fn document(
+    kind: DocumentKind,
+    source_url: Option<&str>,
+    title: Option<&str>,
+    paragraphs: &[&str],
+) -> Vec<Document> {
+    let text = paragraphs
+        .iter()
+        .map(|p| plain_text(p))
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Document {
+        source_url: source_url.map(ToString::to_string),
+        title: plain_text(title.unwrap_or_default()),
+        text,
+        kind,
+    }]
+}
+
+/// Removes decoration markers and HTML tags from a display string, decodes the most common HTML
+/// entities, and collapses whitespace on each line.
+///
+/// Only a `<` followed by a letter, `/` or `!` starts a tag, so comparisons like `a < b` are kept.
+#[must_use]
+pub fn plain_text(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut in_tag = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' if !in_tag
+                && chars.peek().is_some_and(|&next| {
+                    next.is_ascii_alphabetic() || next == '/' || next == '!'
+                }) =>
+            {
+                in_tag = true;
+            }
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    let decoded = stripped
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+
+    decoded
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
@@ -1,35 +1,274 @@
+use crate::apis::params::{MAX_COUNT, MAX_OFFSET, MAX_QUERY_CHARS, MAX_QUERY_WORDS};
+use crate::goggles::{MAX_INSTRUCTIONS, MAX_STRENGTH, MAX_WILDCARDS};
+use crate::subscription::Feature;
+
 /// Error type returned from this library's functions
 #[derive(Debug)]
 pub enum Error {
     /// Encountered an unknown error.
     Unknown(Box<dyn std::error::Error + Send + Sync + 'static>),
-    /// An Error returned by the API
-    ApiError(String),
+    /// An Error returned by the API, with the HTTP status of the response when it failed.
+    ApiError(Option<u16>, String),
     /// An Error occurred when serializing an object.
     SerializeError(serde_json::error::Error),
     /// An Error occurred when deserializing an object.
     DeserializeError(serde_json::error::Error),
     /// An Error occurred when deserializing an object.
     DeserializeIoError(std::io::Error),
+    /// An Error occurred when deserializing the value at the given path of an object. Only
+    /// returned in strict mode.
+    DeserializePathError(String, serde_json::error::Error),
     /// An Error not related to the API
     RequestError(String),
+    /// A request parameter failed validation.
+    ValidationError(ValidationError),
+    /// The operation didn't complete in the allotted time.
+    TimedOut,
+    /// The stream of events ended in the middle of an event.
+    UnexpectedEof,
+    /// An Error occurred when recording or replaying a request.
+    CassetteError(cassette::Error),
+    /// An invalid client setting, like a malformed proxy URL or root certificate.
+    ConfigError(String),
+    /// None of the subscription tokens of the client covers the feature.
+    NoSubscription(Feature),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Error::{
-            ApiError, DeserializeError, DeserializeIoError, RequestError, SerializeError, Unknown,
+            ApiError, CassetteError, ConfigError, DeserializeError, DeserializeIoError,
+            DeserializePathError, NoSubscription, RequestError, SerializeError, TimedOut,
+            UnexpectedEof, Unknown, ValidationError,
         };
 
         match self {
             SerializeError(err) => write!(f, "serialize error: {err}"),
             DeserializeError(err) => write!(f, "deserialize error: {err}"),
-            ApiError(s) => write!(f, "API Error: {s}"),
+            ApiError(Some(status), s) => write!(f, "API Error ({status}): {s}"),
+            ApiError(None, s) => write!(f, "API Error: {s}"),
             Unknown(err) => write!(f, "sse stream error: {err}"),
             DeserializeIoError(err) => write!(f, "deserialize into error: {err}"),
+            DeserializePathError(path, err) => write!(f, "deserialize error at `{path}`: {err}"),
             RequestError(s) => write!(f, "Request Error: {s}"),
+            ValidationError(err) => write!(f, "validation error: {err}"),
+            TimedOut => write!(f, "timed out"),
+            UnexpectedEof => write!(f, "unexpected eof"),
+            CassetteError(err) => write!(f, "cassette error: {err}"),
+            ConfigError(s) => write!(f, "config error: {s}"),
+            NoSubscription(feature) => {
+                let plans = feature.subscriptions().iter().map(ToString::to_string);
+                write!(
+                    f,
+                    "no subscription grants access to the {feature}, it needs a token of: {}",
+                    plans.collect::<Vec<_>>().join(", ")
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+impl From<sse::Error> for Error {
+    fn from(error: sse::Error) -> Self {
+        match error {
+            sse::Error::Io(error) => Self::DeserializeIoError(error),
+            sse::Error::UnexpectedEof => Self::UnexpectedEof,
+        }
+    }
+}
+
+impl From<http_agent::Error> for Error {
+    fn from(error: http_agent::Error) -> Self {
+        Self::ConfigError(error.to_string())
+    }
+}
+
+impl From<cassette::Error> for Error {
+    fn from(error: cassette::Error) -> Self {
+        Self::CassetteError(error)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Self::ValidationError(error)
+    }
+}
+
+/// Error type returned when a request parameter holds an unsupported value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The query is empty.
+    EmptyQuery,
+    /// The query has more than 400 characters.
+    QueryTooLong(usize),
+    /// The query has more than 50 words.
+    TooManyWords(usize),
+    /// The `count` value is outside of the supported range.
+    InvalidCount(u16),
+    /// The `offset` value is outside of the supported range.
+    InvalidOffset(u16),
+    /// The `safesearch` value is not one of `off`, `moderate` or `strict`.
+    InvalidSafeSearch(String),
+    /// The `freshness` value is not a supported time delta or date range.
+    InvalidFreshness(String),
+    /// The `units` value is not one of `metric` or `imperial`.
+    InvalidUnits(String),
+    /// The `result_filter` value contains an unsupported result type.
+    InvalidResultFilter(String),
+    /// The country is not a 2 character country code.
+    InvalidCountry(String),
+    /// The language is not a valid language code.
+    InvalidLanguage(String),
+    /// The age is not a number followed by a unit, like `7d`.
+    InvalidAge(String),
+    /// The inline goggle doesn't parse.
+    InvalidGoggle(GoggleError),
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ValidationError::{
+            EmptyQuery, InvalidAge, InvalidCount, InvalidCountry, InvalidFreshness, InvalidGoggle,
+            InvalidLanguage, InvalidOffset, InvalidResultFilter, InvalidSafeSearch, InvalidUnits,
+            QueryTooLong, TooManyWords,
+        };
+
+        match self {
+            EmptyQuery => write!(f, "query term can't be empty"),
+            QueryTooLong(n) => write!(
+                f,
+                "query term is too long ({n} characters). Maximum {MAX_QUERY_CHARS} characters allowed"
+            ),
+            TooManyWords(n) => write!(
+                f,
+                "query term is too long ({n} words). Maximum {MAX_QUERY_WORDS} words allowed"
+            ),
+            InvalidCount(n) => {
+                write!(f, "invalid count value '{n}'. Must be between 1 and {MAX_COUNT}")
+            }
+            InvalidOffset(n) => write!(f, "invalid offset value '{n}'. Maximum is {MAX_OFFSET}"),
+            InvalidSafeSearch(s) => {
+                write!(f, "invalid safesearch value '{s}'. Must be 'off', 'moderate' or 'strict'")
+            }
+            InvalidFreshness(s) => write!(
+                f,
+                "invalid freshness value '{s}'. Must be 'pd', 'pw', 'pm', 'py' or 'YYYY-MM-DDtoYYYY-MM-DD'"
+            ),
+            InvalidUnits(s) => {
+                write!(f, "invalid units value '{s}'. Must be 'metric' or 'imperial'")
+            }
+            InvalidResultFilter(s) => write!(
+                f,
+                "invalid result filter value '{s}'. It should be a comma-separated list of these values: discussions, faq, infobox, news, query, summarizer, videos, web"
+            ),
+            InvalidCountry(s) => {
+                write!(f, "invalid country code '{s}'. Must be a 2 character country code or 'ALL'")
+            }
+            InvalidLanguage(s) => write!(
+                f,
+                "invalid language code '{s}'. Must be a 2 or more character language code (e.g. 'en' or 'pt-br')"
+            ),
+            InvalidAge(s) => write!(
+                f,
+                "invalid age '{s}'. Must be a number followed by 'h', 'd', 'w', 'm' or 'y' (e.g. '7d')"
+            ),
+            InvalidGoggle(err) => write!(f, "invalid goggle: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<GoggleError> for ValidationError {
+    fn from(error: GoggleError) -> Self {
+        Self::InvalidGoggle(error)
+    }
+}
+
+/// Error type returned when a line of a goggle isn't valid. Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoggleError {
+    /// The goggle has no instructions.
+    NoInstructions,
+    /// The goggle has more than 100,000 instructions.
+    TooManyInstructions(usize),
+    /// The instruction has neither a pattern nor a `site`.
+    EmptyPattern(usize),
+    /// The pattern has more than 2 `*` wildcards.
+    TooManyWildcards(usize, usize),
+    /// The option isn't one of the Goggles DSL.
+    UnknownOption(usize, String),
+    /// The option has an unsupported value, like a `boost` outside of 1 to 10.
+    InvalidValue(usize, String, String),
+    /// The `site` isn't a bare domain, like `example.com`.
+    InvalidSite(usize, String),
+    /// Both options can't be given to the same instruction, like `boost` and `discard`.
+    ConflictingOptions(usize, String, String),
+}
+
+impl GoggleError {
+    /// The line of the error, if it comes from a single line.
+    #[must_use]
+    pub const fn line(&self) -> Option<usize> {
+        match self {
+            Self::NoInstructions | Self::TooManyInstructions(_) => None,
+            Self::EmptyPattern(line)
+            | Self::TooManyWildcards(line, _)
+            | Self::UnknownOption(line, _)
+            | Self::InvalidValue(line, _, _)
+            | Self::InvalidSite(line, _)
+            | Self::ConflictingOptions(line, _, _) => Some(*line),
+        }
+    }
+}
+
+impl std::fmt::Display for GoggleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use GoggleError::{
+            ConflictingOptions, EmptyPattern, InvalidSite, InvalidValue, NoInstructions,
+            TooManyInstructions, TooManyWildcards, UnknownOption,
+        };
+
+        match self {
+            NoInstructions => write!(f, "the goggle has no instructions"),
+            TooManyInstructions(n) => write!(
+                f,
+                "the goggle has {n} instructions. Maximum {MAX_INSTRUCTIONS} instructions allowed"
+            ),
+            EmptyPattern(line) => {
+                write!(f, "line {line}: the instruction needs a pattern or a 'site' option")
+            }
+            TooManyWildcards(line, n) => write!(
+                f,
+                "line {line}: the pattern has {n} wildcards. Maximum {MAX_WILDCARDS} allowed"
+            ),
+            UnknownOption(line, option) => write!(
+                f,
+                "line {line}: unknown option '{option}'. Must be 'boost', 'downrank', 'discard', \
+                 'site', 'inurl', 'intitle', 'indescription' or 'incontent'"
+            ),
+            InvalidValue(line, option, value) => match option.as_str() {
+                "boost" | "downrank" => write!(
+                    f,
+                    "line {line}: invalid {option} value '{value}'. Must be between 1 and {MAX_STRENGTH}"
+                ),
+                _ => write!(f, "line {line}: the '{option}' option takes no value"),
+            },
+            InvalidSite(line, site) => write!(
+                f,
+                "line {line}: invalid site '{site}'. Must be a domain without a scheme or path (e.g. 'example.com')"
+            ),
+            ConflictingOptions(line, first, second) if first == second => {
+                write!(f, "line {line}: the '{first}' option is repeated")
+            }
+            ConflictingOptions(line, first, second) => {
+                write!(f, "line {line}: the '{first}' and '{second}' options can't be combined")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GoggleError {}
@@ -0,0 +1,347 @@
+//! Client-side filters of web search responses, for constraints the API can't express.
+//!
+//! A result passes a filter only if it has the filtered field: e.g. results without a `page_age`
+//! are dropped by `min_age` and `max_age`. Only web results have a language, a family friendly
+//! flag and a page age, so filtering on them drops the other sections. Infoboxes and locations
+//! are left untouched.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    error::ValidationError, DiscussionResult, MetaUrl, NewsResult, ResultReference, VideoResult,
+    WebSearchApiResponse, QA,
+};
+
+/// Which results of a response to keep.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Keeps the results of these domains, or their subdomains.
+    pub domains: Vec<String>,
+    /// Drops the results of these domains, or their subdomains.
+    pub exclude_domains: Vec<String>,
+    /// Keeps the results in this language, e.g. `en`, which also matches `en-gb`.
+    pub lang: Option<String>,
+    /// Keeps the family friendly results.
+    pub family_friendly: bool,
+    /// Keeps the results whose page is at least this old.
+    pub min_age: Option<Duration>,
+    /// Keeps the results whose page is at most this old.
+    pub max_age: Option<Duration>,
+}
+
+impl Filter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the results of the domain, or its subdomains. Can be called more than once.
+    #[must_use]
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domains.push(normalize_domain(domain));
+        self
+    }
+
+    /// Drops the results of the domain, or its subdomains. Can be called more than once.
+    #[must_use]
+    pub fn exclude_domain(mut self, domain: &str) -> Self {
+        self.exclude_domains.push(normalize_domain(domain));
+        self
+    }
+
+    #[must_use]
+    pub fn lang(mut self, lang: &str) -> Self {
+        self.lang = Some(lang.trim().to_lowercase());
+        self
+    }
+
+    #[must_use]
+    pub fn family_friendly(mut self, family_friendly: bool) -> Self {
+        self.family_friendly = family_friendly;
+        self
+    }
+
+    #[must_use]
+    pub fn min_age(mut self, age: Duration) -> Self {
+        self.min_age = Some(age);
+        self
+    }
+
+    #[must_use]
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Whether the filter keeps every result.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the filter keeps the web result.
+    #[must_use]
+    pub fn matches(&self, result: &crate::Result) -> bool {
+        self.keeps(result, now())
+    }
+
+    /// Drops the results the filter doesn't keep from every section of the response, and from
+    /// its `mixed` ranking.
+    pub fn apply(&self, response: &mut WebSearchApiResponse) {
+        if self.is_empty() {
+            return;
+        }
+
+        let now = now();
+        let web = self.retain(response.web.as_mut().and_then(|s| s.results.as_mut()), now);
+        let news = self.retain(response.news.as_mut().and_then(|s| s.results.as_mut()), now);
+        let videos = self.retain(response.videos.as_mut().and_then(|s| s.results.as_mut()), now);
+        let faq = self.retain(response.faq.as_mut().and_then(|s| s.results.as_mut()), now);
+        let discussions =
+            self.retain(response.discussions.as_mut().and_then(|s| s.results.as_mut()), now);
+
+        let Some(mixed) = response.mixed.as_mut() else {
+            return;
+        };
+        for references in [&mut mixed.top, &mut mixed.main, &mut mixed.side].into_iter().flatten() {
+            references.retain_mut(|reference| {
+                let kept = match reference.r#type.as_deref() {
+                    Some("web") => &web,
+                    Some("news") => &news,
+                    Some("videos") => &videos,
+                    Some("faq") => &faq,
+                    Some("discussions") => &discussions,
+                    _ => return true,
+                };
+                reindex(reference, kept)
+            });
+        }
+    }
+
+    /// Keeps the matching results, and returns which of them were kept.
+    fn retain<T: Filterable>(&self, results: Option<&mut Vec<T>>, now: u64) -> Vec<bool> {
+        let Some(results) = results else {
+            return Vec::new();
+        };
+
+        let kept = results.iter().map(|result| self.keeps(result, now)).collect::<Vec<_>>();
+        let mut flags = kept.iter();
+        results.retain(|_| flags.next().copied().unwrap_or(false));
+        kept
+    }
+
+    fn keeps(&self, result: &impl Filterable, now: u64) -> bool {
+        let host = result.host();
+        if !self.domains.is_empty() && !self.domains.iter().any(|d| is_subdomain(&host, d)) {
+            return false;
+        }
+        if self.exclude_domains.iter().any(|d| is_subdomain(&host, d)) {
+            return false;
+        }
+
+        if let Some(lang) = &self.lang {
+            let Some(language) = result.language().map(str::to_lowercase) else {
+                return false;
+            };
+            if language != *lang && !language.starts_with(&format!("{lang}-")) {
+                return false;
+            }
+        }
+
+        if self.family_friendly && result.family_friendly() != Some(true) {
+            return false;
+        }
+
+        if self.min_age.is_some() || self.max_age.is_some() {
+            let Some(published) = result.page_age().and_then(timestamp) else {
+                return false;
+            };
+            let age = Duration::from_secs(now.saturating_sub(published));
+            if self.min_age.is_some_and(|min| age < min)
+                || self.max_age.is_some_and(|max| age > max)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parses an age like `12h`, `7d`, `2w`, `6m` or `1y`. Months are 30 days, and years 365 days.
+///
+/// # Errors
+///
+/// Will return `Err` if the age isn't a number followed by one of those units.
+pub fn parse_age(s: &str) -> Result<Duration, ValidationError> {
+    let s = s.trim();
+    let invalid = || ValidationError::InvalidAge(s.to_string());
+
+    let unit = s.chars().last().ok_or_else(invalid)?;
+    let amount = s[..s.len() - unit.len_utf8()].parse::<u64>().map_err(|_| invalid())?;
+    let hours = match unit {
+        'h' => 1,
+        'd' => 24,
+        'w' => 7 * 24,
+        'm' => 30 * 24,
+        'y' => 365 * 24,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_secs(amount.saturating_mul(hours * 60 * 60)))
+}
+
+/// The lowercased host of a url, without its port or credentials.
+#[must_use]
+pub fn host(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    host.split(':').next().unwrap_or_default().to_lowercase()
+}
+
+/// The fields of a result the filters look at.
+trait Filterable {
+    fn url(&self) -> Option<&str>;
+
+    fn meta_url(&self) -> Option<&MetaUrl> {
+        None
+    }
+
+    fn language(&self) -> Option<&str> {
+        None
+    }
+
+    fn family_friendly(&self) -> Option<bool> {
+        None
+    }
+
+    fn page_age(&self) -> Option<&str> {
+        None
+    }
+
+    fn host(&self) -> String {
+        self.meta_url()
+            .and_then(|meta_url| meta_url.hostname.as_deref())
+            .map_or_else(|| host(self.url().unwrap_or_default()), str::to_lowercase)
+    }
+}
+
+impl Filterable for crate::Result {
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn meta_url(&self) -> Option<&MetaUrl> {
+        self.meta_url.as_ref()
+    }
+
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    fn family_friendly(&self) -> Option<bool> {
+        self.family_friendly
+    }
+
+    fn page_age(&self) -> Option<&str> {
+        self.page_age.as_deref()
+    }
+}
+
+impl Filterable for NewsResult {
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn meta_url(&self) -> Option<&MetaUrl> {
+        self.meta_url.as_ref()
+    }
+}
+
+impl Filterable for VideoResult {
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn meta_url(&self) -> Option<&MetaUrl> {
+        self.meta_url.as_ref()
+    }
+}
+
+impl Filterable for QA {
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn meta_url(&self) -> Option<&MetaUrl> {
+        self.meta_url.as_ref()
+    }
+}
+
+impl Filterable for DiscussionResult {
+    fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+/// Points the reference to the new index of its result, or returns `false` if the result was
+/// dropped.
+fn reindex(reference: &mut ResultReference, kept: &[bool]) -> bool {
+    if reference.all.unwrap_or(false) {
+        return kept.iter().any(|kept| *kept);
+    }
+
+    let Some(index) = reference.index.and_then(|i| usize::try_from(i).ok()) else {
+        return true;
+    };
+    if !kept.get(index).copied().unwrap_or(false) {
+        return false;
+    }
+
+    let new_index = kept[..index].iter().filter(|kept| **kept).count();
+    reference.index = i32::try_from(new_index).ok();
+    true
+}
+
+fn normalize_domain(domain: &str) -> String {
+    host(domain.trim()).trim_start_matches("www.").trim_end_matches('.').to_string()
+}
+
+fn is_subdomain(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// Parses a `page_age` like `2024-08-01T12:30:00` or `2024-08-01` into seconds since the unix
+/// epoch. Any timezone or fraction of a second is ignored.
+fn timestamp(page_age: &str) -> Option<u64> {
+    let number = |range: std::ops::Range<usize>| page_age.get(range)?.parse::<u64>().ok();
+
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if page_age.get(4..5) != Some("-") || page_age.get(7..8) != Some("-") {
+        return None;
+    }
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let seconds = match page_age.get(10..11) {
+        Some("T" | " ") => number(11..13)? * 3600 + number(14..16)? * 60 + number(17..19)?,
+        _ => 0,
+    };
+
+    Some(days_from_civil(year, month, day) * 24 * 60 * 60 + seconds)
+}
+
+/// Number of days between the unix epoch and a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
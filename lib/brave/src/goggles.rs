@@ -0,0 +1,288 @@
+//! Parser and validator of the Goggles DSL, to lint a goggle before searching with it.
+//!
+//! A goggle is a list of instructions, one per line, which re-rank the results of a search:
+//!
+//! ```text
+//! ! name: Rust
+//! ! description: Boosts the Rust docs, and drops the content farms.
+//! /docs/$boost=3,site=rust-lang.org
+//! $discard,site=w3schools.com
+//! tutorial$downrank=2,intitle
+//! ```
+//!
+//! Each instruction is a url pattern followed by `$` and its options: one action out of
+//! `boost`, `downrank` or `discard`, an optional `site=` and an optional target field. Lines
+//! starting with `!` are comments, and `! key: value` comments hold the metadata of the goggle.
+//! See the [Goggles repository](https://github.com/brave/goggles-quickstart) for the details.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GoggleError;
+
+/// Maximum strength of a `boost` or `downrank` action.
+pub const MAX_STRENGTH: u8 = 10;
+/// Maximum number of `*` wildcards in a pattern.
+pub const MAX_WILDCARDS: usize = 2;
+/// Maximum number of instructions in a goggle.
+pub const MAX_INSTRUCTIONS: usize = 100_000;
+
+/// The metadata keys of a goggle.
+const METADATA_KEYS: [&str; 9] = [
+    "name",
+    "description",
+    "public",
+    "author",
+    "avatar",
+    "homepage",
+    "issues",
+    "transferred_to",
+    "license",
+];
+
+/// A parsed goggle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goggle {
+    /// The `! key: value` metadata comments, in the order they appear.
+    pub metadata: Vec<(String, String)>,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Goggle {
+    /// Parses a goggle, stopping at the first invalid line.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an instruction is invalid, or the goggle has no instructions.
+    pub fn parse(source: &str) -> Result<Self, GoggleError> {
+        let mut goggle = Self::default();
+        for (i, line) in source.lines().enumerate() {
+            match parse_line(i + 1, line)? {
+                Line::Metadata(key, value) => goggle.metadata.push((key, value)),
+                Line::Instruction(instruction) => goggle.instructions.push(instruction),
+                Line::Blank => {}
+            }
+        }
+
+        if goggle.instructions.is_empty() {
+            return Err(GoggleError::NoInstructions);
+        }
+        if goggle.instructions.len() > MAX_INSTRUCTIONS {
+            return Err(GoggleError::TooManyInstructions(goggle.instructions.len()));
+        }
+        Ok(goggle)
+    }
+
+    /// The value of a metadata key, e.g. `name`.
+    #[must_use]
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+}
+
+impl FromStr for Goggle {
+    type Err = GoggleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Checks every line of a goggle, and returns all the errors found.
+#[must_use]
+pub fn lint(source: &str) -> Vec<GoggleError> {
+    let mut errors = Vec::new();
+    let mut instructions = 0;
+    for (i, line) in source.lines().enumerate() {
+        match parse_line(i + 1, line) {
+            Ok(Line::Instruction(_)) => instructions += 1,
+            Ok(_) => {}
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if instructions == 0 && errors.is_empty() {
+        errors.push(GoggleError::NoInstructions);
+    }
+    if instructions > MAX_INSTRUCTIONS {
+        errors.push(GoggleError::TooManyInstructions(instructions));
+    }
+    errors
+}
+
+/// A rule of a goggle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instruction {
+    /// The line of the instruction, from 1.
+    pub line: usize,
+    /// The url pattern, which can be empty when the instruction has a `site`.
+    pub pattern: String,
+    pub action: Action,
+    /// Restricts the instruction to the results of this domain, or its subdomains.
+    pub site: Option<String>,
+    /// Matches the pattern against this field of the results instead of their url.
+    pub target: Option<Target>,
+}
+
+/// What an instruction does to the results it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Moves the results up, with a strength from 1 to 10.
+    Boost(u8),
+    /// Moves the results down, with a strength from 1 to 10.
+    Downrank(u8),
+    /// Drops the results.
+    Discard,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boost(strength) => write!(f, "boost={strength}"),
+            Self::Downrank(strength) => write!(f, "downrank={strength}"),
+            Self::Discard => write!(f, "discard"),
+        }
+    }
+}
+
+/// The field of the results a pattern is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Target {
+    Url,
+    Title,
+    Description,
+    Content,
+}
+
+impl Target {
+    /// The option that selects the target.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Url => "inurl",
+            Self::Title => "intitle",
+            Self::Description => "indescription",
+            Self::Content => "incontent",
+        }
+    }
+}
+
+enum Line {
+    Blank,
+    Metadata(String, String),
+    Instruction(Instruction),
+}
+
+fn parse_line(line: usize, text: &str) -> Result<Line, GoggleError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(Line::Blank);
+    }
+    if let Some(comment) = text.strip_prefix('!') {
+        let metadata = comment.split_once(':').map(|(key, value)| (key.trim(), value.trim()));
+        return Ok(match metadata {
+            Some((key, value)) if METADATA_KEYS.contains(&key) => {
+                Line::Metadata(key.to_string(), value.to_string())
+            }
+            _ => Line::Blank,
+        });
+    }
+
+    // Options follow the last `$`, which url patterns don't usually contain.
+    let (pattern, options) = text.rsplit_once('$').unwrap_or((text, ""));
+    let mut instruction = Instruction {
+        line,
+        pattern: pattern.to_string(),
+        action: Action::Boost(1),
+        site: None,
+        target: None,
+    };
+    let mut action = None;
+
+    for option in options.split(',').map(str::trim).filter(|option| !option.is_empty()) {
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (option, None),
+        };
+        let invalid =
+            || GoggleError::InvalidValue(line, name.to_string(), value.unwrap_or("").to_string());
+
+        match name {
+            "boost" | "downrank" | "discard" => {
+                if let Some(previous) = action.replace(name) {
+                    return Err(GoggleError::ConflictingOptions(
+                        line,
+                        previous.to_string(),
+                        name.to_string(),
+                    ));
+                }
+                instruction.action = match (name, value) {
+                    ("discard", None) => Action::Discard,
+                    ("discard", Some(_)) => return Err(invalid()),
+                    (_, value) => {
+                        let strength = match value {
+                            Some(value) => value.parse().map_err(|_| invalid())?,
+                            None => 1,
+                        };
+                        if !(1..=MAX_STRENGTH).contains(&strength) {
+                            return Err(invalid());
+                        }
+                        if name == "boost" {
+                            Action::Boost(strength)
+                        } else {
+                            Action::Downrank(strength)
+                        }
+                    }
+                };
+            }
+            "site" => {
+                if instruction.site.is_some() {
+                    return Err(GoggleError::ConflictingOptions(
+                        line,
+                        name.to_string(),
+                        name.to_string(),
+                    ));
+                }
+                let site = value.unwrap_or("");
+                let valid = !site.is_empty()
+                    && !site.contains("://")
+                    && !site.contains(['/', '*', '^', '|'])
+                    && !site.chars().any(char::is_whitespace);
+                if !valid {
+                    return Err(GoggleError::InvalidSite(line, site.to_string()));
+                }
+                instruction.site = Some(site.to_lowercase());
+            }
+            "inurl" | "intitle" | "indescription" | "incontent" => {
+                if value.is_some() {
+                    return Err(invalid());
+                }
+                let target = [Target::Url, Target::Title, Target::Description, Target::Content]
+                    .into_iter()
+                    .find(|target| target.as_str() == name)
+                    .expect("every target has an option");
+                if let Some(previous) = instruction.target.replace(target) {
+                    return Err(GoggleError::ConflictingOptions(
+                        line,
+                        previous.as_str().to_string(),
+                        name.to_string(),
+                    ));
+                }
+            }
+            _ => return Err(GoggleError::UnknownOption(line, name.to_string())),
+        }
+    }
+
+    if instruction.pattern.is_empty() && instruction.site.is_none() {
+        return Err(GoggleError::EmptyPattern(line));
+    }
+    let wildcards = instruction.pattern.matches('*').count();
+    if wildcards > MAX_WILDCARDS {
+        return Err(GoggleError::TooManyWildcards(line, wildcards));
+    }
+
+    Ok(Line::Instruction(instruction))
+}
@@ -4,8 +4,16 @@ pub use apis::*;
 pub mod brave;
 pub use brave::*;
 
+pub mod cache;
+pub mod de;
+pub mod documents;
 pub mod error;
+pub mod filter;
+pub mod goggles;
+pub mod plan;
 pub mod query;
+pub mod ranking;
+pub mod subscription;
 
 use log as _;
 
@@ -0,0 +1,79 @@
+//! Finds out what the plan of a subscription token unlocks, with a cheap request to the API.
+//!
+//! Features the plan doesn't include are silently left out of the responses, e.g. the summary
+//! key or the extra snippets of the web results, so the probes ask for them and check which came
+//! back.
+
+use crate::{
+    query::Query as _, suggest::Api as _, web_search::Api as _, ApiResult, Brave,
+    SuggestSearchParams, WebSearchParams, SPELLCHECK,
+};
+
+/// Query sent by the probes.
+const PROBE_QUERY: &str = "brave search";
+
+/// The features of a Web Search plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebSearchPlan {
+    /// Summaries of the results, only in the Pro AI plan.
+    pub summarizer: bool,
+    /// Extra snippets of the web results.
+    pub extra_snippets: bool,
+}
+
+/// The features of a Suggest plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuggestPlan {
+    /// Suggestions enriched with entity titles, descriptions and images.
+    pub rich: bool,
+}
+
+/// Checks the token of the client with a single web search.
+///
+/// # Errors
+///
+/// Will return `Err` if the token is rejected, or the request fails for some other reason.
+pub fn web_search_plan(client: &Brave) -> ApiResult<WebSearchPlan> {
+    let params = WebSearchParams::builder(PROBE_QUERY)
+        .count(1)
+        .extra_snippets(true)
+        .summary(true)
+        .build()?;
+    let response = client.search(&params, None)?;
+
+    Ok(WebSearchPlan {
+        summarizer: response.summary_key().is_some(),
+        extra_snippets: response
+            .web_results()
+            .iter()
+            .any(|result| result.extra_snippets.as_ref().is_some_and(|s| !s.is_empty())),
+    })
+}
+
+/// Checks the token of the client with a rich suggest request, falling back to a plain one if
+/// the plan rejects it.
+///
+/// # Errors
+///
+/// Will return `Err` if the token is rejected, or the request fails for some other reason.
+pub fn suggest_plan(client: &Brave) -> ApiResult<SuggestPlan> {
+    let rich = SuggestSearchParams::builder(PROBE_QUERY).count(1).rich(true).build()?;
+    if let Ok(response) = client.suggest(&rich, None) {
+        let rich = response.results.iter().any(|result| result.is_entity.is_some());
+        return Ok(SuggestPlan { rich });
+    }
+
+    client.suggest(&SuggestSearchParams::new(PROBE_QUERY), None)?;
+    Ok(SuggestPlan { rich: false })
+}
+
+/// Checks the token of the client with a single spellcheck request. The Spellcheck plans only
+/// differ in their rate limits, so there's nothing else to find out.
+///
+/// # Errors
+///
+/// Will return `Err` if the token is rejected, or the request fails for some other reason.
+pub fn spellcheck_plan(client: &Brave) -> ApiResult<()> {
+    client.query(SPELLCHECK, Some(vec![("q", PROBE_QUERY)]), None)?;
+    Ok(())
+}
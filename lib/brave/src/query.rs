@@ -1,5 +1,11 @@
+use std::io::Read;
+
+use sse::Events;
+
 use crate::brave::Brave;
+use crate::cache::Key;
 use crate::error;
+use crate::subscription::Feature;
 use crate::{error::Error, ApiResult, Json};
 
 #[cfg(not(test))]
@@ -18,6 +24,18 @@ pub trait Query {
         query_pairs: Option<Vec<(&str, &str)>>,
         version: Option<&str>,
     ) -> ApiResult<Json>;
+
+    /// Sends a GET request that responds with a stream of server-sent events.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the GET request fails.
+    fn query_stream(
+        &self,
+        sub_url: &str,
+        query_pairs: Option<Vec<(&str, &str)>>,
+        version: Option<&str>,
+    ) -> ApiResult<Events<Box<dyn Read + Send + Sync + 'static>>>;
 }
 
 impl Query for Brave {
@@ -27,8 +45,53 @@ impl Query for Brave {
         query_pairs: Option<Vec<(&str, &str)>>,
         version: Option<&str>,
     ) -> ApiResult<Json> {
+        let Some(cache) = self.cache.as_ref().filter(|cache| cache.caches(sub_url)) else {
+            let response = self.request(sub_url, query_pairs, version, "application/json")?;
+            return deal_response(response, sub_url);
+        };
+
+        let key = Key::new(sub_url, query_pairs.as_deref().unwrap_or_default(), version);
+        if let Some(json) = cache.get(&key) {
+            debug!("<== ✔️\n\tCached api: {sub_url}, resp: {json}");
+            return Ok(json);
+        }
+
+        let response = self.request(sub_url, query_pairs, version, "application/json")?;
+        let json = deal_response(response, sub_url)?;
+        if let Err(err) = cache.put(&key, &json) {
+            error!("<== ❌\n\tFailed to cache api: {sub_url}, error: {err}");
+        }
+
+        Ok(json)
+    }
+
+    fn query_stream(
+        &self,
+        sub_url: &str,
+        query_pairs: Option<Vec<(&str, &str)>>,
+        version: Option<&str>,
+    ) -> ApiResult<Events<Box<dyn Read + Send + Sync + 'static>>> {
+        let response = self.request(sub_url, query_pairs, version, "text/event-stream")?;
+        debug!("<== ✔️\n\tStream open api: {sub_url}");
+
+        Ok(Events::new(response.into_reader()))
+    }
+}
+
+impl Brave {
+    fn request(
+        &self,
+        sub_url: &str,
+        query_pairs: Option<Vec<(&str, &str)>>,
+        version: Option<&str>,
+        accept: &str,
+    ) -> ApiResult<ureq::Response> {
         let path = &format!("{}/{}", self.api_url, sub_url);
         let query = query_pairs.unwrap_or_default();
+        let token = match Feature::of(sub_url, &query) {
+            Some(feature) => self.auth.token_for(feature)?,
+            None => self.auth.subscription_token.as_deref().unwrap_or_default(),
+        };
         let query_string =
             &query.iter().map(|q| format!("{}={}", q.0, q.1)).collect::<Vec<String>>().join("&");
         info!("GET {path}?{query_string}");
@@ -37,36 +100,78 @@ impl Query for Brave {
             .agent
             .get(path)
             .set("content-type", "application/json")
+            .set("accept", accept)
             .set("accept-encoding", "gzip")
-            .set("x-subscription-token", &self.auth.subscription_token);
+            .set("x-subscription-token", token);
 
         request =
             if let Some(version) = version { request.set("Api-Version", version) } else { request };
+        if self.cache.as_ref().is_some_and(|cache| cache.bypass) {
+            request = request.set("Cache-Control", "no-cache");
+        }
 
-        let response = request.query_pairs(query).call();
+        let Some(cassette) = &self.cassette else {
+            return request.query_pairs(query).call().map_err(|err| deal_error(err, sub_url));
+        };
 
-        deal_response(response, sub_url)
+        let recorded = cassette::Request::new(
+            "GET",
+            &if query_string.is_empty() { path.clone() } else { format!("{path}?{query_string}") },
+            None,
+        );
+        if cassette.is_replay() {
+            return replay(&cassette.load(&recorded)?, sub_url);
+        }
+
+        let response = match request.query_pairs(query).call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => return Err(deal_error(err, sub_url)),
+        };
+        let status = response.status();
+        let body = response.into_string().map_err(error::Error::DeserializeIoError)?;
+        let recording = cassette::Response::new(status, &body);
+        cassette.save(&recorded, &recording)?;
+
+        replay(&recording, sub_url)
+    }
+}
+
+/// Rebuilds the result of a request out of its recorded response.
+fn replay(recording: &cassette::Response, sub_url: &str) -> ApiResult<ureq::Response> {
+    let response = ureq::Response::new(recording.status, recording.status_text(), &recording.body)
+        .map_err(|err| deal_error(err, sub_url))?;
+
+    if recording.status >= 400 {
+        Err(deal_error(ureq::Error::Status(recording.status, response), sub_url))
+    } else {
+        Ok(response)
     }
 }
 
-fn deal_response(response: Result<ureq::Response, ureq::Error>, sub_url: &str) -> ApiResult<Json> {
-    match response {
-        Ok(resp) => {
-            let json = resp.into_json::<Json>().map_err(error::Error::DeserializeIoError)?;
-            debug!("<== ✔️\n\tDone api: {sub_url}, resp: {json}");
-            Ok(json)
+fn deal_response(response: ureq::Response, sub_url: &str) -> ApiResult<Json> {
+    let json = response.into_json::<Json>().map_err(error::Error::DeserializeIoError)?;
+    debug!("<== ✔️\n\tDone api: {sub_url}, resp: {json}");
+    Ok(json)
+}
+
+fn deal_error(err: ureq::Error, sub_url: &str) -> Error {
+    match err {
+        ureq::Error::Status(status, response) => {
+            let body = match response.into_string() {
+                Ok(body) => body,
+                Err(e) => return error::Error::DeserializeIoError(e),
+            };
+            // Proxies and rate limiters may answer with something other than JSON.
+            let error_msg = match serde_json::from_str::<Json>(&body) {
+                Ok(json) => json.to_string(),
+                Err(_) => body,
+            };
+            error!("<== ❌\n\tError api: {sub_url}, status: {status}, error: {error_msg}");
+            Error::ApiError(Some(status), error_msg)
+        }
+        ureq::Error::Transport(e) => {
+            error!("<== ❌\n\tError api: {sub_url}, error: {:?}", e.to_string());
+            Error::RequestError(e.to_string())
         }
-        Err(err) => match err {
-            ureq::Error::Status(status, response) => {
-                let error_msg =
-                    response.into_json::<Json>().map_err(error::Error::DeserializeIoError)?;
-                error!("<== ❌\n\tError api: {sub_url}, status: {status}, error: {error_msg}");
-                Err(Error::ApiError(format!("{error_msg}")))
-            }
-            ureq::Error::Transport(e) => {
-                error!("<== ❌\n\tError api: {sub_url}, error: {:?}", e.to_string());
-                Err(Error::RequestError(e.to_string()))
-            }
-        },
     }
 }
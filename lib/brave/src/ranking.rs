@@ -0,0 +1,120 @@
+//! Resolves the `mixed` ranking of a web search response into an ordered list of results.
+
+use serde::Serialize;
+
+use crate::{
+    DiscussionResult, GraphInfobox, LocationResult, NewsResult, ResultReference, VideoResult,
+    WebSearchApiResponse, QA,
+};
+
+/// A section of the search result page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Top,
+    Main,
+    Side,
+}
+
+/// A search result referenced by the `mixed` ranking of a web search response.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RankedItem<'a> {
+    Web(&'a crate::Result),
+    News(&'a NewsResult),
+    Video(&'a VideoResult),
+    Faq(&'a QA),
+    Discussion(&'a DiscussionResult),
+    Infobox(&'a GraphInfobox),
+    Location(&'a LocationResult),
+}
+
+/// A ranked result together with the section of the page where it should be shown.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Ranked<'a> {
+    pub section: Section,
+    #[serde(flatten)]
+    pub item: RankedItem<'a>,
+}
+
+impl WebSearchApiResponse {
+    /// Returns the results of the response following Brave's preferred ranking.
+    ///
+    /// The `top`, `main` and `side` sections of `mixed` are walked in that order. References to
+    /// sections missing from the response, or indices out of bounds, are skipped.
+    #[must_use]
+    pub fn ranked(&self) -> Vec<Ranked<'_>> {
+        let Some(ref mixed) = self.mixed else {
+            return Vec::new();
+        };
+
+        [(Section::Top, &mixed.top), (Section::Main, &mixed.main), (Section::Side, &mixed.side)]
+            .into_iter()
+            .flat_map(|(section, references)| {
+                references
+                    .iter()
+                    .flatten()
+                    .flat_map(|reference| self.resolve(reference))
+                    .map(move |item| Ranked { section, item })
+            })
+            .collect()
+    }
+
+    /// Returns the results of a single section of the page following Brave's preferred ranking.
+    #[must_use]
+    pub fn ranked_section(&self, section: Section) -> Vec<RankedItem<'_>> {
+        let references = self.mixed.as_ref().and_then(|mixed| match section {
+            Section::Top => mixed.top.as_ref(),
+            Section::Main => mixed.main.as_ref(),
+            Section::Side => mixed.side.as_ref(),
+        });
+
+        references.into_iter().flatten().flat_map(|reference| self.resolve(reference)).collect()
+    }
+
+    /// Resolves a single reference into the results it points to. A reference with `all` set
+    /// resolves to every result of its type.
+    fn resolve(&self, reference: &ResultReference) -> Vec<RankedItem<'_>> {
+        let all = reference.all.unwrap_or(false);
+        let index = reference.index.and_then(|i| usize::try_from(i).ok());
+
+        match reference.r#type.as_deref() {
+            Some("web") => pick(self.web.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                .map(RankedItem::Web)
+                .collect(),
+            Some("news") => pick(self.news.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                .map(RankedItem::News)
+                .collect(),
+            Some("videos") => {
+                pick(self.videos.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                    .map(RankedItem::Video)
+                    .collect()
+            }
+            Some("faq") => pick(self.faq.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                .map(RankedItem::Faq)
+                .collect(),
+            Some("discussions") => {
+                pick(self.discussions.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                    .map(RankedItem::Discussion)
+                    .collect()
+            }
+            Some("locations") => {
+                pick(self.locations.as_ref().and_then(|s| s.results.as_ref()), index, all)
+                    .map(RankedItem::Location)
+                    .collect()
+            }
+            Some("infobox") => self.infobox.iter().map(RankedItem::Infobox).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn pick<T>(results: Option<&Vec<T>>, index: Option<usize>, all: bool) -> impl Iterator<Item = &T> {
+    let results = results.map(Vec::as_slice).unwrap_or_default();
+    let selected = match index {
+        _ if all => results,
+        Some(i) => results.get(i..=i).unwrap_or_default(),
+        None => &[],
+    };
+    selected.iter()
+}
@@ -0,0 +1,131 @@
+//! The subscriptions of the Brave Search API, and the features each of them grants.
+//!
+//! Each plan comes with its own subscription token, so a client can hold the token of several of
+//! them, see `Auth::token`. Every request is then sent with the token of the first plan, in order
+//! of preference, that covers it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::apis::{SPELLCHECK, SUGGEST, SUMMARIZER, WEB_SEARCH};
+
+/// A subscription to the Brave Search API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subscription {
+    /// Data for Search: Free, Base or Pro.
+    DataForSearch,
+    /// Data for AI: Free AI, Base AI or Pro AI. Pro AI unlocks the summarizer.
+    DataForAi,
+    /// Suggest.
+    Suggest,
+    /// Spellcheck: Free Spellcheck or Spellcheck.
+    Spellcheck,
+    /// Autosuggest: Free Autosuggest or Autosuggest.
+    Autosuggest,
+}
+
+impl Subscription {
+    pub const ALL: [Self; 5] =
+        [Self::DataForSearch, Self::DataForAi, Self::Suggest, Self::Spellcheck, Self::Autosuggest];
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::DataForSearch => "data_for_search",
+            Self::DataForAi => "data_for_ai",
+            Self::Suggest => "suggest",
+            Self::Spellcheck => "spellcheck",
+            Self::Autosuggest => "autosuggest",
+        }
+    }
+
+    /// The environment variable read by `Auth::from_env` for the token of the subscription.
+    #[must_use]
+    pub const fn env(&self) -> &'static str {
+        match self {
+            Self::DataForSearch => "BRAVE_WEB_SEARCH_DATA_FOR_SEARCH_API_KEY",
+            Self::DataForAi => "BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY",
+            Self::Suggest => "BRAVE_SUGGEST_API_KEY",
+            Self::Spellcheck => "BRAVE_SPELLCHECK_API_KEY",
+            Self::Autosuggest => "BRAVE_AUTOSUGGEST_API_KEY",
+        }
+    }
+}
+
+impl std::fmt::Display for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Subscription {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|subscription| subscription.as_str() == s).ok_or_else(|| {
+            format!(
+                "invalid subscription '{s}'. Must be 'data_for_search', 'data_for_ai', 'suggest', \
+                 'spellcheck' or 'autosuggest'"
+            )
+        })
+    }
+}
+
+/// A feature of the API, covered by some of the subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// The web search endpoint.
+    WebSearch,
+    /// The summarizer endpoints, and web searches that ask for a summary key.
+    Summarizer,
+    /// The suggest endpoint.
+    Suggest,
+    /// The spellcheck endpoint.
+    Spellcheck,
+}
+
+impl Feature {
+    /// The feature used by a request to the given endpoint.
+    #[must_use]
+    pub fn of(sub_url: &str, query_pairs: &[(&str, &str)]) -> Option<Self> {
+        if sub_url == WEB_SEARCH {
+            let summary =
+                query_pairs.iter().any(|(k, v)| *k == "summary" && matches!(*v, "1" | "true"));
+            return Some(if summary { Self::Summarizer } else { Self::WebSearch });
+        }
+        if sub_url == SUGGEST {
+            return Some(Self::Suggest);
+        }
+        if sub_url == SPELLCHECK {
+            return Some(Self::Spellcheck);
+        }
+        // Every summarizer endpoint shares the prefix of `summarizer/search`.
+        if sub_url.starts_with(SUMMARIZER.trim_end_matches("search")) {
+            return Some(Self::Summarizer);
+        }
+
+        None
+    }
+
+    /// The subscriptions that cover the feature, in order of preference.
+    #[must_use]
+    pub const fn subscriptions(&self) -> &'static [Subscription] {
+        match self {
+            Self::WebSearch => &[Subscription::DataForSearch, Subscription::DataForAi],
+            Self::Summarizer => &[Subscription::DataForAi],
+            Self::Suggest => &[Subscription::Suggest, Subscription::Autosuggest],
+            Self::Spellcheck => &[Subscription::Spellcheck],
+        }
+    }
+}
+
+impl std::fmt::Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::WebSearch => "web search",
+            Self::Summarizer => "summarizer",
+            Self::Suggest => "suggest",
+            Self::Spellcheck => "spellcheck",
+        })
+    }
+}
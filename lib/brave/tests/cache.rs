@@ -0,0 +1,169 @@
+//! Checks that repeated requests to a local mock of the Brave Search API are served from the
+//! on-disk cache.
+
+mod common;
+
+use std::time::Duration;
+
+use brave::{
+    cache::{Cache, Key},
+    summarizer::Api as _,
+    web_search::Api as _,
+    WebSearchParams, WEB_SEARCH,
+};
+use common::{fixture, MockServer};
+
+#[test]
+fn repeated_searches_are_served_from_the_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let client = server.client().cache(Some(Cache::new(dir.path())));
+    let params = WebSearchParams::new("rust");
+
+    let first = client.search(&params, None).unwrap();
+    let second = client.search(&params, None).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(server.requests().len(), 1);
+    assert!(server.requests()[0].header("cache-control").is_none());
+}
+
+#[test]
+fn params_and_versions_are_cached_separately() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let client = server.client().cache(Some(Cache::new(dir.path())));
+
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+    client.search(&WebSearchParams::new("rust"), Some("2023-01-01")).unwrap();
+    client.search(&WebSearchParams::new("go"), None).unwrap();
+    client.search(&WebSearchParams::new("go"), None).unwrap();
+
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn keys_ignore_the_order_of_the_params() {
+    assert_eq!(
+        Key::new(WEB_SEARCH, &[("q", "rust"), ("count", "5")], None),
+        Key::new(WEB_SEARCH, &[("count", "5"), ("q", "rust ")], None)
+    );
+}
+
+#[test]
+fn expired_responses_are_fetched_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let cache = Cache::new(dir.path()).ttl(WEB_SEARCH, Duration::from_millis(50));
+    let client = server.client().cache(Some(cache));
+    let params = WebSearchParams::new("rust");
+
+    client.search(&params, None).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+    client.search(&params, None).unwrap();
+
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn bypass_asks_for_fresh_responses() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let params = WebSearchParams::new("rust");
+
+    let bypassing = server.client().cache(Some(Cache::new(dir.path()).bypass(true)));
+    bypassing.search(&params, None).unwrap();
+    bypassing.search(&params, None).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.header("cache-control") == Some("no-cache")));
+
+    // Fresh responses are still stored for the clients that don't bypass the cache.
+    server.client().cache(Some(Cache::new(dir.path()))).search(&params, None).unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn least_recently_used_responses_are_evicted() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let search = |client: &brave::Brave, query: &str| {
+        client.search(&WebSearchParams::new(query), None).unwrap();
+    };
+
+    let client = server.client().cache(Some(Cache::new(dir.path())));
+    search(&client, "rust");
+    let entry = std::fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap();
+    let size = entry.metadata().unwrap().len();
+
+    // Room for two responses.
+    let client = server.client().cache(Some(Cache::new(dir.path()).max_size(size * 5 / 2)));
+    search(&client, "go");
+    search(&client, "rust");
+    search(&client, "zig");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    assert_eq!(server.requests().len(), 3);
+
+    search(&client, "rust");
+    assert_eq!(server.requests().len(), 3);
+    search(&client, "go");
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[test]
+fn summaries_are_not_cached() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    server.mock("/summarizer/search", 200, &fixture("summarizer_pending.json")).mock(
+        "/summarizer/search",
+        200,
+        &fixture("summarizer_complete.json"),
+    );
+    let client = server.client().cache(Some(Cache::new(dir.path())));
+
+    let response = client.summarize(&WebSearchParams::new("pizza near me"), None).unwrap();
+
+    assert!(response.is_complete());
+    assert_eq!(server.requests_to("/summarizer/search").len(), 2);
+}
+
+#[test]
+fn other_files_of_the_directory_are_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let foreign = ["notes.json", "web-search.json", "web-search-0123456789ABCDEF.json", "web.txt"];
+    for name in foreign {
+        std::fs::write(dir.path().join(name), "x".repeat(1024 * 1024)).unwrap();
+    }
+
+    // Too small for any response, so each one is evicted as soon as it's stored.
+    let cache = Cache::new(dir.path()).max_size(1);
+    let client = server.client().cache(Some(cache.clone()));
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+    assert_eq!(server.requests().len(), 2);
+
+    server
+        .client()
+        .cache(Some(Cache::new(dir.path())))
+        .search(&WebSearchParams::new("go"), None)
+        .unwrap();
+    cache.clear().unwrap();
+
+    let mut names = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    let mut foreign = foreign.to_vec();
+    foreign.sort_unstable();
+    assert_eq!(names, foreign);
+}
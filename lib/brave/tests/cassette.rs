@@ -0,0 +1,89 @@
+//! Records interactions with a local mock of the Brave Search API, and replays them offline.
+
+mod common;
+
+use brave::{
+    error::Error,
+    summarizer::{Api as _, SummaryEvent},
+    web_search::Api as _,
+    WebSearchParams,
+};
+use cassette::{Cassette, Mode};
+use common::{fixture, MockServer};
+
+#[test]
+fn searches_are_replayed_without_the_network() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let client = server.client();
+    let params = WebSearchParams::new("rust");
+
+    let recorded = client
+        .clone()
+        .cassette(Some(Cassette::new(Mode::Record, dir.path())))
+        .search(&params, None)
+        .unwrap();
+    drop(server);
+
+    let replayed = client
+        .cassette(Some(Cassette::new(Mode::Replay, dir.path())))
+        .search(&params, None)
+        .unwrap();
+    assert_eq!(recorded, replayed);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn streams_are_replayed_without_the_network() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    server.mock_sse("/summarizer/search", &fixture("summarizer_stream.txt"));
+    let client = server.client();
+    let params = WebSearchParams::new("pizza near me");
+
+    let count = |client: brave::Brave| {
+        client
+            .summarize_stream(&params, None)
+            .unwrap()
+            .filter(|event| matches!(event, Ok(SummaryEvent::Message(_))))
+            .count()
+    };
+
+    let recorded = count(client.clone().cassette(Some(Cassette::new(Mode::Record, dir.path()))));
+    drop(server);
+
+    let replayed = count(client.cassette(Some(Cassette::new(Mode::Replay, dir.path()))));
+    assert_eq!(recorded, 5);
+    assert_eq!(recorded, replayed);
+}
+
+#[test]
+fn api_errors_are_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    server.mock("/web/search", 429, &fixture("error_rate_limited.json"));
+    let client = server.client();
+    let params = WebSearchParams::new("rust");
+
+    let recorder = client.clone().cassette(Some(Cassette::new(Mode::Record, dir.path())));
+    assert!(matches!(recorder.search(&params, None), Err(Error::ApiError(Some(429), _))));
+    drop(server);
+
+    let replayer = client.cassette(Some(Cassette::new(Mode::Replay, dir.path())));
+    let err = replayer.search(&params, None).unwrap_err();
+    assert!(matches!(err, Error::ApiError(Some(429), ref msg) if msg.contains("RATE_LIMITED")));
+}
+
+#[test]
+fn missing_recordings_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let server = MockServer::start();
+    let client = server.client().cassette(Some(Cassette::new(Mode::Replay, dir.path())));
+
+    let err = client.search(&WebSearchParams::new("rust"), None).unwrap_err();
+
+    assert!(matches!(err, Error::CassetteError(cassette::Error::NotRecorded(..))));
+    assert!(server.requests().is_empty());
+}
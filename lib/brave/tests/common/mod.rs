@@ -0,0 +1,116 @@
+//! Helpers shared by the integration tests: recorded fixtures, JSON comparison, and a local mock
+//! of the Brave Search API.
+
+#![allow(dead_code)]
+
+use std::ops::Deref;
+
+use brave::{Auth, Brave, Json};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub const TOKEN: &str = "test-subscription-token";
+
+/// Reads a recorded fixture from `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"))
+}
+
+/// Reads a recorded JSON fixture from `tests/fixtures`.
+pub fn fixture_json(name: &str) -> Json {
+    serde_json::from_str(&fixture(name)).unwrap_or_else(|err| panic!("invalid {name}: {err}"))
+}
+
+/// Parses a fixture in strict mode, and checks that serializing it back keeps every value of the
+/// recorded payload, and that parsing the serialized value gives back the same model.
+pub fn assert_round_trip<T>(name: &str) -> T
+where
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
+{
+    let json = fixture_json(name);
+    let parsed: T = brave::de::from_json_strict(json.clone())
+        .unwrap_or_else(|err| panic!("{name} doesn't match the model: {err}"));
+
+    let serialized = serde_json::to_value(&parsed).expect("models serialize to JSON");
+    if let Err(diff) = json_eq(&strip_nulls(json), &strip_nulls(serialized.clone()), "$") {
+        panic!("{name} doesn't survive a round trip: {diff}");
+    }
+
+    let reparsed: T = brave::de::from_json_strict(serialized).expect("serialized models parse");
+    assert_eq!(parsed, reparsed, "{name} parses differently after a round trip");
+
+    parsed
+}
+
+/// Removes `null` values from objects, since missing and `null` fields are equivalent.
+fn strip_nulls(json: Json) -> Json {
+    match json {
+        Json::Object(map) => Json::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        Json::Array(values) => Json::Array(values.into_iter().map(strip_nulls).collect()),
+        json => json,
+    }
+}
+
+/// Compares two JSON values, allowing for the precision lost by `f32` fields, and returns the path
+/// of the first difference.
+fn json_eq(expected: &Json, actual: &Json, path: &str) -> Result<(), String> {
+    match (expected, actual) {
+        (Json::Object(e), Json::Object(a)) => {
+            for (key, value) in e {
+                let path = format!("{path}.{key}");
+                let other = a.get(key).ok_or_else(|| format!("`{path}` is missing"))?;
+                json_eq(value, other, &path)?;
+            }
+            match a.keys().find(|key| !e.contains_key(*key)) {
+                Some(key) => Err(format!("`{path}.{key}` is unexpected")),
+                None => Ok(()),
+            }
+        }
+        (Json::Array(e), Json::Array(a)) if e.len() == a.len() => e
+            .iter()
+            .zip(a)
+            .enumerate()
+            .try_for_each(|(i, (e, a))| json_eq(e, a, &format!("{path}[{i}]"))),
+        (Json::Number(e), Json::Number(a)) => {
+            let (e, a) = (e.as_f64().unwrap_or_default(), a.as_f64().unwrap_or_default());
+            if (e - a).abs() <= e.abs().max(1.0) * 1e-6 {
+                Ok(())
+            } else {
+                Err(format!("`{path}` is {a}, expected {e}"))
+            }
+        }
+        (e, a) if e == a => Ok(()),
+        (e, a) => Err(format!("`{path}` is {a}, expected {e}")),
+    }
+}
+
+/// A local mock of the Brave Search API, see [`mock_server::MockServer`].
+pub struct MockServer(mock_server::MockServer);
+
+impl MockServer {
+    pub fn start() -> Self {
+        let server = mock_server::MockServer::start();
+        server.not_found(|path| {
+            format!(r#"{{"type": "ErrorResponse", "error": {{"detail": "no mock for {path}"}}}}"#)
+        });
+        Self(server)
+    }
+
+    /// A strict client pointing to the mock API.
+    pub fn client(&self) -> Brave {
+        Brave::new(Auth::new(TOKEN), &self.url()).strict(true)
+    }
+}
+
+impl Deref for MockServer {
+    type Target = mock_server::MockServer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
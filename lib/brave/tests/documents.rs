@@ -0,0 +1,161 @@
+//! Checks the plain text documents extracted from search responses.
+
+use brave::{
+    documents::{plain_text, Document, DocumentKind, ToDocuments},
+    WebSearchApiResponse,
+};
+use serde_json::json;
+
+fn documents(response: serde_json::Value) -> Vec<Document> {
+    serde_json::from_value::<WebSearchApiResponse>(response).unwrap().to_documents()
+}
+
+#[test]
+fn markers_and_tags_are_stripped() {
+    assert_eq!(plain_text("The <strong>Rust</strong> book"), "The Rust book");
+    assert_eq!(plain_text("<!-- hidden -->shown<br/>  text"), "shown text");
+    assert_eq!(plain_text("  spread \t out \n  lines  "), "spread out\nlines");
+}
+
+#[test]
+fn less_than_signs_are_kept() {
+    assert_eq!(plain_text("a < b and c"), "a < b and c");
+    assert_eq!(plain_text("1<2 and 3 <= 4"), "1<2 and 3 <= 4");
+    assert_eq!(plain_text("x <b>bold</b> < y"), "x bold < y");
+}
+
+#[test]
+fn entities_are_decoded() {
+    assert_eq!(
+        plain_text("&quot;it&#x27;s&quot; &amp; it&#39;s &lt;b&gt;&nbsp;ok"),
+        "\"it's\" & it's <b> ok"
+    );
+    assert_eq!(plain_text("&amp;lt;"), "&lt;");
+}
+
+#[test]
+fn web_results_join_the_extra_snippets() {
+    let documents = documents(json!({
+        "web": {
+            "type": "search",
+            "results": [
+                {
+                    "title": "<strong>Rust</strong>",
+                    "url": "https://www.rust-lang.org/",
+                    "description": "A language",
+                    "extra_snippets": ["Fast &amp; safe", "", "Productive"],
+                },
+                { "title": "Empty", "url": "https://example.com/" },
+            ],
+        },
+    }));
+
+    assert_eq!(
+        documents,
+        [Document {
+            source_url: Some("https://www.rust-lang.org/".to_string()),
+            title: "Rust".to_string(),
+            text: "A language\n\nFast & safe\n\nProductive".to_string(),
+            kind: DocumentKind::Web,
+        }]
+    );
+}
+
+#[test]
+fn faqs_use_the_question_as_title() {
+    let documents = documents(json!({
+        "faq": {
+            "type": "faq",
+            "results": [
+                {
+                    "question": "Is Rust fast?",
+                    "answer": "<strong>Yes</strong>.",
+                    "title": "Rust FAQ",
+                    "url": "https://example.com/faq",
+                },
+                { "title": "Unanswered", "url": "https://example.com/faq" },
+            ],
+        },
+    }));
+
+    assert_eq!(
+        documents,
+        [Document {
+            source_url: Some("https://example.com/faq".to_string()),
+            title: "Is Rust fast?".to_string(),
+            text: "Yes.".to_string(),
+            kind: DocumentKind::Faq,
+        }]
+    );
+}
+
+#[test]
+fn discussions_prefer_the_forum_data() {
+    let documents = documents(json!({
+        "discussions": {
+            "type": "search",
+            "results": [
+                {
+                    "type": "discussion",
+                    "title": "Page title",
+                    "url": "https://forum.example.com/1",
+                    "description": "Page description",
+                    "data": {
+                        "title": "Thread title",
+                        "question": "Why Rust?",
+                        "top_comment": "Because of the borrow checker.",
+                    },
+                },
+                {
+                    "type": "discussion",
+                    "title": "Bare thread",
+                    "url": "https://forum.example.com/2",
+                    "description": "Only a description",
+                },
+            ],
+        },
+    }));
+
+    assert_eq!(
+        documents,
+        [
+            Document {
+                source_url: Some("https://forum.example.com/1".to_string()),
+                title: "Thread title".to_string(),
+                text: "Question: Why Rust?\n\nTop comment: Because of the borrow checker."
+                    .to_string(),
+                kind: DocumentKind::Discussion,
+            },
+            Document {
+                source_url: Some("https://forum.example.com/2".to_string()),
+                title: "Bare thread".to_string(),
+                text: "Only a description".to_string(),
+                kind: DocumentKind::Discussion,
+            },
+        ]
+    );
+}
+
+#[test]
+fn infoboxes_list_their_attributes() {
+    let documents = documents(json!({
+        "infobox": {
+            "type": "graph",
+            "label": "Rust",
+            "long_desc": "A programming language.",
+            "website_url": "https://www.rust-lang.org/",
+            "attributes": [["Designed by", "Graydon Hoare"], ["First appeared", "2015"], ["Alone"]],
+        },
+    }));
+
+    assert_eq!(
+        documents,
+        [Document {
+            source_url: Some("https://www.rust-lang.org/".to_string()),
+            title: "Rust".to_string(),
+            text: "A programming language.\n\nDesigned by: Graydon Hoare\nFirst appeared: 2015"
+                .to_string(),
+            kind: DocumentKind::Infobox,
+        }]
+    );
+}
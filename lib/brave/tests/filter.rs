@@ -0,0 +1,110 @@
+//! Checks the client-side filters of web search responses.
+
+mod common;
+
+use std::time::Duration;
+
+use brave::{
+    error::ValidationError,
+    filter::{parse_age, Filter},
+    ranking::RankedItem,
+    WebSearchApiResponse,
+};
+use common::fixture_json;
+use serde_json::json;
+
+fn response() -> WebSearchApiResponse {
+    brave::de::from_json_strict(fixture_json("web_search_free.json")).unwrap()
+}
+
+fn ranked_urls(response: &WebSearchApiResponse) -> Vec<&str> {
+    response
+        .ranked()
+        .into_iter()
+        .map(|ranked| match ranked.item {
+            RankedItem::Web(result) => result.url.as_deref().unwrap_or_default(),
+            RankedItem::Video(result) => result.url.as_deref().unwrap_or_default(),
+            _ => panic!("unexpected result type"),
+        })
+        .collect()
+}
+
+#[test]
+fn domains_keep_their_subdomains_and_the_ranking() {
+    let mut response = response();
+    Filter::new().domain("rust-lang.org").apply(&mut response);
+
+    assert_eq!(response.urls(), ["https://www.rust-lang.org/", "https://doc.rust-lang.org/book/"]);
+    assert!(response.video_results().is_empty());
+    assert_eq!(ranked_urls(&response), response.urls());
+}
+
+#[test]
+fn excluded_domains_are_dropped() {
+    let mut response = response();
+    Filter::new().exclude_domain("https://en.wikipedia.org/").apply(&mut response);
+
+    assert_eq!(
+        ranked_urls(&response),
+        [
+            "https://www.rust-lang.org/",
+            "https://www.youtube.com/watch?v=rust101",
+            "https://doc.rust-lang.org/book/",
+        ]
+    );
+}
+
+#[test]
+fn web_only_filters_drop_the_other_sections() {
+    let mut response = response();
+    Filter::new().lang("EN").family_friendly(true).apply(&mut response);
+
+    assert_eq!(response.web_results().len(), 3);
+    assert!(response.video_results().is_empty());
+
+    let mut response = self::response();
+    Filter::new().lang("fr").apply(&mut response);
+    assert!(response.web_results().is_empty());
+    assert!(response.ranked().is_empty());
+}
+
+#[test]
+fn ages_are_based_on_the_page_age() {
+    let mut json = fixture_json("web_search_free.json");
+    json["web"]["results"][0]["page_age"] = json!("2999-01-01T00:00:00");
+    json["web"]["results"][1]["page_age"] = json!("2001-09-09");
+    let response: WebSearchApiResponse = brave::de::from_json_strict(json).unwrap();
+
+    let mut recent = response.clone();
+    Filter::new().max_age(parse_age("30d").unwrap()).apply(&mut recent);
+    assert_eq!(recent.urls(), ["https://www.rust-lang.org/"]);
+
+    let mut old = response.clone();
+    Filter::new().min_age(parse_age("1y").unwrap()).apply(&mut old);
+    assert_eq!(old.urls(), ["https://en.wikipedia.org/wiki/Rust_(programming_language)"]);
+    assert_eq!(ranked_urls(&old), old.urls());
+
+    let filter = Filter::new().min_age(Duration::ZERO);
+    assert!(!filter.matches(&response.web_results()[2]), "results without a page age are dropped");
+}
+
+#[test]
+fn empty_filters_keep_everything() {
+    let mut response = response();
+    let filter = Filter::new();
+    filter.apply(&mut response);
+
+    assert!(filter.is_empty());
+    assert_eq!(response, self::response());
+}
+
+#[test]
+fn ages_are_parsed() {
+    assert_eq!(parse_age("12h").unwrap(), Duration::from_secs(12 * 60 * 60));
+    assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 24 * 60 * 60));
+    assert_eq!(parse_age("1y").unwrap(), Duration::from_secs(365 * 24 * 60 * 60));
+
+    for age in ["", "d", "7", "7x", "-1d", "1.5d"] {
+        assert_eq!(parse_age(age), Err(ValidationError::InvalidAge(age.to_string())));
+    }
+}
@@ -0,0 +1,168 @@
+//! Checks the response models against recorded payloads of every endpoint and plan.
+
+mod common;
+
+use brave::{
+    error::Error, ranking::RankedItem, LocationResult, ProductReview, StringOrSummaryEntity,
+    SuggestSearchApiResponse, SummarizerSearchApiResponse, SummaryEnrichments,
+    WebSearchApiResponse,
+};
+use common::{assert_round_trip, fixture_json};
+use serde_json::json;
+
+#[test]
+fn web_search_free_plan() {
+    let response: WebSearchApiResponse = assert_round_trip("web_search_free.json");
+
+    assert_eq!(
+        response.urls(),
+        [
+            "https://www.rust-lang.org/",
+            "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+            "https://doc.rust-lang.org/book/",
+        ]
+    );
+    assert_eq!(response.video_results().len(), 1);
+    assert_eq!(response.query.as_ref().and_then(|q| q.more_results_available), Some(true));
+    assert!(response.summary_key().is_none());
+
+    let ranked = response.ranked();
+    assert_eq!(ranked.len(), 4);
+    assert!(matches!(ranked[2].item, RankedItem::Video(_)));
+}
+
+#[test]
+fn web_search_base_ai_plan() {
+    let response: WebSearchApiResponse = assert_round_trip("web_search_base_ai.json");
+
+    let snippets = response.web_results()[0].extra_snippets.as_ref().unwrap();
+    assert_eq!(snippets.len(), 3);
+    assert_eq!(response.news_results()[0].extra_snippets.as_ref().unwrap().len(), 2);
+
+    let article = response.web_results()[1].article.as_ref().unwrap();
+    assert_eq!(article.author.as_ref().unwrap()[0].name.as_deref(), Some("Ferris Crab"));
+}
+
+#[test]
+fn web_search_pro_ai_plan() {
+    let response: WebSearchApiResponse = assert_round_trip("web_search_pro_ai.json");
+
+    let infobox = response.infobox.as_ref().unwrap();
+    let movie = infobox.movie.as_ref().unwrap();
+    assert_eq!(movie.directors.as_ref().unwrap()[0].name.as_deref(), Some("Jane Doe"));
+    assert_eq!(movie.genre.as_deref(), Some(&["Comedy".to_string(), "Drama".to_string()][..]));
+
+    let location = &response.location_results()[0];
+    let opening_hours = location.opening_hours.as_ref().unwrap();
+    assert_eq!(opening_hours.days.as_ref().unwrap()[1].len(), 2);
+    let review = &location.reviews.as_ref().unwrap().results.as_ref().unwrap()[0];
+    assert_eq!(review.author.as_ref().unwrap().name.as_deref(), Some("PizzaFan42"));
+    assert_eq!(location.extra.get("id"), Some(&json!("1520066f3f39496780c5931d9f7b26a6")));
+
+    let [recipe_page, product_page, software_page] = response.web_results() else {
+        panic!("expected three web results");
+    };
+    let recipe = recipe_page.recipe.as_ref().unwrap();
+    assert_eq!(recipe.instructions.as_ref().unwrap().len(), 3);
+    assert_eq!(recipe.servings, Some(4));
+    assert_eq!(recipe_page.deep_results.as_ref().unwrap().images.as_ref().unwrap().len(), 1);
+
+    assert!(
+        matches!(product_page.product, Some(ProductReview::Product(ref p)) if p.offers.is_some())
+    );
+    assert!(matches!(product_page.product_cluster.as_deref(), Some([ProductReview::Review(_)])));
+    assert_eq!(product_page.cluster.as_ref().unwrap().len(), 1);
+
+    assert_eq!(software_page.software.as_ref().unwrap().stars, Some(1234));
+    assert_eq!(software_page.book.as_ref().unwrap().pages, Some(320));
+
+    assert_eq!(response.faq_results().len(), 1);
+    assert_eq!(response.discussion_results()[0].data.as_ref().unwrap().num_answers, Some(84));
+    assert!(response.summary_key().is_some_and(|key| key.contains("pizza near me")));
+    assert!(response.extra.contains_key("rich"));
+}
+
+#[test]
+fn suggest_free_plan() {
+    let response: SuggestSearchApiResponse = assert_round_trip("suggest_free.json");
+
+    assert_eq!(response.queries(), ["hello kitty", "hello fresh", "hello world"]);
+}
+
+#[test]
+fn suggest_rich_plan() {
+    let response: SuggestSearchApiResponse = assert_round_trip("suggest_rich.json");
+
+    assert_eq!(response.queries(), ["albert einstein", "albertsons"]);
+    assert_eq!(response.results[0].is_entity, Some(true));
+    assert_eq!(response.results[0].title.as_deref(), Some("Albert Einstein"));
+}
+
+#[test]
+fn summarizer_complete() {
+    let response: SummarizerSearchApiResponse = assert_round_trip("summarizer_complete.json");
+
+    assert!(response.is_complete());
+    let summary = response.summary.as_ref().unwrap();
+    assert!(matches!(summary[0].data, Some(StringOrSummaryEntity::String(_))));
+    assert!(
+        matches!(summary[2].data, Some(StringOrSummaryEntity::SummaryEntity(ref e)) if e.url.is_some())
+    );
+    assert!(summary[4].data.is_none());
+    assert_eq!(response.references().len(), 2);
+    assert_eq!(response.entities_info.as_ref().unwrap().len(), 1);
+    assert_eq!(response.followups.as_ref().unwrap().len(), 2);
+}
+
+#[test]
+fn summarizer_deprecated_version() {
+    let response: SummarizerSearchApiResponse = assert_round_trip("summarizer_deprecated.json");
+
+    let result = &response.results.as_ref().unwrap()[0];
+    assert_eq!(result.answer.as_ref().unwrap().location.as_ref().unwrap().end, Some(48));
+    assert_eq!(result.references.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn summarizer_pending() {
+    let response: SummarizerSearchApiResponse = assert_round_trip("summarizer_pending.json");
+
+    assert!(!response.is_complete());
+    assert!(response.summary.is_none());
+}
+
+#[test]
+fn missing_fields_are_tolerated() {
+    let location: LocationResult =
+        brave::de::from_json_strict(json!({ "title": "No hours" })).unwrap();
+    assert!(location.opening_hours.is_none());
+
+    let enrichments: SummaryEnrichments = brave::de::from_json_strict(json!({})).unwrap();
+    assert!(enrichments.raw.is_none());
+
+    let response: SummarizerSearchApiResponse = brave::de::from_json_strict(json!({})).unwrap();
+    assert!(!response.is_complete());
+}
+
+#[test]
+fn unknown_product_types_are_tolerated() {
+    let product: ProductReview =
+        brave::de::from_json_strict(json!({ "type": "Offer", "url": "https://example.com" }))
+            .unwrap();
+
+    assert_eq!(product, ProductReview::Unknown);
+}
+
+#[test]
+fn strict_mode_reports_the_failing_path() {
+    let mut json = fixture_json("web_search_free.json");
+    json["web"]["results"][1]["title"] = json!(42);
+
+    let err = brave::de::from_json_strict::<WebSearchApiResponse>(json.clone()).unwrap_err();
+    assert!(
+        matches!(err, Error::DeserializePathError(ref path, _) if path == "web.results[1].title")
+    );
+
+    let err = brave::de::from_json::<WebSearchApiResponse>(json, false).unwrap_err();
+    assert!(matches!(err, Error::DeserializeError(_)));
+}
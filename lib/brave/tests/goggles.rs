@@ -0,0 +1,112 @@
+//! Checks the parser of the Goggles DSL, and inline goggles in web searches.
+
+mod common;
+
+use brave::{
+    error::{GoggleError, ValidationError},
+    goggles::{self, Action, Goggle, Instruction, Target},
+    web_search::Api as _,
+    WebSearchParams,
+};
+use common::{fixture, MockServer};
+
+const GOGGLE: &str = "\
+! name: Rust
+! description: Boosts the Rust docs.
+! A comment: not metadata.
+
+/docs/$boost=3,site=rust-lang.org
+$discard,site=W3Schools.com
+tutorial$downrank,intitle
+/rust/*
+";
+
+#[test]
+fn goggles_parse_into_metadata_and_instructions() {
+    let goggle: Goggle = GOGGLE.parse().unwrap();
+
+    assert_eq!(goggle.metadata("name"), Some("Rust"));
+    assert_eq!(goggle.metadata("description"), Some("Boosts the Rust docs."));
+    assert_eq!(goggle.metadata("A comment"), None);
+    assert_eq!(
+        goggle.instructions,
+        [
+            Instruction {
+                line: 5,
+                pattern: "/docs/".into(),
+                action: Action::Boost(3),
+                site: Some("rust-lang.org".into()),
+                target: None,
+            },
+            Instruction {
+                line: 6,
+                pattern: String::new(),
+                action: Action::Discard,
+                site: Some("w3schools.com".into()),
+                target: None,
+            },
+            Instruction {
+                line: 7,
+                pattern: "tutorial".into(),
+                action: Action::Downrank(1),
+                site: None,
+                target: Some(Target::Title),
+            },
+            Instruction {
+                line: 8,
+                pattern: "/rust/*".into(),
+                action: Action::Boost(1),
+                site: None,
+                target: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn invalid_instructions_are_reported_with_their_line() {
+    let cases = [
+        ("a$boost=11", GoggleError::InvalidValue(1, "boost".into(), "11".into())),
+        ("a$downrank=x", GoggleError::InvalidValue(1, "downrank".into(), "x".into())),
+        ("a$discard=2", GoggleError::InvalidValue(1, "discard".into(), "2".into())),
+        ("a$boost,discard", GoggleError::ConflictingOptions(1, "boost".into(), "discard".into())),
+        ("a$inurl,intitle", GoggleError::ConflictingOptions(1, "inurl".into(), "intitle".into())),
+        ("a$boost,promote", GoggleError::UnknownOption(1, "promote".into())),
+        ("$site=https://example.com", GoggleError::InvalidSite(1, "https://example.com".into())),
+        ("$site=", GoggleError::InvalidSite(1, String::new())),
+        ("$boost=2", GoggleError::EmptyPattern(1)),
+        ("/a/*/b/*/c/*", GoggleError::TooManyWildcards(1, 3)),
+        ("! name: Empty", GoggleError::NoInstructions),
+    ];
+
+    for (goggle, expected) in cases {
+        assert_eq!(Goggle::parse(goggle), Err(expected.clone()), "{goggle}");
+        assert!(!expected.to_string().is_empty());
+    }
+}
+
+#[test]
+fn lint_reports_every_invalid_line() {
+    assert!(goggles::lint(GOGGLE).is_empty());
+
+    let errors = goggles::lint("a$boost=0\n/docs/$boost\nb$sites=example.com\n");
+    let lines = errors.iter().map(GoggleError::line).collect::<Vec<_>>();
+    assert_eq!(lines, [Some(1), Some(3)]);
+    assert_eq!(goggles::lint("\n! comment\n"), [GoggleError::NoInstructions]);
+}
+
+#[test]
+fn inline_goggles_are_validated_and_sent() {
+    let err = WebSearchParams::builder("rust").goggles("a$boost=20").build().unwrap_err();
+    assert!(matches!(err, ValidationError::InvalidGoggle(GoggleError::InvalidValue(1, _, _))));
+    assert!(err.to_string().contains("line 1"), "{err}");
+
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+    let params = WebSearchParams::builder("rust").goggles(GOGGLE).build().unwrap();
+    server.client().search(&params, None).unwrap();
+
+    let request = &server.requests_to("/web/search")[0];
+    assert_eq!(request.query("goggles"), Some(GOGGLE));
+    assert_eq!(request.query("goggles_id"), None);
+}
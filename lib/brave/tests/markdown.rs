@@ -0,0 +1,89 @@
+//! Checks the Markdown rendering of summarizer responses.
+
+mod common;
+
+use brave::{Json, SummarizerSearchApiResponse};
+use common::fixture_json;
+use serde_json::json;
+
+fn response(value: Json) -> SummarizerSearchApiResponse {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn lists_are_rendered_with_their_entities() {
+    let response = response(json!({
+        "summary": [
+            {"type": "token", "data": "Pick one:"},
+            {"type": "enum_start", "data": "ol"},
+            {"type": "enum_item", "data": {"name": "Tokio", "url": "https://tokio.rs/", "text": "Async\nruntime."}},
+            {"type": "enum_item", "data": {"name": "smol", "text": "Small runtime."}},
+            {"type": "enum_end"},
+            {"type": "enum_start", "data": "ul"},
+            {"type": "enum_item", "data": {"name": "[beta]", "url": "https://example.com/"}},
+            {"type": "enum_end"},
+            {"type": "token", "data": "Done."},
+        ],
+    }));
+
+    assert_eq!(
+        response.to_markdown(),
+        "Pick one:\n\n\
+         1. **[Tokio](https://tokio.rs/)**: Async runtime.\n\
+         2. **smol**: Small runtime.\n\
+         \n\
+         - **[\\[beta\\]](https://example.com/)**\n\
+         \n\
+         Done.\n"
+    );
+}
+
+#[test]
+fn entities_are_linked_once_without_matching_inside_links() {
+    let response = response(json!({
+        "summary": [
+            {"type": "token", "data": "Rust is made by rust-lang. "},
+            {"type": "token", "data": "Rust again, and the Rust Foundation."},
+        ],
+        "enrichments": {
+            "entities": [
+                {"name": "", "url": "https://empty.example/"},
+                {"name": "Rust", "url": "https://rust-lang.org/"},
+                {"name": "rust-lang", "url": "https://github.com/rust-lang"},
+                {"name": "Rust Foundation", "url": "https://foundation.rust-lang.org/"},
+            ],
+        },
+    }));
+
+    assert_eq!(
+        response.to_markdown(),
+        "[Rust](https://rust-lang.org/) is made by [rust-lang](https://github.com/rust-lang). \
+         Rust again, and the [Rust Foundation](https://foundation.rust-lang.org/).\n"
+    );
+}
+
+#[test]
+fn references_are_cited_as_footnotes() {
+    let response: SummarizerSearchApiResponse =
+        serde_json::from_value(fixture_json("summarizer_complete.json")).unwrap();
+
+    assert_eq!(
+        response.to_markdown(),
+        "# Best pizza in San Francisco\n\
+         \n\
+         San Francisco has a few pizzerias that locals keep recommending:\n\
+         \n\
+         1. **[Tony's Pizza Napoletana](https://tonyspizzanapoletana.com/)**[^2]: Award-winning Neapolitan pies in North Beach.\n\
+         2. **Golden Boy Pizza**: Focaccia-style square slices.\n\
+         \n\
+         Expect lines on weekends. [^1]\n\
+         \n\
+         [^1]: [Best pizza in SF? : r/AskSF](https://www.reddit.com/r/AskSF/comments/abc123/best_pizza_in_sf/)\n\
+         [^2]: [Tony's Pizza Napoletana](https://tonyspizzanapoletana.com/)\n\
+         \n\
+         ## Follow-ups\n\
+         \n\
+         - best pizza in north beach\n\
+         - tony's pizza napoletana reservations\n"
+    );
+}
@@ -0,0 +1,237 @@
+//! Exercises the `Brave` client end to end against a local mock of the Brave Search API.
+
+mod common;
+
+use std::time::Duration;
+
+use brave::{
+    error::{Error, ValidationError},
+    suggest::Api as _,
+    summarizer::{Api as _, SummaryEvent},
+    web_search::Api as _,
+    CountryCode, SafeSearch, SuggestSearchParams, WebSearchParams,
+};
+use common::{fixture, MockServer, TOKEN};
+
+#[test]
+fn search_sends_the_params_and_token() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+
+    let params = WebSearchParams::builder("rust programming language")
+        .count(5)
+        .safesearch(SafeSearch::Strict)
+        .country("US".parse::<CountryCode>().unwrap())
+        .build()
+        .unwrap();
+    let response = server.client().search(&params, Some("2023-01-01")).unwrap();
+
+    assert_eq!(response.web_results().len(), 3);
+
+    let [request] = &server.requests()[..] else {
+        panic!("expected a single request");
+    };
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/web/search");
+    assert_eq!(request.query("q"), Some("rust programming language"));
+    assert_eq!(request.query("count"), Some("5"));
+    assert_eq!(request.query("safesearch"), Some("strict"));
+    assert_eq!(request.query("country"), Some("US"));
+    assert_eq!(request.header("x-subscription-token"), Some(TOKEN));
+    assert_eq!(request.header("accept"), Some("application/json"));
+    assert_eq!(request.header("api-version"), Some("2023-01-01"));
+}
+
+#[test]
+fn search_all_walks_every_page() {
+    let server = MockServer::start();
+    server
+        .mock("/web/search", 200, &fixture("web_search_free.json"))
+        .mock("/web/search", 200, &fixture("web_search_base_ai.json"))
+        .mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+
+    let params = WebSearchParams::new("rust");
+    let results =
+        server.client().search_all(&params, None).collect::<brave::ApiResult<Vec<_>>>().unwrap();
+
+    assert_eq!(results.len(), 8);
+    let offsets = server
+        .requests_to("/web/search")
+        .iter()
+        .map(|r| r.query("offset").unwrap_or("0").to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, ["0", "1", "2"]);
+}
+
+#[test]
+fn suggest_returns_the_suggested_queries() {
+    let server = MockServer::start();
+    server.mock("/suggest/search", 200, &fixture("suggest_rich.json"));
+
+    let params = SuggestSearchParams::builder("albert").rich(true).build().unwrap();
+    let response = server.client().suggest(&params, None).unwrap();
+
+    assert_eq!(response.queries(), ["albert einstein", "albertsons"]);
+    let request = &server.requests_to("/suggest/search")[0];
+    assert_eq!(request.query("q"), Some("albert"));
+    assert_eq!(request.query("rich"), Some("1"));
+}
+
+#[test]
+fn summarize_polls_until_complete() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    server.mock("/summarizer/search", 200, &fixture("summarizer_pending.json")).mock(
+        "/summarizer/search",
+        200,
+        &fixture("summarizer_complete.json"),
+    );
+
+    let params = WebSearchParams::new("pizza near me");
+    let response = server.client().summarize(&params, None).unwrap();
+
+    assert!(response.is_complete());
+    assert_eq!(server.requests_to("/web/search")[0].query("summary"), Some("1"));
+
+    let polls = server.requests_to("/summarizer/search");
+    assert_eq!(polls.len(), 2);
+    assert!(polls.iter().all(|r| r.query("key").is_some_and(|key| key.contains("pizza near me"))));
+    assert!(polls.iter().all(|r| r.query("entity_info") == Some("1")));
+}
+
+#[test]
+fn summarize_times_out() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    server.mock("/summarizer/search", 200, &fixture("summarizer_pending.json"));
+
+    let params = WebSearchParams::new("pizza near me");
+    let err = server
+        .client()
+        .summarize_with_timeout(&params, None, Duration::from_millis(100))
+        .unwrap_err();
+
+    assert!(matches!(err, Error::TimedOut));
+}
+
+#[test]
+fn summarize_fails_without_a_summary_key() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json"));
+
+    let params = WebSearchParams::new("rust");
+    let err = server.client().summarize(&params, None).unwrap_err();
+
+    assert!(matches!(err, Error::ApiError(None, ref msg) if msg == "No summarizer found"));
+    assert!(server.requests_to("/summarizer/search").is_empty());
+}
+
+#[test]
+fn summarize_stream_yields_messages_as_they_arrive() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    server.mock_sse("/summarizer/search", &fixture("summarizer_stream.txt"));
+
+    let params = WebSearchParams::new("pizza near me");
+    let events = server
+        .client()
+        .summarize_stream(&params, None)
+        .unwrap()
+        .collect::<brave::ApiResult<Vec<_>>>()
+        .unwrap();
+
+    let types = events
+        .iter()
+        .map(|event| match event {
+            SummaryEvent::Message(message) => message.r#type.as_str(),
+            SummaryEvent::Other(json) => json["type"].as_str().unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(types, ["token", "token", "enum_start", "enum_item", "enum_end", "followups"]);
+
+    let request = &server.requests_to("/summarizer/search")[0];
+    assert_eq!(request.query("streaming"), Some("1"));
+    assert_eq!(request.header("accept"), Some("text/event-stream"));
+}
+
+#[test]
+fn summarize_stream_fails_when_the_body_is_cut() {
+    let params = WebSearchParams::new("pizza near me");
+    let first = "data: {\"type\": \"token\", \"data\": \"San Francisco\"}\n\n";
+
+    // In the middle of an event, and in the middle of a line.
+    for cut in ["event: message\ndata: {\"type\": \"token\"}\n", "data: {\"type\": \"tok"] {
+        let server = MockServer::start();
+        server.mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+        server.mock_sse("/summarizer/search", &format!("{first}{cut}"));
+
+        let events = server.client().summarize_stream(&params, None).unwrap().collect::<Vec<_>>();
+
+        assert!(
+            matches!(events[..], [Ok(SummaryEvent::Message(_)), Err(Error::UnexpectedEof)]),
+            "{cut:?} gave {events:?}"
+        );
+    }
+}
+
+#[test]
+fn summary_sub_endpoints_use_the_key() {
+    let server = MockServer::start();
+    let complete = fixture("summarizer_complete.json");
+    for path in ["/summarizer/title", "/summarizer/followups", "/summarizer/entity_info"] {
+        server.mock(path, 200, &complete);
+    }
+    let client = server.client();
+
+    assert_eq!(
+        client.summary_title("some-key", None).unwrap().as_deref(),
+        Some("Best pizza in San Francisco")
+    );
+    assert_eq!(client.summary_followups("some-key", None).unwrap().len(), 2);
+    assert_eq!(client.summary_entity_info("some-key", None).unwrap().len(), 1);
+    assert!(server.requests().iter().all(|r| r.query("key") == Some("some-key")));
+}
+
+#[test]
+fn api_errors_are_reported() {
+    let server = MockServer::start();
+    server.mock("/web/search", 429, &fixture("error_rate_limited.json"));
+
+    let params = WebSearchParams::new("rust");
+    let err = server.client().search(&params, None).unwrap_err();
+
+    assert!(matches!(err, Error::ApiError(Some(429), ref msg) if msg.contains("RATE_LIMITED")));
+}
+
+#[test]
+fn api_errors_keep_bodies_that_arent_json() {
+    let server = MockServer::start();
+    server.mock("/web/search", 429, "Too Many Requests");
+
+    let params = WebSearchParams::new("rust");
+    let err = server.client().search(&params, None).unwrap_err();
+
+    assert!(matches!(err, Error::ApiError(Some(429), ref msg) if msg == "Too Many Requests"));
+}
+
+#[test]
+fn invalid_params_are_rejected_before_sending_a_request() {
+    let server = MockServer::start();
+
+    let params = WebSearchParams::new("");
+    let err = server.client().search(&params, None).unwrap_err();
+
+    assert!(matches!(err, Error::ValidationError(ValidationError::EmptyQuery)));
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn strict_clients_report_the_failing_path() {
+    let server = MockServer::start();
+    server.mock("/suggest/search", 200, r#"{"type": "suggest", "results": [{"query": 1}]}"#);
+
+    let params = SuggestSearchParams::new("hello");
+    let err = server.client().suggest(&params, None).unwrap_err();
+
+    assert!(matches!(err, Error::DeserializePathError(ref path, _) if path == "results[0].query"));
+}
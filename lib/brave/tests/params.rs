@@ -0,0 +1,38 @@
+//! Checks the parsing of the typed search parameters.
+
+use brave::{error::ValidationError, DateRange, Freshness};
+
+#[test]
+fn freshness_ranges_must_be_calendar_days() {
+    let range = "2024-02-29to2024-12-31".parse::<Freshness>().unwrap();
+    assert_eq!(range.to_string(), "2024-02-29to2024-12-31");
+    assert_eq!("pw".parse::<Freshness>(), Ok(Freshness::PastWeek));
+
+    for invalid in [
+        "2024-13-45to2024-99-99",
+        "2024-00-10to2024-01-10",
+        "2024-04-31to2024-05-01",
+        "2023-02-29to2023-03-01",
+        "1900-02-29to1900-03-01",
+        "2024-1-01to2024-02-01",
+    ] {
+        assert_eq!(
+            invalid.parse::<Freshness>(),
+            Err(ValidationError::InvalidFreshness(invalid.to_string())),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn freshness_ranges_must_be_in_order() {
+    assert!(DateRange::new("2024-03-01", "2024-03-01").is_ok());
+    assert_eq!(
+        DateRange::new("2024-03-02", "2024-03-01"),
+        Err(ValidationError::InvalidFreshness("2024-03-02to2024-03-01".to_string()))
+    );
+    assert_eq!(
+        "2025-01-01to2024-12-31".parse::<Freshness>(),
+        Err(ValidationError::InvalidFreshness("2025-01-01to2024-12-31".to_string()))
+    );
+}
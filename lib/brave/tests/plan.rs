@@ -0,0 +1,76 @@
+//! Checks that the plan probes report the features of each subscription.
+
+mod common;
+
+use brave::plan::{suggest_plan, web_search_plan, SuggestPlan, WebSearchPlan};
+use common::{fixture, MockServer};
+
+#[test]
+fn web_search_features_come_from_the_response() {
+    let server = MockServer::start();
+    server
+        .mock("/web/search", 200, &fixture("web_search_free.json"))
+        .mock("/web/search", 200, &fixture("web_search_base_ai.json"))
+        .mock("/web/search", 200, &fixture("web_search_pro_ai.json"));
+    let client = server.client();
+
+    let free = web_search_plan(&client).unwrap();
+    assert_eq!(free, WebSearchPlan { summarizer: false, extra_snippets: false });
+    let base_ai = web_search_plan(&client).unwrap();
+    assert_eq!(base_ai, WebSearchPlan { summarizer: false, extra_snippets: true });
+    let pro_ai = web_search_plan(&client).unwrap();
+    assert_eq!(pro_ai, WebSearchPlan { summarizer: true, extra_snippets: true });
+
+    let request = &server.requests_to("/web/search")[0];
+    assert_eq!(request.query("count"), Some("1"));
+    assert_eq!(request.query("extra_snippets"), Some("1"));
+    assert_eq!(request.query("summary"), Some("1"));
+}
+
+#[test]
+fn summarizers_without_a_key_are_not_reported() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, r#"{"type": "search", "summarizer": {"type": "summarizer"}}"#);
+
+    let plan = web_search_plan(&server.client()).unwrap();
+    assert_eq!(plan, WebSearchPlan { summarizer: false, extra_snippets: false });
+}
+
+#[test]
+fn rich_suggestions_are_detected() {
+    let server = MockServer::start();
+    server.mock("/suggest/search", 200, &fixture("suggest_rich.json")).mock(
+        "/suggest/search",
+        200,
+        &fixture("suggest_free.json"),
+    );
+    let client = server.client();
+
+    assert_eq!(suggest_plan(&client).unwrap(), SuggestPlan { rich: true });
+    assert_eq!(suggest_plan(&client).unwrap(), SuggestPlan { rich: false });
+}
+
+#[test]
+fn plans_without_rich_suggestions_fall_back_to_plain_ones() {
+    let server = MockServer::start();
+    server.mock("/suggest/search", 403, r#"{"error": {"code": "OPTION_NOT_IN_PLAN"}}"#).mock(
+        "/suggest/search",
+        200,
+        &fixture("suggest_free.json"),
+    );
+
+    assert_eq!(suggest_plan(&server.client()).unwrap(), SuggestPlan { rich: false });
+
+    let requests = server.requests_to("/suggest/search");
+    assert_eq!(requests[0].query("rich"), Some("1"));
+    assert_eq!(requests[1].query("rich"), None);
+}
+
+#[test]
+fn rejected_tokens_are_errors() {
+    let server = MockServer::start();
+    server.mock("/web/search", 401, r#"{"error": {"code": "SUBSCRIPTION_TOKEN_INVALID"}}"#);
+
+    let err = web_search_plan(&server.client()).unwrap_err();
+    assert!(err.to_string().contains("SUBSCRIPTION_TOKEN_INVALID"), "{err}");
+}
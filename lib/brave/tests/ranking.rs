@@ -0,0 +1,148 @@
+//! Checks how the `mixed` ranking of a web search response is resolved into results.
+
+use brave::{
+    ranking::{RankedItem, Section},
+    WebSearchApiResponse,
+};
+use serde_json::json;
+
+fn response(mixed: serde_json::Value) -> WebSearchApiResponse {
+    serde_json::from_value(json!({
+        "type": "search",
+        "web": {
+            "type": "search",
+            "results": [
+                { "title": "Web 0", "url": "https://example.com/0" },
+                { "title": "Web 1", "url": "https://example.com/1" },
+                { "title": "Web 2", "url": "https://example.com/2" },
+            ],
+        },
+        "news": {
+            "type": "news",
+            "results": [
+                { "title": "News 0", "url": "https://news.example.com/0" },
+                { "title": "News 1", "url": "https://news.example.com/1" },
+            ],
+        },
+        "faq": {
+            "type": "faq",
+            "results": [{ "question": "Why?", "answer": "Because." }],
+        },
+        "infobox": { "type": "graph", "label": "Example" },
+        "mixed": mixed,
+    }))
+    .unwrap()
+}
+
+/// Describes a ranked result by its type and title.
+fn describe(item: &RankedItem<'_>) -> String {
+    match item {
+        RankedItem::Web(result) => format!("web {}", result.title.as_deref().unwrap_or_default()),
+        RankedItem::News(result) => {
+            format!("news {}", result.title.as_deref().unwrap_or_default())
+        }
+        RankedItem::Faq(qa) => format!("faq {}", qa.question.as_deref().unwrap_or_default()),
+        RankedItem::Infobox(infobox) => {
+            format!("infobox {}", infobox.label.as_deref().unwrap_or_default())
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+}
+
+fn ranked(response: &WebSearchApiResponse) -> Vec<(Section, String)> {
+    response.ranked().iter().map(|ranked| (ranked.section, describe(&ranked.item))).collect()
+}
+
+#[test]
+fn indices_resolve_to_their_result_in_order() {
+    let response = response(json!({
+        "type": "mixed",
+        "main": [
+            { "type": "web", "index": 2 },
+            { "type": "news", "index": 0 },
+            { "type": "web", "index": 0 },
+        ],
+    }));
+
+    assert_eq!(
+        ranked(&response),
+        [
+            (Section::Main, "web Web 2".to_string()),
+            (Section::Main, "news News 0".to_string()),
+            (Section::Main, "web Web 0".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn all_expands_to_every_result_of_the_type() {
+    let response = response(json!({
+        "type": "mixed",
+        "main": [{ "type": "news", "all": true }, { "type": "web", "index": 1, "all": true }],
+    }));
+
+    assert_eq!(
+        ranked(&response),
+        [
+            (Section::Main, "news News 0".to_string()),
+            (Section::Main, "news News 1".to_string()),
+            (Section::Main, "web Web 0".to_string()),
+            (Section::Main, "web Web 1".to_string()),
+            (Section::Main, "web Web 2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn dangling_references_are_skipped() {
+    let response = response(json!({
+        "type": "mixed",
+        "main": [
+            { "type": "web", "index": 3 },
+            { "type": "web", "index": -1 },
+            { "type": "web" },
+            { "type": "videos", "index": 0 },
+            { "type": "unknown", "index": 0 },
+            { "index": 0 },
+            { "type": "faq", "index": 0 },
+        ],
+    }));
+
+    assert_eq!(ranked(&response), [(Section::Main, "faq Why?".to_string())]);
+}
+
+#[test]
+fn sections_are_walked_top_main_side() {
+    let response = response(json!({
+        "type": "mixed",
+        "side": [{ "type": "infobox", "all": false }],
+        "main": [{ "type": "web", "index": 0 }],
+        "top": [{ "type": "news", "index": 1 }],
+    }));
+
+    assert_eq!(
+        ranked(&response),
+        [
+            (Section::Top, "news News 1".to_string()),
+            (Section::Main, "web Web 0".to_string()),
+            (Section::Side, "infobox Example".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn a_single_section_can_be_resolved() {
+    let response = response(json!({
+        "type": "mixed",
+        "top": [{ "type": "news", "index": 1 }],
+        "main": [{ "type": "web", "index": 1 }, { "type": "faq", "all": true }],
+    }));
+
+    let main: Vec<_> = response.ranked_section(Section::Main).iter().map(describe).collect();
+    assert_eq!(main, ["web Web 1", "faq Why?"]);
+    assert!(response.ranked_section(Section::Side).is_empty());
+
+    let unranked = WebSearchApiResponse { mixed: None, ..response };
+    assert!(unranked.ranked().is_empty());
+    assert!(unranked.ranked_section(Section::Main).is_empty());
+}
@@ -0,0 +1,120 @@
+//! Checks that every request is sent with the token of a subscription that covers it.
+
+mod common;
+
+use brave::{
+    error::Error,
+    subscription::{Feature, Subscription},
+    suggest::Api as _,
+    summarizer::Api as _,
+    web_search::Api as _,
+    Auth, Brave, SuggestSearchParams, WebSearchParams, SPELLCHECK, SUGGEST, SUMMARIZER_TITLE,
+    WEB_SEARCH,
+};
+use common::{fixture, MockServer};
+
+fn client(server: &MockServer, auth: Auth) -> Brave {
+    Brave::new(auth, &server.url()).strict(true)
+}
+
+#[test]
+fn requests_are_sent_with_the_token_of_their_plan() {
+    let server = MockServer::start();
+    server
+        .mock("/web/search", 200, &fixture("web_search_free.json"))
+        .mock("/web/search", 200, &fixture("web_search_pro_ai.json"))
+        .mock("/suggest/search", 200, &fixture("suggest_free.json"));
+    let auth = Auth::default()
+        .token(Subscription::DataForSearch, "search-token")
+        .token(Subscription::DataForAi, "ai-token")
+        .token(Subscription::Suggest, "suggest-token");
+    let client = client(&server, auth);
+
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+    let params = WebSearchParams::builder("rust").summary(true).build().unwrap();
+    client.search(&params, None).unwrap();
+    client.suggest(&SuggestSearchParams::new("rust"), None).unwrap();
+
+    let tokens = server
+        .requests()
+        .iter()
+        .map(|request| request.header("x-subscription-token").unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(tokens, ["search-token", "ai-token", "suggest-token"]);
+}
+
+#[test]
+fn web_searches_fall_back_to_data_for_ai() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_base_ai.json"));
+    let client = client(&server, Auth::default().token(Subscription::DataForAi, "ai-token"));
+
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+
+    let request = &server.requests_to("/web/search")[0];
+    assert_eq!(request.header("x-subscription-token"), Some("ai-token"));
+}
+
+#[test]
+fn features_without_a_plan_are_reported_before_any_request() {
+    let server = MockServer::start();
+    let client = client(&server, Auth::default().token(Subscription::DataForSearch, "token"));
+
+    let err = client.summarize(&WebSearchParams::new("rust"), None).unwrap_err();
+    assert!(matches!(err, Error::NoSubscription(Feature::Summarizer)), "{err}");
+    assert!(err.to_string().contains("data_for_ai"), "{err}");
+
+    let err = client.suggest(&SuggestSearchParams::new("rust"), None).unwrap_err();
+    assert!(matches!(err, Error::NoSubscription(Feature::Suggest)), "{err}");
+
+    assert!(server.requests().is_empty());
+    assert!(client.auth.grants(Feature::WebSearch));
+    assert!(!client.auth.grants(Feature::Summarizer));
+}
+
+#[test]
+fn spellcheck_requests_are_sent_with_the_spellcheck_token() {
+    let server = MockServer::start();
+    server.mock("/spellcheck/search", 200, r#"{"type": "spellcheck", "results": []}"#);
+    let auth = Auth::default()
+        .token(Subscription::DataForAi, "ai-token")
+        .token(Subscription::Spellcheck, "spellcheck-token");
+
+    brave::plan::spellcheck_plan(&client(&server, auth)).unwrap();
+
+    let request = &server.requests_to("/spellcheck/search")[0];
+    assert_eq!(request.header("x-subscription-token"), Some("spellcheck-token"));
+    assert_eq!(request.query("q"), Some("brave search"));
+}
+
+#[test]
+fn the_catch_all_token_covers_the_other_plans() {
+    let server = MockServer::start();
+    server.mock("/web/search", 200, &fixture("web_search_free.json")).mock(
+        "/suggest/search",
+        200,
+        &fixture("suggest_free.json"),
+    );
+    let auth = Auth::new("any-token").token(Subscription::Suggest, "suggest-token");
+    let client = client(&server, auth);
+
+    client.search(&WebSearchParams::new("rust"), None).unwrap();
+    client.suggest(&SuggestSearchParams::new("rust"), None).unwrap();
+
+    assert_eq!(server.requests()[0].header("x-subscription-token"), Some("any-token"));
+    assert_eq!(server.requests()[1].header("x-subscription-token"), Some("suggest-token"));
+}
+
+#[test]
+fn endpoints_map_to_their_feature() {
+    assert_eq!(Feature::of(WEB_SEARCH, &[("q", "rust")]), Some(Feature::WebSearch));
+    assert_eq!(Feature::of(WEB_SEARCH, &[("summary", "true")]), Some(Feature::Summarizer));
+    assert_eq!(Feature::of(SUMMARIZER_TITLE, &[]), Some(Feature::Summarizer));
+    assert_eq!(Feature::of(SUGGEST, &[]), Some(Feature::Suggest));
+    assert_eq!(Feature::of(SPELLCHECK, &[]), Some(Feature::Spellcheck));
+    assert_eq!(Feature::of("images/search", &[]), None);
+
+    for subscription in Subscription::ALL {
+        assert_eq!(subscription.to_string().parse::<Subscription>(), Ok(subscription));
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+/// Error type returned from this library's functions
+#[derive(Debug)]
+pub enum Error {
+    /// There's no recorded interaction for the request.
+    NotRecorded(String, PathBuf),
+    /// An Error occurred when reading or writing a cassette.
+    IoError(PathBuf, std::io::Error),
+    /// An Error occurred when serializing or deserializing a cassette.
+    SerdeError(PathBuf, serde_json::error::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Error::{IoError, NotRecorded, SerdeError};
+
+        match self {
+            NotRecorded(request, path) => {
+                write!(f, "no recorded interaction for {request} at {}", path.display())
+            }
+            IoError(path, err) => write!(f, "cassette error at {}: {err}", path.display()),
+            SerdeError(path, err) => write!(f, "invalid cassette at {}: {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
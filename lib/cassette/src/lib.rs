@@ -0,0 +1,229 @@
+//! Record and replay HTTP interactions.
+//!
+//! In record mode, API clients send their requests as usual and save every request/response pair
+//! in a cassette directory. In replay mode they don't reach the network at all, and answer each
+//! request with the response recorded for it, so the same queries can be run over and over
+//! without spending quota or tokens.
+//!
+//! Interactions are stored one per file, as JSON, and are looked up by method, url and body.
+//! Headers aren't recorded, so credentials never end up on disk.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+pub mod error;
+
+pub use error::Error;
+
+pub type Json = serde_json::Value;
+
+/// Environment variable that enables a cassette, set to `record` or `replay`.
+pub const MODE_ENV: &str = "CASSETTE_MODE";
+/// Environment variable with the directory where interactions are stored.
+pub const DIR_ENV: &str = "CASSETTE_DIR";
+/// Directory used when `CASSETTE_DIR` isn't set.
+pub const DEFAULT_DIR: &str = "cassettes";
+
+/// What a cassette does with the requests it sees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Send requests to the API and save every interaction.
+    Record,
+    /// Answer requests with the saved interactions, without reaching the network.
+    Replay,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            _ => Err(format!("invalid cassette mode `{s}`, expected `record` or `replay`")),
+        }
+    }
+}
+
+/// The parts of a request used to find its recorded response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub method: String,
+    /// Full url, including the query string.
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Json>,
+}
+
+impl Request {
+    #[must_use]
+    pub fn new(method: &str, url: &str, body: Option<Json>) -> Self {
+        Self { method: method.to_string(), url: url.to_string(), body }
+    }
+}
+
+impl std::fmt::Display for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.url)
+    }
+}
+
+/// A recorded response. Streams of server-sent events are stored as their raw body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    #[must_use]
+    pub fn new(status: u16, body: &str) -> Self {
+        Self { status, body: body.to_string() }
+    }
+
+    /// The standard reason phrase of the status code.
+    #[must_use]
+    pub fn status_text(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            422 => "Unprocessable Entity",
+            429 => "Too Many Requests",
+            500 => "Internal Server Error",
+            502 => "Bad Gateway",
+            503 => "Service Unavailable",
+            529 => "Overloaded",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    request: Request,
+    response: Response,
+}
+
+/// A directory of recorded interactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cassette {
+    pub mode: Mode,
+    pub dir: PathBuf,
+}
+
+impl Cassette {
+    #[must_use]
+    pub fn new(mode: Mode, dir: impl Into<PathBuf>) -> Self {
+        Self { mode, dir: dir.into() }
+    }
+
+    /// Creates a cassette from the `CASSETTE_MODE` and `CASSETTE_DIR` environment variables.
+    /// Returns `None` if `CASSETTE_MODE` isn't set, or holds an invalid mode.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let mode = std::env::var(MODE_ENV).ok()?;
+        let mode = match mode.parse() {
+            Ok(mode) => mode,
+            Err(err) => {
+                log::warn!("Ignoring {MODE_ENV}: {err}");
+                return None;
+            }
+        };
+        let dir = std::env::var(DIR_ENV).unwrap_or_else(|_| DEFAULT_DIR.to_string());
+
+        Some(Self::new(mode, dir))
+    }
+
+    #[must_use]
+    pub fn is_replay(&self) -> bool {
+        self.mode == Mode::Replay
+    }
+
+    #[must_use]
+    pub fn is_record(&self) -> bool {
+        self.mode == Mode::Record
+    }
+
+    /// The file where the interaction for the request is stored.
+    #[must_use]
+    pub fn path(&self, request: &Request) -> PathBuf {
+        self.dir.join(file_name(request))
+    }
+
+    /// Loads the response recorded for the request.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request wasn't recorded, or the cassette can't be read.
+    pub fn load(&self, request: &Request) -> Result<Response, Error> {
+        let path = self.path(request);
+        if !path.exists() {
+            return Err(Error::NotRecorded(request.to_string(), path));
+        }
+
+        let file = std::fs::read_to_string(&path).map_err(|e| Error::IoError(path.clone(), e))?;
+        let interaction: Interaction =
+            serde_json::from_str(&file).map_err(|e| Error::SerdeError(path.clone(), e))?;
+        log::debug!("Replaying {request} from {}", path.display());
+
+        Ok(interaction.response)
+    }
+
+    /// Saves the response to the request, replacing any previous recording.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the cassette can't be written.
+    pub fn save(&self, request: &Request, response: &Response) -> Result<(), Error> {
+        let path = self.path(request);
+        std::fs::create_dir_all(&self.dir).map_err(|e| Error::IoError(self.dir.clone(), e))?;
+
+        let interaction = Interaction { request: request.clone(), response: response.clone() };
+        let file = serde_json::to_string_pretty(&interaction)
+            .map_err(|e| Error::SerdeError(path.clone(), e))?;
+        std::fs::write(&path, file + "\n").map_err(|e| Error::IoError(path.clone(), e))?;
+        log::debug!("Recorded {request} to {}", path.display());
+
+        Ok(())
+    }
+}
+
+/// Builds a readable and stable file name out of the request path, and a hash of everything that
+/// identifies the request.
+fn file_name(request: &Request) -> String {
+    let path = request.url.split(['?', '#']).next().unwrap_or_default();
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    let slug = path
+        .split('/')
+        .skip(1)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>();
+
+    let body = request.body.as_ref().map(ToString::to_string).unwrap_or_default();
+    let hash = fnv1a([request.method.as_str(), request.url.as_str(), body.as_str()]);
+
+    format!("{}-{slug}-{hash:016x}.json", request.method.to_lowercase())
+}
+
+/// FNV-1a hash of the given parts, which unlike the std hashers is guaranteed to be stable across
+/// releases, so it can be used to name files.
+#[must_use]
+pub fn fnv1a<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for part in parts {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
@@ -0,0 +1,48 @@
+//! Checks that interactions are saved and found again by request.
+
+use cassette::{Cassette, Error, Mode, Request, Response};
+use serde_json::json;
+
+#[test]
+fn saved_interactions_are_loaded_by_request() {
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = Cassette::new(Mode::Record, dir.path());
+    let request =
+        Request::new("POST", "https://api.example.com/v1/messages", Some(json!({"a": 1})));
+
+    cassette.save(&request, &Response::new(200, r#"{"ok": true}"#)).unwrap();
+
+    let replay = Cassette::new(Mode::Replay, dir.path());
+    assert_eq!(replay.load(&request).unwrap(), Response::new(200, r#"{"ok": true}"#));
+}
+
+#[test]
+fn requests_are_told_apart_by_url_and_body() {
+    let cassette = Cassette::new(Mode::Replay, "cassettes");
+    let url = "https://api.example.com/v1/messages";
+
+    let path = cassette.path(&Request::new("POST", url, Some(json!({"a": 1}))));
+    assert!(path.starts_with("cassettes"));
+    assert!(path.file_name().unwrap().to_str().unwrap().starts_with("post-v1-messages-"));
+
+    assert_eq!(path, cassette.path(&Request::new("POST", url, Some(json!({"a": 1})))));
+    assert_ne!(path, cassette.path(&Request::new("POST", url, Some(json!({"a": 2})))));
+    assert_ne!(path, cassette.path(&Request::new("GET", url, None)));
+}
+
+#[test]
+fn missing_interactions_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let cassette = Cassette::new(Mode::Replay, dir.path());
+
+    let err = cassette.load(&Request::new("GET", "https://api.example.com/search?q=rust", None));
+
+    assert!(matches!(err, Err(Error::NotRecorded(ref request, _)) if request.contains("q=rust")));
+}
+
+#[test]
+fn modes_are_parsed_from_strings() {
+    assert_eq!("record".parse::<Mode>(), Ok(Mode::Record));
+    assert_eq!("Replay".parse::<Mode>(), Ok(Mode::Replay));
+    assert!("rewind".parse::<Mode>().is_err());
+}
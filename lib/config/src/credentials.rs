@@ -0,0 +1,412 @@
+//! Storage of the subscription tokens and API keys of each profile, out of the config file.
+//!
+//! Tokens are kept in the system keyring: the Secret Service on Linux, the Keychain on macOS and
+//! the Credential Manager on Windows. When no keyring is reachable, e.g. on a headless Linux
+//! server, they are kept in a file encrypted with a passphrase using [age], next to the config
+//! file. `BRAVE_CREDENTIAL_STORE` forces one or the other, with `keyring` or `file`.
+//!
+//! The passphrase of the file is read from `BRAVE_CREDENTIALS_PASSPHRASE`, or asked on the
+//! terminal.
+//!
+//! [age]: https://age-encryption.org
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use age::secrecy::SecretString;
+
+use crate::{Error, Tokens};
+
+/// Environment variable that selects the store, `keyring` or `file`.
+pub const STORE_ENV: &str = "BRAVE_CREDENTIAL_STORE";
+/// Environment variable with the path of the encrypted file.
+pub const FILE_ENV: &str = "BRAVE_CREDENTIALS_FILE";
+/// Environment variable with the passphrase of the encrypted file.
+pub const PASSPHRASE_ENV: &str = "BRAVE_CREDENTIALS_PASSPHRASE";
+
+/// Service the tokens are saved under in the keyring.
+const SERVICE: &str = "brave-opus";
+
+/// A token that can be stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Credential {
+    /// Brave Web Search Data for Search token.
+    DataForSearch,
+    /// Brave Web Search Data for AI token.
+    DataForAi,
+    /// Brave Suggest token.
+    Suggest,
+    /// Brave Spellcheck token.
+    Spellcheck,
+    /// Brave Autosuggest token.
+    Autosuggest,
+    /// Anthropic API key.
+    Anthropic,
+}
+
+impl Credential {
+    pub const ALL: [Self; 6] = [
+        Self::DataForSearch,
+        Self::DataForAi,
+        Self::Suggest,
+        Self::Spellcheck,
+        Self::Autosuggest,
+        Self::Anthropic,
+    ];
+
+    /// The name of the credential, as in the `tokens` of the profiles.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DataForSearch => "data_for_search",
+            Self::DataForAi => "data_for_ai",
+            Self::Suggest => "suggest",
+            Self::Spellcheck => "spellcheck",
+            Self::Autosuggest => "autosuggest",
+            Self::Anthropic => "anthropic",
+        }
+    }
+
+    /// The environment variable that takes precedence over the stored credential.
+    #[must_use]
+    pub fn env(self) -> &'static str {
+        match self {
+            Self::DataForSearch => "BRAVE_WEB_SEARCH_DATA_FOR_SEARCH_API_KEY",
+            Self::DataForAi => "BRAVE_WEB_SEARCH_DATA_FOR_AI_API_KEY",
+            Self::Suggest => "BRAVE_SUGGEST_API_KEY",
+            Self::Spellcheck => "BRAVE_SPELLCHECK_API_KEY",
+            Self::Autosuggest => "BRAVE_AUTOSUGGEST_API_KEY",
+            Self::Anthropic => "ANTHROPIC_API_KEY",
+        }
+    }
+}
+
+impl std::fmt::Display for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl std::str::FromStr for Credential {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|credential| credential.name() == s).ok_or_else(|| {
+            format!(
+                "invalid credential `{s}`, expected `data_for_search`, `data_for_ai`, `suggest`, \
+                 `spellcheck`, `autosuggest` or `anthropic`"
+            )
+        })
+    }
+}
+
+impl Tokens {
+    #[must_use]
+    pub fn get(&self, credential: Credential) -> Option<&str> {
+        match credential {
+            Credential::DataForSearch => self.data_for_search.as_deref(),
+            Credential::DataForAi => self.data_for_ai.as_deref(),
+            Credential::Suggest => self.suggest.as_deref(),
+            Credential::Spellcheck => self.spellcheck.as_deref(),
+            Credential::Autosuggest => self.autosuggest.as_deref(),
+            Credential::Anthropic => self.anthropic.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, credential: Credential, token: Option<String>) {
+        match credential {
+            Credential::DataForSearch => self.data_for_search = token,
+            Credential::DataForAi => self.data_for_ai = token,
+            Credential::Suggest => self.suggest = token,
+            Credential::Spellcheck => self.spellcheck = token,
+            Credential::Autosuggest => self.autosuggest = token,
+            Credential::Anthropic => self.anthropic = token,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        Credential::ALL.into_iter().all(|credential| self.get(credential).is_none())
+    }
+}
+
+/// Where the credentials are kept.
+#[derive(Debug)]
+pub enum Store {
+    Keyring,
+    File(FileStore),
+}
+
+impl Store {
+    /// The store selected by `BRAVE_CREDENTIAL_STORE`, or the keyring if it's reachable, or the
+    /// encrypted file.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `BRAVE_CREDENTIAL_STORE` has an invalid value, or if the file is
+    /// needed and there's no directory to keep it in.
+    pub fn open() -> Result<Self, Error> {
+        let store = match crate::env(STORE_ENV).as_deref() {
+            Some("keyring") => Self::Keyring,
+            Some("file") => Self::File(FileStore::open()?),
+            Some(store) => {
+                return Err(Error::CredentialError(format!(
+                    "invalid {STORE_ENV} `{store}`, expected `keyring` or `file`"
+                )))
+            }
+            None if keyring_available() => Self::Keyring,
+            None => Self::File(FileStore::open()?),
+        };
+
+        log::debug!("Keeping the credentials in {store}");
+        Ok(store)
+    }
+
+    /// The stored token of the profile.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the store can't be read, or the passphrase of the file is wrong.
+    pub fn get(&self, profile: &str, credential: Credential) -> Result<Option<String>, Error> {
+        match self {
+            Self::Keyring => match entry(profile, credential)?.get_password() {
+                Ok(token) => Ok(Some(token)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(err) => Err(Error::KeyringError(err)),
+            },
+            Self::File(file) => {
+                Ok(file.read()?.get(profile).and_then(|t| t.get(credential)).map(str::to_string))
+            }
+        }
+    }
+
+    /// Saves the token of the profile, replacing the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the store can't be written, or the passphrase of the file is wrong.
+    pub fn set(&self, profile: &str, credential: Credential, token: &str) -> Result<(), Error> {
+        match self {
+            Self::Keyring => {
+                entry(profile, credential)?.set_password(token).map_err(Error::KeyringError)
+            }
+            Self::File(file) => {
+                let mut profiles = file.read()?;
+                profiles
+                    .entry(profile.to_string())
+                    .or_default()
+                    .set(credential, Some(token.into()));
+                file.write(profiles)
+            }
+        }
+    }
+
+    /// Removes the token of the profile. Returns whether there was one.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the store can't be written, or the passphrase of the file is wrong.
+    pub fn delete(&self, profile: &str, credential: Credential) -> Result<bool, Error> {
+        match self {
+            Self::Keyring => match entry(profile, credential)?.delete_credential() {
+                Ok(()) => Ok(true),
+                Err(keyring::Error::NoEntry) => Ok(false),
+                Err(err) => Err(Error::KeyringError(err)),
+            },
+            Self::File(file) => {
+                let mut profiles = file.read()?;
+                let Some(tokens) = profiles.get_mut(profile) else {
+                    return Ok(false);
+                };
+                if tokens.get(credential).is_none() {
+                    return Ok(false);
+                }
+                tokens.set(credential, None);
+                if tokens.is_empty() {
+                    profiles.remove(profile);
+                }
+                file.write(profiles)?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keyring => f.write_str("the system keyring"),
+            Self::File(file) => write!(f, "{}", file.path.display()),
+        }
+    }
+}
+
+/// A file with the tokens of every profile, encrypted with a passphrase.
+///
+/// The file is decrypted once, and the passphrase is asked at most once.
+pub struct FileStore {
+    path: PathBuf,
+    passphrase: OnceCell<SecretString>,
+    profiles: RefCell<Option<BTreeMap<String, Tokens>>>,
+}
+
+impl std::fmt::Debug for FileStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileStore").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+impl FileStore {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), passphrase: OnceCell::new(), profiles: RefCell::new(None) }
+    }
+
+    /// The file at `BRAVE_CREDENTIALS_FILE`, or `credentials.age` next to the config file.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if neither `XDG_CONFIG_HOME` nor `HOME` are set.
+    pub fn open() -> Result<Self, Error> {
+        let path = crate::env(FILE_ENV)
+            .map(PathBuf::from)
+            .or_else(|| crate::config_dir().map(|dir| dir.join("brave").join("credentials.age")));
+        path.map(Self::new).ok_or_else(|| {
+            Error::CredentialError(format!("no directory for the credentials, set {FILE_ENV}"))
+        })
+    }
+
+    /// Uses this passphrase instead of asking for it.
+    #[must_use]
+    pub fn passphrase(self, passphrase: &str) -> Self {
+        let _ = self.passphrase.set(SecretString::from(passphrase));
+        self
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, Tokens>, Error> {
+        if let Some(profiles) = self.profiles.borrow().as_ref() {
+            return Ok(profiles.clone());
+        }
+
+        let ciphertext = match std::fs::read(&self.path) {
+            Ok(ciphertext) => ciphertext,
+            // Nothing was stored yet, so there's no need for the passphrase.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(err) => return Err(Error::IoError(self.path.clone(), err)),
+        };
+
+        let identity = age::scrypt::Identity::new(self.get_passphrase(false)?.clone());
+        let plaintext = age::decrypt(&identity, &ciphertext)
+            .map_err(|err| Error::DecryptError(self.path.clone(), err))?;
+        let profiles: BTreeMap<String, Tokens> = String::from_utf8(plaintext)
+            .map_err(|err| Error::CredentialError(err.to_string()))
+            .and_then(|plaintext| {
+                toml::from_str(&plaintext).map_err(|e| Error::ParseError(self.path.clone(), e))
+            })?;
+
+        self.profiles.replace(Some(profiles.clone()));
+        Ok(profiles)
+    }
+
+    fn write(&self, profiles: BTreeMap<String, Tokens>) -> Result<(), Error> {
+        let plaintext =
+            toml::to_string(&profiles).map_err(|err| Error::CredentialError(err.to_string()))?;
+        let creating = !self.path.exists();
+        let recipient = age::scrypt::Recipient::new(self.get_passphrase(creating)?.clone());
+        let ciphertext = age::encrypt(&recipient, plaintext.as_bytes())
+            .map_err(|err| Error::CredentialError(err.to_string()))?;
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| Error::IoError(dir.to_path_buf(), err))?;
+        }
+        write_private(&self.path, &ciphertext).map_err(|e| Error::IoError(self.path.clone(), e))?;
+
+        self.profiles.replace(Some(profiles));
+        Ok(())
+    }
+
+    /// The passphrase of the file, from `BRAVE_CREDENTIALS_PASSPHRASE` or the terminal. A new
+    /// passphrase is asked twice.
+    fn get_passphrase(&self, new: bool) -> Result<&SecretString, Error> {
+        if let Some(passphrase) = self.passphrase.get() {
+            return Ok(passphrase);
+        }
+
+        let passphrase = match crate::env(PASSPHRASE_ENV) {
+            Some(passphrase) => passphrase,
+            None => {
+                let path = self.path.display();
+                let prompt = |prompt: &str| {
+                    rpassword::prompt_password(prompt).map_err(|err| {
+                        Error::CredentialError(format!(
+                            "can't ask for the passphrase of {path}, set {PASSPHRASE_ENV}: {err}"
+                        ))
+                    })
+                };
+
+                let passphrase = prompt(&format!("Passphrase for {path}: "))?;
+                if new && prompt("Confirm the passphrase: ")? != passphrase {
+                    return Err(Error::CredentialError("the passphrases don't match".into()));
+                }
+                passphrase
+            }
+        };
+        if passphrase.is_empty() {
+            return Err(Error::CredentialError("the passphrase can't be empty".into()));
+        }
+
+        Ok(self.passphrase.get_or_init(|| SecretString::from(passphrase)))
+    }
+}
+
+/// Reads a token from the terminal without echoing it, or a line of stdin when it isn't a
+/// terminal, e.g. `pass show brave | bravecli auth login`.
+///
+/// # Errors
+///
+/// Will return `Err` if neither can be read.
+pub fn read_token(prompt: &str) -> Result<String, Error> {
+    let token = if std::io::stdin().is_terminal() {
+        rpassword::prompt_password(prompt)
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map(|_| line)
+    };
+
+    token
+        .map(|token| token.trim().to_string())
+        .map_err(|err| Error::CredentialError(format!("can't read the token: {err}")))
+}
+
+fn entry(profile: &str, credential: Credential) -> Result<keyring::Entry, Error> {
+    keyring::Entry::new(SERVICE, &format!("{profile}/{credential}")).map_err(Error::KeyringError)
+}
+
+/// Whether the keyring can be reached, e.g. there's a Secret Service running.
+fn keyring_available() -> bool {
+    match keyring::Entry::new(SERVICE, "probe").and_then(|entry| entry.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(err) => {
+            log::debug!("The keyring isn't available: {err}");
+            false
+        }
+    }
+}
+
+/// Writes the file so only its owner can read it.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(contents)
+}
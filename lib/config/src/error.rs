@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+/// Error type returned from this library's functions
+#[derive(Debug)]
+pub enum Error {
+    /// An Error occurred when reading the config file.
+    IoError(PathBuf, std::io::Error),
+    /// The config file isn't valid TOML, or has unknown settings.
+    ParseError(PathBuf, toml::de::Error),
+    /// The profile isn't defined in the config file.
+    UnknownProfile(String, PathBuf),
+    /// An Error returned by the system keyring.
+    KeyringError(keyring::Error),
+    /// The credentials file can't be decrypted, usually because of a wrong passphrase.
+    DecryptError(PathBuf, age::DecryptError),
+    /// An Error occurred when storing or reading credentials.
+    CredentialError(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Error::{
+            CredentialError, DecryptError, IoError, KeyringError, ParseError, UnknownProfile,
+        };
+
+        match self {
+            IoError(path, err) => write!(f, "can't read the config at {}: {err}", path.display()),
+            ParseError(path, err) => write!(f, "invalid config at {}: {err}", path.display()),
+            UnknownProfile(name, path) => {
+                write!(f, "no profile named `{name}` in {}", path.display())
+            }
+            KeyringError(err) => write!(f, "keyring error: {err}"),
+            DecryptError(path, err) => write!(f, "can't decrypt {}: {err}", path.display()),
+            CredentialError(s) => write!(f, "credentials error: {s}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,223 @@
+//! Named profiles of credentials and defaults for `bravecli` and `brave-opus`.
+//!
+//! The config file is TOML, read from `$XDG_CONFIG_HOME/brave/config.toml`, or
+//! `~/.config/brave/config.toml` when `XDG_CONFIG_HOME` isn't set. `BRAVE_CONFIG` points to
+//! another file. Each profile holds the tokens of the subscriptions it can use, and the defaults
+//! of the commands:
+//!
+//! ```toml
+//! default_profile = "work"
+//!
+//! [profiles.work]
+//! country = "us"
+//! search_lang = "en"
+//! safesearch = "moderate"
+//! format = "table"
+//! model = "claude-3-opus-20240229"
+//!
+//! [profiles.work.tokens]
+//! data_for_ai = "BSA..."
+//! suggest = "BSA..."
+//! anthropic = "sk-ant-..."
+//!
+//! [profiles.work.rerank]
+//! top_k = 5
+//! domains = { "docs.rs" = 2.0, "pinterest.com" = 0.0 }
+//! ```
+//!
+//! A profile only fills in what isn't given otherwise: command line flags take precedence over
+//! environment variables, which take precedence over the profile, which takes precedence over
+//! the built-in defaults.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+pub mod credentials;
+pub mod error;
+
+pub use error::Error;
+
+/// Environment variable with the path of the config file.
+pub const PATH_ENV: &str = "BRAVE_CONFIG";
+/// Environment variable with the name of the profile to use.
+pub const PROFILE_ENV: &str = "BRAVE_PROFILE";
+/// Profile used when none is selected and the config has no `default_profile`.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The contents of a config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profile used when none is selected.
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Where the config was read from.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Credentials and defaults selected together, e.g. for work and personal accounts.
+///
+/// Values are kept as written, and are validated by the commands that use them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub tokens: Tokens,
+    /// Country the results come from, e.g. `us`.
+    pub country: Option<String>,
+    /// Language of the results, e.g. `en`.
+    pub search_lang: Option<String>,
+    /// Adult content filter: `off`, `moderate` or `strict`.
+    pub safesearch: Option<String>,
+    /// Output format of the commands that support it, e.g. `json` or `table`.
+    pub format: Option<String>,
+    /// Version of the Brave Web Search API, in the `YYYY-MM-DD` format.
+    pub web_search_version: Option<String>,
+    /// Version of the Brave Suggest API, in the `YYYY-MM-DD` format.
+    pub suggest_version: Option<String>,
+    /// Version of the Anthropic API, e.g. `2023-06-01`.
+    pub anthropic_version: Option<String>,
+    /// Anthropic model to run, e.g. `claude-3-opus-20240229`.
+    pub model: Option<String>,
+    #[serde(default)]
+    pub rerank: Rerank,
+}
+
+/// How `brave-opus` re-ranks the search results before fetching their pages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rerank {
+    /// Number of results whose pages are fetched.
+    pub top_k: Option<usize>,
+    /// Weight of the results of each domain, or its subdomains, which multiplies their score:
+    /// above `1` boosts them, below `1` penalizes them, and `0` drops them.
+    #[serde(default)]
+    pub domains: BTreeMap<String, f64>,
+}
+
+/// The token of each subscription.
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tokens {
+    /// Brave Web Search Data for Search token.
+    pub data_for_search: Option<String>,
+    /// Brave Web Search Data for AI token.
+    pub data_for_ai: Option<String>,
+    /// Brave Suggest token.
+    pub suggest: Option<String>,
+    /// Brave Spellcheck token.
+    pub spellcheck: Option<String>,
+    /// Brave Autosuggest token.
+    pub autosuggest: Option<String>,
+    /// Anthropic API key.
+    pub anthropic: Option<String>,
+}
+
+impl std::fmt::Debug for Tokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redact = |token: &Option<String>| token.as_ref().map(|_| "<redacted>");
+        f.debug_struct("Tokens")
+            .field("data_for_search", &redact(&self.data_for_search))
+            .field("data_for_ai", &redact(&self.data_for_ai))
+            .field("suggest", &redact(&self.suggest))
+            .field("spellcheck", &redact(&self.spellcheck))
+            .field("autosuggest", &redact(&self.autosuggest))
+            .field("anthropic", &redact(&self.anthropic))
+            .finish()
+    }
+}
+
+impl Config {
+    /// The path of the config file: `BRAVE_CONFIG`, or `brave/config.toml` in the XDG config
+    /// directory. Returns `None` if neither `XDG_CONFIG_HOME` nor `HOME` are set.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env(PATH_ENV) {
+            return Some(PathBuf::from(path));
+        }
+
+        Some(config_dir()?.join("brave").join("config.toml"))
+    }
+
+    /// Reads the config file at `path`, or at the default path when `None`.
+    ///
+    /// A missing file at the default path is an empty config, so everything works without one.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file can't be read, or isn't a valid config, or if `path` is
+    /// given and doesn't exist.
+    pub fn load(path: Option<&Path>) -> Result<Self, Error> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, env(PATH_ENV).is_some()),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound && !required => {
+                log::debug!("No config at {}", path.display());
+                return Ok(Self { path, ..Self::default() });
+            }
+            Err(err) => return Err(Error::IoError(path, err)),
+        };
+
+        log::debug!("Reading the config at {}", path.display());
+        let config = Self::parse(&contents).map_err(|err| Error::ParseError(path.clone(), err))?;
+        Ok(Self { path, ..config })
+    }
+
+    /// Parses the contents of a config file.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the contents aren't valid TOML, or have unknown settings.
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// The profile named `name`, or the default one when `None`.
+    ///
+    /// Without a name, the profile is `default_profile`, or the one named `default`. If neither
+    /// exist the profile is empty, so the commands fall back to their built-in defaults.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the profile is named, by `name` or `default_profile`, and doesn't
+    /// exist.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, Error> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(self.profiles.get(DEFAULT_PROFILE).cloned().unwrap_or_default());
+        };
+
+        self.profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownProfile(name.to_string(), self.path.clone()))
+    }
+
+    /// The name of the profile selected by `name`, as in [`Config::profile`]. Credentials are
+    /// stored under this name.
+    #[must_use]
+    pub fn profile_name<'a>(&'a self, name: Option<&'a str>) -> &'a str {
+        name.or(self.default_profile.as_deref()).unwrap_or(DEFAULT_PROFILE)
+    }
+}
+
+/// The XDG config directory, `XDG_CONFIG_HOME` or `~/.config`.
+fn config_dir() -> Option<PathBuf> {
+    env("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
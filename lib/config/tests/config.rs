@@ -0,0 +1,91 @@
+//! Checks that config files are read and profiles are selected.
+
+use config::{Config, Error, Profile};
+
+const CONFIG: &str = r#"
+default_profile = "work"
+
+[profiles.work]
+country = "us"
+format = "table"
+model = "claude-3-opus-20240229"
+
+[profiles.work.tokens]
+data_for_ai = "work-ai"
+suggest = "work-suggest"
+spellcheck = "work-spellcheck"
+
+[profiles.work.rerank]
+top_k = 3
+domains = { "docs.rs" = 2.0, "pinterest.com" = 0 }
+
+[profiles.home]
+search_lang = "es"
+"#;
+
+#[test]
+fn profiles_are_read_from_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, CONFIG).unwrap();
+
+    let config = Config::load(Some(&path)).unwrap();
+
+    assert_eq!(config.path, path);
+    assert_eq!(config.profiles.len(), 2);
+    let work = &config.profiles["work"];
+    assert_eq!(work.country.as_deref(), Some("us"));
+    assert_eq!(work.tokens.data_for_ai.as_deref(), Some("work-ai"));
+    assert_eq!(work.tokens.spellcheck.as_deref(), Some("work-spellcheck"));
+    assert_eq!(work.tokens.anthropic, None);
+    assert_eq!(work.rerank.top_k, Some(3));
+    assert_eq!(work.rerank.domains["docs.rs"], 2.0);
+    assert_eq!(config.profiles["home"].rerank, config::Rerank::default());
+}
+
+#[test]
+fn the_default_profile_is_used_without_a_name() {
+    let config = Config::parse(CONFIG).unwrap();
+
+    assert_eq!(config.profile(None).unwrap().format.as_deref(), Some("table"));
+    assert_eq!(config.profile(Some("home")).unwrap().search_lang.as_deref(), Some("es"));
+
+    let config = Config::parse("[profiles.default]\ncountry = \"ar\"").unwrap();
+    assert_eq!(config.profile(None).unwrap().country.as_deref(), Some("ar"));
+
+    assert_eq!(Config::default().profile(None).unwrap(), Profile::default());
+}
+
+#[test]
+fn unknown_profiles_are_reported() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert!(
+        matches!(config.profile(Some("play")), Err(Error::UnknownProfile(ref name, _)) if name == "play")
+    );
+
+    let config = Config::parse("default_profile = \"play\"").unwrap();
+    assert!(matches!(config.profile(None), Err(Error::UnknownProfile(..))));
+}
+
+#[test]
+fn invalid_files_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = Config::load(Some(&dir.path().join("missing.toml")));
+    assert!(matches!(err, Err(Error::IoError(..))), "named files must exist");
+
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[profiles.work]\ncontry = \"us\"").unwrap();
+    let err = Config::load(Some(&path)).unwrap_err();
+    assert!(matches!(err, Error::ParseError(..)));
+    assert!(err.to_string().contains("contry"), "{err}");
+}
+
+#[test]
+fn tokens_are_not_printed() {
+    let config = Config::parse(CONFIG).unwrap();
+
+    let debug = format!("{config:?}");
+    assert!(!debug.contains("work-ai"), "{debug}");
+    assert!(debug.contains("<redacted>"));
+}
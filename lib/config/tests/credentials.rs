@@ -0,0 +1,70 @@
+//! Checks that tokens are kept in the encrypted file, and only readable with its passphrase.
+
+use config::{
+    credentials::{Credential, FileStore, Store},
+    Error,
+};
+
+#[test]
+fn tokens_are_stored_per_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brave").join("credentials.age");
+    let store = Store::File(FileStore::new(&path).passphrase("correct horse"));
+
+    store.set("work", Credential::DataForAi, "work-ai").unwrap();
+    store.set("work", Credential::Suggest, "work-suggest").unwrap();
+    store.set("home", Credential::DataForAi, "home-ai").unwrap();
+
+    let contents = std::fs::read(&path).unwrap();
+    assert!(contents.starts_with(b"age-encryption.org/v1"));
+    assert!(!String::from_utf8_lossy(&contents).contains("work-ai"));
+
+    let store = Store::File(FileStore::new(&path).passphrase("correct horse"));
+    assert_eq!(store.get("work", Credential::DataForAi).unwrap().as_deref(), Some("work-ai"));
+    assert_eq!(store.get("work", Credential::Suggest).unwrap().as_deref(), Some("work-suggest"));
+    assert_eq!(store.get("home", Credential::DataForAi).unwrap().as_deref(), Some("home-ai"));
+    assert_eq!(store.get("home", Credential::Anthropic).unwrap(), None);
+    assert_eq!(store.get("play", Credential::DataForAi).unwrap(), None);
+}
+
+#[test]
+fn tokens_are_deleted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("credentials.age");
+    let store = Store::File(FileStore::new(&path).passphrase("correct horse"));
+    store.set("work", Credential::Anthropic, "sk-ant").unwrap();
+
+    assert!(store.delete("work", Credential::Anthropic).unwrap());
+    assert!(!store.delete("work", Credential::Anthropic).unwrap());
+    assert!(!store.delete("home", Credential::Suggest).unwrap());
+    assert_eq!(store.get("work", Credential::Anthropic).unwrap(), None);
+}
+
+#[test]
+fn a_wrong_passphrase_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("credentials.age");
+    Store::File(FileStore::new(&path).passphrase("correct horse"))
+        .set("work", Credential::Suggest, "work-suggest")
+        .unwrap();
+
+    let store = Store::File(FileStore::new(&path).passphrase("battery staple"));
+    assert!(matches!(store.get("work", Credential::Suggest), Err(Error::DecryptError(..))));
+}
+
+#[test]
+fn missing_files_have_no_tokens() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // No passphrase is needed until something is stored.
+    let store = Store::File(FileStore::new(dir.path().join("credentials.age")));
+    assert_eq!(store.get("work", Credential::DataForAi).unwrap(), None);
+}
+
+#[test]
+fn credentials_are_named_as_the_profile_tokens() {
+    for credential in Credential::ALL {
+        assert_eq!(credential.name().parse::<Credential>(), Ok(credential));
+    }
+    assert!("brave".parse::<Credential>().is_err());
+}
@@ -0,0 +1,29 @@
+/// Error type returned from this library's functions
+#[derive(Debug)]
+pub enum Error {
+    /// The proxy URL can't be parsed.
+    InvalidProxy(String),
+    /// A root certificate isn't valid PEM, or isn't a valid certificate.
+    InvalidCertificate(String),
+    /// The TLS settings can't be used with the crypto provider.
+    TlsError(String),
+    /// A custom agent was given along with other HTTP settings.
+    AgentWithSettings,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Error::{AgentWithSettings, InvalidCertificate, InvalidProxy, TlsError};
+
+        match self {
+            InvalidProxy(err) => write!(f, "invalid proxy: {err}"),
+            InvalidCertificate(err) => write!(f, "invalid root certificate: {err}"),
+            TlsError(err) => write!(f, "{err}"),
+            AgentWithSettings => {
+                write!(f, "a custom agent can't be combined with other HTTP settings")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,145 @@
+//! Checks the HTTP settings of the agents built by `ClientBuilder`.
+
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+
+use http_agent::{Client, ClientBuilder, Error};
+use mock_server::MockServer;
+use ureq::{Agent, AgentBuilder};
+
+/// A client that only keeps what it was built with.
+struct Probe {
+    auth: String,
+    api_url: String,
+    agent: Agent,
+}
+
+impl Client for Probe {
+    type Auth = String;
+    type Error = Error;
+
+    fn with_agent(auth: String, api_url: &str, agent: Agent) -> Self {
+        Self { auth, api_url: api_url.to_string(), agent }
+    }
+}
+
+impl Probe {
+    fn ping(&self) -> Result<ureq::Response, String> {
+        let request = self.agent.get(&format!("{}/ping", self.api_url));
+        request.set("x-api-key", &self.auth).call().map_err(|err| err.to_string())
+    }
+}
+
+fn builder(api_url: &str) -> ClientBuilder<Probe> {
+    ClientBuilder::new("secret".to_string(), api_url)
+}
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"))
+}
+
+/// Starts a server that reads a single request and never answers it.
+fn silent_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind silent server");
+    let url = format!("http://{}", listener.local_addr().expect("tcp listener"));
+    std::thread::spawn(move || {
+        if let Ok((stream, _)) = listener.accept() {
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            std::thread::sleep(Duration::from_secs(5));
+        }
+    });
+    url
+}
+
+#[test]
+fn the_client_gets_the_auth_and_url() {
+    let server = MockServer::start();
+    server.mock("/ping", 200, "{}");
+
+    let client = builder(&server.url()).build().unwrap();
+    client.ping().unwrap();
+
+    assert_eq!(client.api_url, server.url());
+    assert_eq!(server.requests()[0].header("x-api-key"), Some("secret"));
+}
+
+#[test]
+fn the_user_agent_is_sent() {
+    let server = MockServer::start();
+    server.mock("/ping", 200, "{}");
+
+    builder(&server.url()).user_agent("acme/1.0").build().unwrap().ping().unwrap();
+
+    assert_eq!(server.requests()[0].header("user-agent"), Some("acme/1.0"));
+}
+
+#[test]
+fn slow_responses_time_out() {
+    let client = builder(&silent_server()).timeout_read(Duration::from_millis(200)).build();
+
+    let start = Instant::now();
+    let err = client.unwrap().ping().unwrap_err();
+
+    assert!(err.contains("timed out"), "{err}");
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn requests_go_through_the_proxy() {
+    let proxy = MockServer::start();
+    proxy.mock("http://api.invalid/ping", 200, "{}");
+
+    builder("http://api.invalid").proxy(&proxy.url()).build().unwrap().ping().unwrap();
+
+    assert_eq!(proxy.requests()[0].header("x-api-key"), Some("secret"));
+}
+
+#[test]
+fn invalid_proxies_are_rejected() {
+    let result = builder("http://api.invalid").proxy("ftp://proxy.invalid:21").build();
+
+    assert!(matches!(result, Err(Error::InvalidProxy(_))));
+}
+
+#[test]
+fn root_certificates_are_loaded() {
+    let client = builder("https://api.invalid").root_certificates(fixture("root_ca.pem")).build();
+
+    assert!(client.is_ok());
+}
+
+#[test]
+fn invalid_root_certificates_are_rejected() {
+    for pem in ["", "not a certificate", "-----BEGIN CERTIFICATE-----\nnot base64\n"] {
+        let result = builder("https://api.invalid").root_certificates(pem).build();
+
+        assert!(matches!(result, Err(Error::InvalidCertificate(_))), "{pem:?} was accepted");
+    }
+}
+
+#[test]
+fn a_custom_agent_is_used() {
+    let server = MockServer::start();
+    server.mock("/ping", 200, "{}");
+
+    let agent = AgentBuilder::new().user_agent("custom-agent").build();
+    builder(&server.url()).agent(agent).build().unwrap().ping().unwrap();
+
+    assert_eq!(server.requests()[0].header("user-agent"), Some("custom-agent"));
+}
+
+#[test]
+fn a_custom_agent_cant_be_combined_with_other_settings() {
+    let result = builder("http://api.invalid")
+        .agent(AgentBuilder::new().build())
+        .timeout(Duration::from_secs(1))
+        .build();
+
+    assert!(matches!(result, Err(Error::AgentWithSettings)));
+}
@@ -0,0 +1,228 @@
+//! A local HTTP server that answers API requests with canned responses, shared by the tests of
+//! the API clients.
+//!
+//! Responses are queued per path: each request takes the next response in the queue, and the
+//! last one keeps being served once the queue runs out. Unknown paths get a `404`, with the body
+//! given by [`MockServer::not_found`].
+//!
+//! Event streams are served as they are scripted, so a script that stops in the middle of an
+//! event behaves like a connection dropped by the server.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+pub type Json = serde_json::Value;
+
+/// A request received by the mock server.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The path of the url, without the query string.
+    pub path: String,
+    /// The decoded query string pairs, in order.
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    #[must_use]
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// The JSON body of the request.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the body isn't JSON.
+    #[must_use]
+    pub fn json(&self) -> Json {
+        serde_json::from_str(&self.body).expect("requests have a JSON body")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockResponse {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+type Routes = BTreeMap<String, VecDeque<MockResponse>>;
+
+/// Builds the body of the `404` answered to unknown paths.
+pub type NotFound = fn(&str) -> String;
+
+/// A local HTTP server with canned responses, see the module docs.
+pub struct MockServer {
+    server: Arc<tiny_http::Server>,
+    base_path: String,
+    routes: Arc<Mutex<Routes>>,
+    not_found: Arc<Mutex<NotFound>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a server whose `url` is `http://127.0.0.1:<port>`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no local port can be bound.
+    #[must_use]
+    pub fn start() -> Self {
+        Self::with_base_path("")
+    }
+
+    /// Starts a server whose `url` ends with the base path, e.g. `/v1/`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no local port can be bound.
+    #[must_use]
+    pub fn with_base_path(base_path: &str) -> Self {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("bind mock server"));
+        let routes = Arc::new(Mutex::new(Routes::new()));
+        let not_found: Arc<Mutex<NotFound>> =
+            Arc::new(Mutex::new(|path| format!(r#"{{"error": "no mock for {path}"}}"#)));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let handle = {
+            let (server, routes, not_found, requests) =
+                (server.clone(), routes.clone(), not_found.clone(), requests.clone());
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let recorded = record(&mut request);
+                    let response =
+                        next_response(&routes, *not_found.lock().unwrap(), &recorded.path);
+                    requests.lock().unwrap().push(recorded);
+
+                    let header =
+                        tiny_http::Header::from_bytes("Content-Type", response.content_type)
+                            .expect("valid header");
+                    let _ = request.respond(
+                        tiny_http::Response::from_string(response.body)
+                            .with_status_code(response.status)
+                            .with_header(header),
+                    );
+                }
+            })
+        };
+
+        Self {
+            server,
+            base_path: base_path.to_string(),
+            routes,
+            not_found,
+            requests,
+            handle: Some(handle),
+        }
+    }
+
+    /// The base url of the mock API.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the server doesn't listen on TCP.
+    #[must_use]
+    pub fn url(&self) -> String {
+        let addr = self.server.server_addr().to_ip().expect("tcp listener");
+        format!("http://{addr}{}", self.base_path)
+    }
+
+    /// Sets the body of the `404` answered to unknown paths, in the error format of the API.
+    pub fn not_found(&self, body: NotFound) -> &Self {
+        *self.not_found.lock().unwrap() = body;
+        self
+    }
+
+    /// Queues a JSON response for the given path, e.g. `/web/search`.
+    pub fn mock(&self, path: &str, status: u16, body: &str) -> &Self {
+        self.push(
+            path,
+            MockResponse { status, content_type: "application/json", body: body.into() },
+        )
+    }
+
+    /// Queues a scripted stream of server-sent events for the given path.
+    pub fn mock_sse(&self, path: &str, script: &str) -> &Self {
+        self.push(
+            path,
+            MockResponse { status: 200, content_type: "text/event-stream", body: script.into() },
+        )
+    }
+
+    /// The requests received so far, in order.
+    #[must_use]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The requests received so far for the given path, in order.
+    #[must_use]
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests().into_iter().filter(|r| r.path == path).collect()
+    }
+
+    fn push(&self, path: &str, response: MockResponse) -> &Self {
+        self.routes.lock().unwrap().entry(path.to_string()).or_default().push_back(response);
+        self
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn next_response(routes: &Mutex<Routes>, not_found: NotFound, path: &str) -> MockResponse {
+    let mut routes = routes.lock().unwrap();
+    match routes.get_mut(path) {
+        Some(queue) if queue.len() > 1 => queue.pop_front().expect("non empty queue"),
+        Some(queue) if !queue.is_empty() => queue[0].clone(),
+        _ => MockResponse { status: 404, content_type: "application/json", body: not_found(path) },
+    }
+}
+
+fn record(request: &mut tiny_http::Request) -> RecordedRequest {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(k), decode(v))
+        })
+        .collect();
+    let headers = request
+        .headers()
+        .iter()
+        .map(|h| (h.field.as_str().as_str().to_string(), h.value.as_str().to_string()))
+        .collect();
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    RecordedRequest {
+        method: request.method().to_string(),
+        path: path.to_string(),
+        query,
+        headers,
+        body,
+    }
+}
+
+fn decode(s: &str) -> String {
+    percent_encoding::percent_decode_str(&s.replace('+', " ")).decode_utf8_lossy().into_owned()
+}
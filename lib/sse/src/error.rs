@@ -0,0 +1,28 @@
+/// Error type returned from this library's functions
+#[derive(Debug)]
+pub enum Error {
+    /// The response body can't be read.
+    Io(std::io::Error),
+    /// The response body ended in the middle of a line or an event.
+    UnexpectedEof,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Error::{Io, UnexpectedEof};
+
+        match self {
+            Io(err) => write!(f, "io error: {err}"),
+            UnexpectedEof => write!(f, "unexpected eof"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::UnexpectedEof => None,
+        }
+    }
+}
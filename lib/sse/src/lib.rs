@@ -0,0 +1,143 @@
+//! Parses the server-sent events of an HTTP response body.
+//!
+//! `Events` reads the body lazily, one event at a time, so the API clients can hand out each event
+//! as soon as it arrives. A body that ends in the middle of a line or an event was cut short, and
+//! ends the iteration with `Error::UnexpectedEof` instead of a partial event.
+
+use std::io::{BufRead, BufReader, Read};
+
+pub mod error;
+
+pub use error::Error;
+
+/// An item of an event stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sse {
+    Event(Event),
+    /// A comment line, without the leading `:`. Servers send them to keep the connection alive.
+    Comment(String),
+}
+
+/// A single server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The event type, `message` unless the server sent one.
+    pub event_type: String,
+    /// The event payload. Multiple `data` lines are joined with a new line.
+    pub data: String,
+    pub id: Option<String>,
+    /// The reconnection time sent by the server, in milliseconds.
+    pub retry: Option<u64>,
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self { event_type: "message".to_string(), data: String::new(), id: None, retry: None }
+    }
+}
+
+/// Lazy iterator over the server-sent events read from a response body.
+pub struct Events<R: Read> {
+    reader: BufReader<R>,
+    done: bool,
+    /// The raw body read so far, when recording.
+    recording: Option<String>,
+}
+
+impl<R: Read> std::fmt::Debug for Events<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Events").field("done", &self.done).finish_non_exhaustive()
+    }
+}
+
+impl<R: Read> Events<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader: BufReader::new(reader), done: false, recording: None }
+    }
+
+    /// Keeps a copy of the raw body as it's read, e.g. to save it in a cassette.
+    #[must_use]
+    pub fn record(mut self) -> Self {
+        self.recording = Some(String::new());
+        self
+    }
+
+    /// The raw body read so far, if recording.
+    #[must_use]
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_deref()
+    }
+}
+
+impl<R: Read> Iterator for Events<R> {
+    type Item = Result<Sse, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut event = Event::default();
+        let mut has_data = false;
+        let mut pending = false;
+        let mut line = String::new();
+
+        while !self.done {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(Error::Io(err)));
+                }
+            }
+            if let Some(recording) = self.recording.as_mut() {
+                recording.push_str(&line);
+            }
+
+            if !line.ends_with('\n') {
+                // The connection was closed in the middle of a line.
+                self.done = true;
+                return Some(Err(Error::UnexpectedEof));
+            }
+
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                if has_data {
+                    return Some(Ok(Sse::Event(event)));
+                }
+                // Events without data are dropped, along with their fields.
+                event = Event::default();
+                pending = false;
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix(':') {
+                // Comments in the middle of an event are dropped, to keep the event whole.
+                if !pending {
+                    return Some(Ok(Sse::Comment(comment.to_string())));
+                }
+                continue;
+            }
+
+            pending = true;
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => event.event_type = value.to_string(),
+                "data" => {
+                    if has_data {
+                        event.data.push('\n');
+                    }
+                    event.data.push_str(value);
+                    has_data = true;
+                }
+                "id" => event.id = Some(value.to_string()),
+                "retry" => event.retry = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        // The body ended before the blank line closing the event.
+        pending.then_some(Err(Error::UnexpectedEof))
+    }
+}
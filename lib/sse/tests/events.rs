@@ -0,0 +1,76 @@
+//! Checks the parsing of event streams, including the ones cut short.
+
+use sse::{Error, Event, Events, Sse};
+
+fn parse(body: &str) -> Vec<Result<Sse, Error>> {
+    Events::new(body.as_bytes()).collect()
+}
+
+fn event(event_type: &str, data: &str) -> Sse {
+    Sse::Event(Event {
+        event_type: event_type.to_string(),
+        data: data.to_string(),
+        ..Event::default()
+    })
+}
+
+#[test]
+fn events_and_comments_are_read_in_order() {
+    let body = ": keep-alive\n\nevent: ping\ndata: {}\n\ndata: first\ndata: second\r\nid: 7\nretry: 500\n\n";
+
+    let events = parse(body).into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(
+        events,
+        [
+            Sse::Comment(" keep-alive".to_string()),
+            event("ping", "{}"),
+            Sse::Event(Event {
+                event_type: "message".to_string(),
+                data: "first\nsecond".to_string(),
+                id: Some("7".to_string()),
+                retry: Some(500),
+            }),
+        ]
+    );
+}
+
+#[test]
+fn blank_lines_and_events_without_data_are_skipped() {
+    let body = "\n\nevent: empty\n\ndata:no space\n\n";
+
+    let events = parse(body).into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(events, [event("message", "no space")]);
+}
+
+#[test]
+fn comments_inside_an_event_keep_it_whole() {
+    let events = parse("event: delta\n: keep-alive\ndata: text\n\n");
+
+    assert!(matches!(&events[..], [Ok(sse)] if *sse == event("delta", "text")));
+}
+
+#[test]
+fn bodies_cut_in_the_middle_of_an_event_fail() {
+    let events = parse("data: whole\n\nevent: delta\ndata: half\n");
+
+    assert!(matches!(&events[..], [Ok(_), Err(Error::UnexpectedEof)]), "{events:?}");
+}
+
+#[test]
+fn bodies_cut_in_the_middle_of_a_line_fail() {
+    let events = parse("data: whole\n\ndata: {\"text\": \"ha");
+
+    assert!(matches!(&events[..], [Ok(_), Err(Error::UnexpectedEof)]), "{events:?}");
+}
+
+#[test]
+fn the_raw_body_can_be_recorded() {
+    let body = "data: one\n\n: comment\n\ndata: two\n\n";
+    let mut events = Events::new(body.as_bytes()).record();
+
+    assert_eq!(events.by_ref().count(), 3);
+    assert_eq!(events.recording(), Some(body));
+    assert_eq!(Events::new(body.as_bytes()).recording(), None);
+}
@@ -1,4 +1,4 @@
-#![allow(clippy::cognitive_complexity)]
+#![allow(clippy::cognitive_complexity, clippy::single_match)]
 use crate::cli;
 use crate::utils;
 use bunt::println;